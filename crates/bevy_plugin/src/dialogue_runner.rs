@@ -1,6 +1,6 @@
 pub use self::events::{
-    DialogueCompleted, DialogueStarted, ExecuteCommand, LineHints, NodeCompleted, NodeStarted,
-    PresentLine, PresentOptions,
+    DialogueCompleted, DialogueRolledBack, DialogueStarted, ExecuteCommand, LineHints,
+    NodeCompleted, NodeStarted, PresentLine, PresentOptions, VariableChanged,
 };
 pub use self::{
     builder::DialogueRunnerBuilder,
@@ -164,6 +164,26 @@ impl DialogueRunner {
         self
     }
 
+    /// Rewinds the dialogue `steps` checkpoints back, e.g. to let the player go back to the previous choice.
+    /// Rollback must be enabled with [`DialogueRunnerBuilder::with_rollback`]. Restarts the dialogue if it was completed in the meantime.
+    ///
+    /// In the next update, a [`DialogueRolledBack`] event is sent, followed by the [`PresentOptions`] or [`PresentLine`] event
+    /// that was sent at that checkpoint. See [`Dialogue::rollback`] for details.
+    pub fn rollback(&mut self, steps: usize) -> Result<&mut Self> {
+        let events = self.inner_mut().0.rollback(steps).map_err(Error::from)?;
+        self.is_running = true;
+        self.last_selected_option = None;
+        self.will_continue_in_next_update = false;
+        self.unsent_events.extend(events);
+        Ok(self)
+    }
+
+    /// Returns the number of checkpoints [`DialogueRunner::rollback`] can currently go back.
+    #[must_use]
+    pub fn available_rollbacks(&self) -> usize {
+        self.inner().0.available_rollbacks()
+    }
+
     /// Starts the dialogue at the given node.
     /// This method must be called after creation or after calling [`DialogueRunner::stop`] before the dialogue can be advanced. Implies [`DialogueRunner::continue_in_next_update`].
     /// If the dialogue was already running, this method will panic.
@@ -229,6 +249,24 @@ impl DialogueRunner {
         self.inner_mut().0.variable_storage_mut()
     }
 
+    /// Returns the IDs of all lines and options delivered so far. Save them apart from the variables to share them between save slots.
+    /// See [`Dialogue::seen_lines`] for details.
+    #[must_use]
+    pub fn seen_lines(&self) -> &SeenLines {
+        self.inner().0.seen_lines()
+    }
+
+    /// Mutably returns the IDs of all lines and options delivered so far, e.g. to mark lines as seen or unseen by hand.
+    #[must_use]
+    pub fn seen_lines_mut(&mut self) -> &mut SeenLines {
+        self.inner_mut().0.seen_lines_mut()
+    }
+
+    /// Replaces the seen lines, e.g. with ones restored from disk. Returns the previous ones.
+    pub fn set_seen_lines(&mut self, seen_lines: SeenLines) -> SeenLines {
+        self.inner_mut().0.set_seen_lines(seen_lines)
+    }
+
     /// Returns whether both the text and asset providers have loaded all their lines.
     #[must_use]
     pub fn update_line_availability(
@@ -396,4 +434,10 @@ impl DialogueRunner {
         self.command_tasks.retain(|task| !task.is_finished());
         self.command_tasks.is_empty()
     }
+
+    /// Takes the changes recorded if [`DialogueRunnerBuilder::with_variable_change_events`] was used.
+    #[must_use]
+    pub(crate) fn take_variable_changes(&self) -> Vec<VariableChange> {
+        self.variable_storage().take_changes()
+    }
 }
@@ -16,11 +16,16 @@ pub struct DialogueRunnerBuilder {
     variable_storage: Box<dyn VariableStorage>,
     text_provider: SharedTextProvider,
     asset_providers: HashMap<TypeId, Box<dyn AssetProvider>>,
+    marker_processors: Vec<(String, Box<dyn AttributeMarkerProcessor>)>,
     library: YarnLibrary,
     commands: YarnCommands,
     compilation: Compilation,
     localizations: Option<Localizations>,
     asset_server: SkipDebug<AssetServer>,
+    rollback_depth: usize,
+    checkpoints_on_lines: bool,
+    unavailable_options_policy: UnavailableOptionsPolicy,
+    variable_change_events: bool,
 }
 
 impl DialogueRunnerBuilder {
@@ -32,11 +37,16 @@ impl DialogueRunnerBuilder {
                 yarn_project,
             )),
             asset_providers: HashMap::default(),
+            marker_processors: Vec::new(),
             library: create_extended_standard_library(),
             commands: YarnCommands::builtin_commands(commands),
             compilation: yarn_project.compilation().clone(),
             localizations: yarn_project.localizations().cloned(),
             asset_server: yarn_project.asset_server.clone(),
+            rollback_depth: 0,
+            checkpoints_on_lines: false,
+            unavailable_options_policy: Default::default(),
+            variable_change_events: false,
         }
     }
 
@@ -62,6 +72,46 @@ impl DialogueRunnerBuilder {
         self
     }
 
+    /// Adds an [`AttributeMarkerProcessor`] that produces the replacement text for all markers named `attribute_name`,
+    /// e.g. `key` for `[key action="jump"/]`. The processor is passed the language of the [`DialogueRunner`] whenever it changes.
+    /// See [`Dialogue::add_marker_processor`] for details.
+    #[must_use]
+    pub fn add_marker_processor(
+        mut self,
+        attribute_name: impl Into<String>,
+        processor: impl AttributeMarkerProcessor + 'static,
+    ) -> Self {
+        self.marker_processors
+            .push((attribute_name.into(), Box::new(processor)));
+        self
+    }
+
+    /// Enables [`DialogueRunner::rollback`] to go back at most `depth` checkpoints. A checkpoint is taken whenever options are presented,
+    /// and also on every line if `checkpoints_on_lines` is set. By default, rollback is disabled.
+    /// See [`Dialogue::set_rollback_depth`] for details.
+    #[must_use]
+    pub fn with_rollback(mut self, depth: usize, checkpoints_on_lines: bool) -> Self {
+        self.rollback_depth = depth;
+        self.checkpoints_on_lines = checkpoints_on_lines;
+        self
+    }
+
+    /// Sets what happens when every option of an option group has a failing condition.
+    /// By default, the options are presented anyway. See [`UnavailableOptionsPolicy`] for details.
+    #[must_use]
+    pub fn with_unavailable_options_policy(mut self, policy: UnavailableOptionsPolicy) -> Self {
+        self.unavailable_options_policy = policy;
+        self
+    }
+
+    /// Fires a [`VariableChanged`] event whenever a variable changes, including changes made through [`DialogueRunner::variable_storage_mut`].
+    /// The variable storage is wrapped in an [`ObservedVariableStorage`], so the initial values of the program's variables are reported as changes in the first update.
+    #[must_use]
+    pub fn with_variable_change_events(mut self) -> Self {
+        self.variable_change_events = true;
+        self
+    }
+
     /// Builds the [`DialogueRunner`]. See [`DialogueRunnerBuilder::try_build`] for the fallible version.
     pub fn build(self) -> DialogueRunner {
         self.try_build().unwrap_or_else(|error| {
@@ -72,13 +122,24 @@ impl DialogueRunnerBuilder {
     /// Builds the [`DialogueRunner`].
     pub fn try_build(mut self) -> Result<DialogueRunner> {
         let text_provider = Box::new(self.text_provider);
+        let variable_storage: Box<dyn VariableStorage> = if self.variable_change_events {
+            Box::new(ObservedVariableStorage::new(self.variable_storage))
+        } else {
+            self.variable_storage
+        };
 
-        let mut dialogue = Dialogue::new(self.variable_storage, text_provider.clone());
+        let mut dialogue = Dialogue::new(variable_storage, text_provider.clone());
         dialogue
             .set_line_hints_enabled(true)
+            .set_rollback_depth(self.rollback_depth)
+            .set_checkpoints_on_lines(self.checkpoints_on_lines)
+            .set_unavailable_options_policy(self.unavailable_options_policy)
             .library_mut()
             .extend(self.library);
         dialogue.add_program(self.compilation.program.unwrap());
+        for (attribute_name, processor) in self.marker_processors {
+            dialogue.add_marker_processor(attribute_name, processor);
+        }
 
         for asset_provider in self.asset_providers.values_mut() {
             if let Some(ref localizations) = self.localizations {
@@ -33,13 +33,25 @@ pub struct DialogueOption {
 }
 
 impl DialogueOption {
+    /// Whether this option was presented before. Shorthand for [`LocalizedLine::is_seen`] of [`DialogueOption::line`].
+    #[must_use]
+    pub fn is_seen(&self) -> bool {
+        self.line.is_seen
+    }
+
     pub(crate) fn from_yarn_dialogue_option(
         yarn_dialogue_option: yarnspinner::prelude::DialogueOption,
         assets: LineAssets,
         metadata: Vec<String>,
+        language: Option<Language>,
     ) -> Self {
         Self {
-            line: LocalizedLine::from_yarn_line(yarn_dialogue_option.line, assets, metadata),
+            line: LocalizedLine::from_yarn_line(
+                yarn_dialogue_option.line,
+                assets,
+                metadata,
+                language,
+            ),
             id: yarn_dialogue_option.id,
             destination_node: yarn_dialogue_option.destination_node,
             is_available: yarn_dialogue_option.is_available,
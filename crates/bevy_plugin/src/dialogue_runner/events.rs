@@ -70,6 +70,36 @@ pub struct LineHints {
     pub entity: Entity,
 }
 
+/// An event that is fired in the update after [`DialogueRunner::rollback`] was called, right before the [`PresentOptions`] or [`PresentLine`]
+/// event of the checkpoint the dialogue was rewound to. The variables are already restored, but the side effects of commands are not.
+/// Handling this event is **optional** for dialogue views, but a game may want to revert the `undone_commands` and clear presented lines.
+#[derive(Debug, Clone, PartialEq, EntityEvent)]
+pub struct DialogueRolledBack {
+    /// The number of checkpoints that were rewound.
+    pub steps: usize,
+    /// The name of the node the dialogue is in after the rollback.
+    pub node_name: String,
+    /// The commands that were executed after the checkpoint, in the order they were executed.
+    pub undone_commands: Vec<UnderlyingYarnCommand>,
+    /// The [`DialogueRunner`] that was rolled back.
+    pub entity: Entity,
+}
+
+/// An event that is fired when a variable was changed, be it by the dialogue, e.g. through `<<set>>`, or by the game through [`DialogueRunner::variable_storage_mut`].
+/// Only fired if enabled with [`DialogueRunnerBuilder::with_variable_change_events`].
+/// Handling this event is **optional** for dialogue views, but a HUD may use it to show variables without polling them.
+#[derive(Debug, Clone, PartialEq, EntityEvent)]
+pub struct VariableChanged {
+    /// The name of the variable, including the leading `$`.
+    pub name: String,
+    /// The value before the change, or `None` if the variable was not set.
+    pub old_value: Option<YarnValue>,
+    /// The value after the change, or `None` if the variable was removed by clearing the [`VariableStorage`].
+    pub new_value: Option<YarnValue>,
+    /// The [`DialogueRunner`] whose variable storage was changed.
+    pub entity: Entity,
+}
+
 /// An event that is fired when a dialogue has been started via [`DialogueRunner::start_node`]/
 /// Handling this event is **optional** for dialogue views.
 #[derive(Debug, Clone, PartialEq, EntityEvent)]
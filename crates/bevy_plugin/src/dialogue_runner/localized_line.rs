@@ -1,6 +1,7 @@
 use crate::line_provider::LineAssets;
 use crate::prelude::*;
 use bevy::prelude::*;
+use std::ops::Range;
 use yarnspinner::runtime::{CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY};
 
 pub(crate) fn localized_line_plugin(_app: &mut App) {}
@@ -8,6 +9,7 @@ pub(crate) fn localized_line_plugin(_app: &mut App) {}
 /// A line from the Yarn file, with all metadata and markup parsed.
 /// The text is localized according to the localization logic used by the [`TextProvider`].
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct LocalizedLine {
     /// The ID of the line in the string table.
     pub id: LineId,
@@ -21,8 +23,28 @@ pub struct LocalizedLine {
     pub metadata: Vec<String>,
     /// The assets associated with this line, provided by [`AssetProvider`]s that were added with [`DialogueRunnerBuilder::add_asset_provider`].
     pub assets: LineAssets,
+    /// The language the [`LocalizedLine::text`] is actually in, as reported by [`TextProvider::get_text_language`].
+    /// This differs from [`DialogueRunner::text_language`] if the line is not translated into the current language and a language
+    /// from the [`Localizations::fallback_chain`] was used instead. Is [`None`] if the language is not known, e.g. because no [`Localizations`] were set up.
+    pub language: Option<Language>,
+    /// Whether the line was delivered before, as reported by [`YarnLine::is_seen`](crate::UnderlyingYarnLine::is_seen).
+    /// A fast-forward feature can use this to stop at unread lines. See [`DialogueRunner::seen_lines`].
+    pub is_seen: bool,
 }
 impl LocalizedLine {
+    /// Creates a line without metadata or assets that was not seen before, e.g. to test a dialogue view.
+    pub fn new(id: LineId, text: impl Into<String>, attributes: Vec<MarkupAttribute>) -> Self {
+        Self {
+            id,
+            text: text.into(),
+            attributes,
+            metadata: Vec::new(),
+            assets: Default::default(),
+            language: None,
+            is_seen: false,
+        }
+    }
+
     // Documentation taken from `YarnLine`
     /// Gets the first attribute with the specified name, if present.
     pub fn attribute(&self, name: &str) -> Option<&MarkupAttribute> {
@@ -36,19 +58,17 @@ impl LocalizedLine {
     /// ```rust
     /// # use bevy::platform::collections::HashMap;
     /// # use bevy_yarnspinner::prelude::*;
-    /// # let line = LocalizedLine {
-    /// #    id: "line".into(),
-    /// #    text: "Alice: Hello! How are you today?".to_owned(),
-    /// #    attributes: vec![MarkupAttribute {
-    /// #        name: "character".to_owned(),
-    /// #        position: 0,
-    /// #        length: 7,
-    /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
-    /// #        source_position: 0,
-    /// #    }],
-    /// #    metadata: vec![],
-    /// #    assets: Default::default(),
-    /// # };
+    /// # let line = LocalizedLine::new(
+    /// #     "line".into(),
+    /// #     "Alice: Hello! How are you today?",
+    /// #     vec![MarkupAttribute {
+    /// #         name: "character".to_owned(),
+    /// #         position: 0,
+    /// #         length: 7,
+    /// #         properties: HashMap::from([("name".to_owned(), "Alice".into())]),
+    /// #         source_position: 0,
+    /// #     }],
+    /// # );
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
     /// ```
@@ -57,13 +77,11 @@ impl LocalizedLine {
     /// ```rust
     /// # use bevy::platform::collections::HashMap;
     /// # use bevy_yarnspinner::prelude::*;
-    /// # let line = LocalizedLine {
-    /// #    id: "line".into(),
-    /// #    text: "Great, thanks".to_owned(),
-    /// #    attributes: vec![],
-    /// #    metadata: vec![],
-    /// #    assets: Default::default(),
-    /// # };
+    /// # let line = LocalizedLine::new(
+    /// #     "line".into(),
+    /// #     "Great, thanks",
+    /// #     vec![],
+    /// # );
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
     pub fn character_name(&self) -> Option<&str> {
@@ -86,19 +104,17 @@ impl LocalizedLine {
     /// ```rust
     /// # use bevy::platform::collections::HashMap;
     /// # use bevy_yarnspinner::prelude::*;
-    /// # let line = LocalizedLine {
-    /// #    id: "line".into(),
-    /// #    text: "Alice: Hello! How are you today?".to_owned(),
-    /// #    attributes: vec![MarkupAttribute {
-    /// #        name: "character".to_owned(),
-    /// #        position: 0,
-    /// #        length: 7,
-    /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
-    /// #        source_position: 0,
-    /// #    }],
-    /// #    metadata: vec![],
-    /// #    assets: Default::default(),
-    /// # };
+    /// # let line = LocalizedLine::new(
+    /// #     "line".into(),
+    /// #     "Alice: Hello! How are you today?",
+    /// #     vec![MarkupAttribute {
+    /// #         name: "character".to_owned(),
+    /// #         position: 0,
+    /// #         length: 7,
+    /// #         properties: HashMap::from([("name".to_owned(), "Alice".into())]),
+    /// #         source_position: 0,
+    /// #     }],
+    /// # );
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
     /// ```
@@ -107,13 +123,11 @@ impl LocalizedLine {
     /// ```rust
     /// # use bevy::platform::collections::HashMap;
     /// # use bevy_yarnspinner::prelude::*;
-    /// # let line = LocalizedLine {
-    /// #    id: "line".into(),
-    /// #    text: "Great, thanks".to_owned(),
-    /// #    attributes: vec![],
-    /// #    metadata: vec![],
-    /// #    assets: Default::default(),
-    /// # };
+    /// # let line = LocalizedLine::new(
+    /// #     "line".into(),
+    /// #     "Great, thanks",
+    /// #     vec![],
+    /// # );
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
     pub fn text_without_character_name(&self) -> String {
@@ -126,14 +140,26 @@ impl LocalizedLine {
 
     // Documentation taken from `YarnLine`
     /// Returns the substring of [`YarnLine::text`] covered by the passed `attribute`s [`MarkupAttribute::position`] and [`MarkupAttribute::length`] fields.
+    ///
+    /// ## Panics
+    /// Panics if `attribute` does not fit into [`LocalizedLine::text`].
     pub fn text_for_attribute(&self, attribute: &MarkupAttribute) -> &str {
-        self.text
-            .get(attribute.position..attribute.position + attribute.length)
-            .unwrap_or_else(|| panic!(
+        &self.text[self.attribute_range(attribute, TextUnit::Byte)]
+    }
+
+    // Documentation taken from `YarnLine`
+    /// Returns the range of [`LocalizedLine::text`] covered by `attribute`, measured in `unit`. See [`MarkupAttribute::range_in`].
+    ///
+    /// ## Panics
+    /// Panics if `attribute` does not fit into [`LocalizedLine::text`].
+    pub fn attribute_range(&self, attribute: &MarkupAttribute, unit: TextUnit) -> Range<usize> {
+        attribute.range_in(&self.text, unit).unwrap_or_else(|| {
+            panic!(
                 "Attribute \"{attribute}\" represents a range not representable by this text: \"{}\". \
                 Does this MarkupAttribute belong to this MarkupParseResult?",
                 self.text
-            ))
+            )
+        })
     }
 
     // Documentation taken from `YarnLine`
@@ -161,7 +187,12 @@ impl LocalizedLine {
     pub fn delete_range(&self, attribute_to_delete: &MarkupAttribute) -> Self {
         let yarn_line: YarnLine = self.clone().into();
         let deleted_range = yarn_line.delete_range(attribute_to_delete);
-        Self::from_yarn_line(deleted_range, self.assets.clone(), self.metadata.clone())
+        Self::from_yarn_line(
+            deleted_range,
+            self.assets.clone(),
+            self.metadata.clone(),
+            self.language.clone(),
+        )
     }
 
     /// Returns `true` if this line comes right before an options block.
@@ -187,6 +218,7 @@ impl LocalizedLine {
         line: YarnLine,
         assets: LineAssets,
         metadata: Vec<String>,
+        language: Option<Language>,
     ) -> Self {
         Self {
             id: line.id,
@@ -194,6 +226,8 @@ impl LocalizedLine {
             attributes: line.attributes,
             metadata,
             assets,
+            language,
+            is_seen: line.is_seen,
         }
     }
 }
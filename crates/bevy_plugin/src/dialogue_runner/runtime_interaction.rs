@@ -43,6 +43,16 @@ fn continue_runtime(
     let mut dialogues: HashMap<_, _, FixedHasher> = HashMap::default();
 
     for (source, mut dialogue_runner) in dialogue_runners.iter_mut() {
+        // Changes made by the game since the last update are reported even if the dialogue isn't running
+        for change in dialogue_runner.take_variable_changes() {
+            commands.trigger(VariableChanged {
+                name: change.name,
+                old_value: change.old_value,
+                new_value: change.new_value,
+                entity: source,
+            });
+        }
+
         let is_sending_missed_events: bool = !dialogue_runner.unsent_events.is_empty();
         if !is_sending_missed_events {
             if dialogue_runner.just_started {
@@ -137,8 +147,9 @@ fn continue_runtime(
                     DialogueEvent::Line(line) => {
                         let assets = dialogue_runner.get_assets(&line);
                         let metadata = project.line_metadata(&line.id).unwrap_or_default().to_vec();
+                        let language = dialogue_runner.text_provider.get_text_language(&line.id);
                         commands.trigger(PresentLine {
-                            line: LocalizedLine::from_yarn_line(line, assets, metadata),
+                            line: LocalizedLine::from_yarn_line(line, assets, metadata, language),
                             entity: source,
                         });
                     }
@@ -151,7 +162,12 @@ fn continue_runtime(
                                     .line_metadata(&option.line.id)
                                     .unwrap_or_default()
                                     .to_vec();
-                                DialogueOption::from_yarn_dialogue_option(option, assets, metadata)
+                                let language = dialogue_runner
+                                    .text_provider
+                                    .get_text_language(&option.line.id);
+                                DialogueOption::from_yarn_dialogue_option(
+                                    option, assets, metadata, language,
+                                )
                             })
                             .collect();
                         last_options.insert(source, options.clone());
@@ -185,12 +201,29 @@ fn continue_runtime(
                             entity: source,
                         });
                     }
+                    DialogueEvent::Rollback(rollback) => {
+                        commands.trigger(DialogueRolledBack {
+                            steps: rollback.steps,
+                            node_name: rollback.node_name,
+                            undone_commands: rollback.undone_commands,
+                            entity: source,
+                        });
+                    }
+                    DialogueEvent::VariableChanged(change) => {
+                        commands.trigger(VariableChanged {
+                            name: change.name,
+                            old_value: change.old_value,
+                            new_value: change.new_value,
+                            entity: source,
+                        });
+                    }
                     DialogueEvent::DialogueComplete => {
                         if !is_sending_missed_events {
                             dialogue_runner.is_running = false;
                         }
                         commands.trigger(DialogueCompleted { entity: source });
                     }
+                    _ => {}
                 }
             }
         }
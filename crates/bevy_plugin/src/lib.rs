@@ -105,6 +105,8 @@ mod line_provider;
 mod localization;
 mod plugin;
 mod project;
+#[cfg(feature = "rich_text")]
+mod rich_text;
 mod utils;
 mod yarn_file_asset;
 pub use anyhow::{Error, Result};
@@ -113,6 +115,8 @@ pub mod default_impl {
     //! Default implementations for Yarn Spinner traits.
     #[cfg(feature = "audio_assets")]
     pub use crate::line_provider::AudioAssetProvider;
+    #[cfg(feature = "fluent")]
+    pub use crate::line_provider::FluentFileTextProvider;
     pub use crate::line_provider::{
         FileExtensionAssetProvider, StringsFileTextProvider, file_extensions,
     };
@@ -122,8 +126,8 @@ pub mod default_impl {
 pub mod events {
     //! Events that are sent by the [`DialogueRunner`](crate::prelude::DialogueRunner). A dialogue view is expected to at least handle [`PresentLine`] event and [`PresentOptions`] event.
     pub use crate::dialogue_runner::{
-        DialogueCompleted, DialogueStarted, ExecuteCommand, LineHints, NodeCompleted, NodeStarted,
-        PresentLine, PresentOptions,
+        DialogueCompleted, DialogueRolledBack, DialogueStarted, ExecuteCommand, LineHints,
+        NodeCompleted, NodeStarted, PresentLine, PresentOptions, VariableChanged,
     };
 }
 
@@ -132,13 +136,20 @@ pub mod prelude {
 
     #[cfg(feature = "audio_assets")]
     pub use crate::default_impl::AudioAssetProvider;
+    #[cfg(feature = "fluent")]
+    pub use crate::default_impl::FluentFileTextProvider;
+    #[cfg(feature = "rich_text")]
+    pub use crate::rich_text::{MarkupStyle, MarkupStyles, RichText, RichTextSegment};
     pub use crate::{
         commands::{YarnCommand, YarnCommands},
         default_impl::FileExtensionAssetProvider,
         development_file_generation::DevelopmentFileGeneration,
         dialogue_runner::{DialogueOption, DialogueRunner, DialogueRunnerBuilder, LocalizedLine},
         line_provider::{AssetProvider, LineAssets, TextProvider},
-        localization::{Localization, Localizations},
+        localization::{
+            Localization, Localizations, TranslationCompletion, TranslationStatus,
+            translation_status,
+        },
         plugin::{YarnFileSource, YarnSpinnerPlugin, YarnSpinnerSystemSet},
         project::YarnProject,
         yarn_file_asset::YarnFile,
@@ -148,8 +159,9 @@ pub mod prelude {
     pub(crate) use serde::{Deserialize, Serialize};
     pub(crate) use yarnspinner::prelude::*;
     pub use yarnspinner::prelude::{
-        IntoYarnValueFromNonYarnValue, Language, LineId, MarkupAttribute, MarkupValue, OptionId,
-        VariableStorage, YarnFn, YarnLibrary, YarnValue,
+        AttributeMarkerProcessor, DialogueHistory, HistoryEntry, IntoYarnValueFromNonYarnValue,
+        Language, LineId, MarkupAttribute, MarkupAttributeMarker, MarkupValue, OptionId, SeenLines,
+        TextUnit, UnavailableOptionsPolicy, VariableStorage, YarnFn, YarnLibrary, YarnValue,
     };
     pub(crate) type SystemResult = anyhow::Result<()>;
     pub(crate) use yarnspinner_internal_shared::prelude::*;
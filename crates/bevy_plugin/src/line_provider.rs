@@ -2,6 +2,8 @@
 pub use asset_provider::AudioAssetProvider;
 pub use asset_provider::{AssetProvider, FileExtensionAssetProvider, LineAssets, file_extensions};
 use bevy::prelude::*;
+#[cfg(feature = "fluent")]
+pub use text_provider::FluentFileTextProvider;
 pub(crate) use text_provider::SharedTextProvider;
 pub use text_provider::{StringsFileTextProvider, TextProvider};
 
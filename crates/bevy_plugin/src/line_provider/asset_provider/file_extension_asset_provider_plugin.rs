@@ -17,6 +17,7 @@ pub(crate) fn file_extension_asset_provider_plugin(_app: &mut App) {}
 ///
 /// By default, the line asset subdirectory will be `"dialogue/<language>"`. So for the language "en-US" and the line ID "123", the provider will
 /// specifically look for "assets/dialogue/en-US/123.png" when calling [`FileExtensionAssetProvider::get_assets`].
+/// If no asset of a type is found for the language, the subdirectories of the other languages in its [`Localizations::fallback_chain`] are searched in order.
 /// Because this requires knowledge of the current language, this provider will only fetch assets if you set up Yarn Spinner with [`Localizations`] using
 /// [`YarnSpinnerPlugin::with_localizations`] or [`LoadYarnProjectEvent::with_localizations`](crate::deferred_loading::LoadYarnProjectEvent::with_localizations).
 ///
@@ -183,19 +184,22 @@ impl AssetProvider for FileExtensionAssetProvider {
         if let Some(language) = self.language.as_ref()
             && let Some(localizations) = self.localizations.as_ref()
         {
-            if let Some(localization) = localizations.supported_localization(language) {
-                let dir = localization.assets_sub_folder.as_path();
+            if localizations.supports_language(language) {
+                let fallback_chain = localizations.fallback_chain(language);
                 let file_name_without_extension = line.id.0.trim_start_matches(LINE_ID_PREFIX);
                 let assets = self
                     .file_extensions
                     .iter()
                     .filter_map(|(type_id, exts)| {
-                        exts.iter().find_map(|ext| {
-                            let file_name = format!("{file_name_without_extension}.{ext}");
-                            let path = dir.join(file_name);
-                            self.loaded_handles
-                                .get(&path)
-                                .map(|handle| (*type_id, handle.clone()))
+                        fallback_chain.iter().find_map(|localization| {
+                            let dir = localization.assets_sub_folder.as_path();
+                            exts.iter().find_map(|ext| {
+                                let file_name = format!("{file_name_without_extension}.{ext}");
+                                let path = dir.join(file_name);
+                                self.loaded_handles
+                                    .get(&path)
+                                    .map(|handle| (*type_id, handle.clone()))
+                            })
                         })
                     })
                     .collect::<HashSet<_>>();
@@ -215,23 +219,25 @@ impl FileExtensionAssetProvider {
         if let Some(language) = self.language.as_ref()
             && let Some(localizations) = self.localizations.as_ref()
         {
-            if let Some(localization) = localizations.supported_localization(language) {
-                let dir = localization.assets_sub_folder.as_path();
+            if localizations.supports_language(language) {
                 self.loading_handles.clear();
                 self.loaded_handles.clear();
                 let Some(asset_server) = self.asset_server.as_ref() else {
                     return;
                 };
-                for line_id in self.line_ids.iter() {
-                    for extension in self.file_extensions.values().flatten() {
-                        let file_name = format!(
-                            "{}.{extension}",
-                            line_id.0.trim_start_matches(LINE_ID_PREFIX)
-                        );
-                        let path = dir.join(file_name);
-                        let asset_path = path.to_string_lossy().replace('\\', "/");
-                        let handle = asset_server.load_untyped(asset_path);
-                        self.loading_handles.insert(path, handle);
+                for localization in localizations.fallback_chain(language) {
+                    let dir = localization.assets_sub_folder.as_path();
+                    for line_id in self.line_ids.iter() {
+                        for extension in self.file_extensions.values().flatten() {
+                            let file_name = format!(
+                                "{}.{extension}",
+                                line_id.0.trim_start_matches(LINE_ID_PREFIX)
+                            );
+                            let path = dir.join(file_name);
+                            let asset_path = path.to_string_lossy().replace('\\', "/");
+                            let handle = asset_server.load_untyped(asset_path);
+                            self.loading_handles.insert(path, handle);
+                        }
                     }
                 }
             } else {
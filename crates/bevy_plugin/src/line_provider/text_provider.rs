@@ -2,11 +2,15 @@ use crate::UnderlyingTextProvider;
 use crate::line_provider::LineProviderSystemSet;
 use crate::prelude::*;
 use bevy::prelude::*;
+#[cfg(feature = "fluent")]
+pub use fluent_file_text_provider::FluentFileTextProvider;
 pub(crate) use shared_text_provider::SharedTextProvider;
 use std::any::Any;
 use std::collections::HashMap;
 pub use strings_file_text_provider::StringsFileTextProvider;
 
+#[cfg(feature = "fluent")]
+mod fluent_file_text_provider;
 mod shared_text_provider;
 mod strings_file_text_provider;
 
@@ -19,6 +23,8 @@ pub(crate) fn text_provider_plugin(app: &mut App) {
                 .in_set(LineProviderSystemSet)
                 .in_set(YarnSpinnerSystemSet),
         );
+    #[cfg(feature = "fluent")]
+    app.add_plugins(fluent_file_text_provider::fluent_file_text_provider_plugin);
 }
 
 /// Trait for the provider the [`DialogueRunner`]s text. By default, this is a [`StringsFileTextProvider`].
@@ -40,6 +46,13 @@ pub trait TextProvider: UnderlyingTextProvider {
     /// This functionality is split into two functions because [`TextProvider::take_fetched_assets`] is mutable,
     /// so we lose access to the [`World`] when calling it since it contains this very [`TextProvider`].
     fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>>;
+
+    /// Returns the language that the text for the given line is actually provided in, which is reported as [`LocalizedLine::language`].
+    /// This differs from [`UnderlyingTextProvider::get_language`] when the line is missing in the current language and a fallback language is used instead.
+    /// By default, this is the current language.
+    fn get_text_language(&self, _id: &LineId) -> Option<Language> {
+        self.get_language()
+    }
 }
 
 pub(crate) fn fetch_resources(world: &mut World) {
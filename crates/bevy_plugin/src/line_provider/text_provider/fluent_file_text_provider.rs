@@ -0,0 +1,246 @@
+use crate::UnderlyingTextProvider;
+use crate::fmt_utils::SkipDebug;
+use crate::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::ecs::message::MessageCursor;
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use yarnspinner::runtime::FluentTextProvider;
+
+pub(crate) fn fluent_file_text_provider_plugin(app: &mut App) {
+    app.init_asset::<FluentFile>()
+        .init_asset_loader::<FluentFileAssetLoader>();
+}
+
+/// The contents of a [Project Fluent](https://projectfluent.org/) `.ftl` file.
+#[derive(Debug, Clone, Eq, PartialEq, Asset, TypePath)]
+pub(crate) struct FluentFile(String);
+
+#[derive(Debug, Default)]
+struct FluentFileAssetLoader;
+
+impl AssetLoader for FluentFileAssetLoader {
+    type Asset = FluentFile;
+    type Settings = ();
+    type Error = anyhow::Error;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(FluentFile(String::from_utf8(bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+/// A [`TextProvider`] that looks up the text of lines in [Project Fluent](https://projectfluent.org/) `.ftl` files loaded as assets,
+/// using a [`FluentTextProvider`] under the hood. See its documentation for how lines and their substitutions map to Fluent messages.
+/// Use it by passing it to [`DialogueRunnerBuilder::with_text_provider`].
+///
+/// When the language is set, the files added for that language and its [`Language::parents`] are loaded.
+/// Lines without a message in any of them use the text of the Yarn files. The files are reloaded whenever they change on disk,
+/// provided that Bevy's `file_watcher` feature is enabled.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::prelude::*;
+/// fn spawn_dialogue_runner(mut commands: Commands, project: Res<YarnProject>) {
+///     let text_provider = FluentFileTextProvider::from_yarn_project(&project)
+///         .add_file("de", "dialogue/de/dialogue.ftl")
+///         .add_file("de-CH", "dialogue/de-CH/dialogue.ftl");
+///     let dialogue_runner = project
+///         .build_dialogue_runner(&mut commands)
+///         .with_text_provider(text_provider)
+///         .build();
+///     commands.spawn(dialogue_runner);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FluentFileTextProvider {
+    asset_server: SkipDebug<AssetServer>,
+    base_language: Option<Language>,
+    language: Option<Language>,
+    base_string_table: HashMap<LineId, StringInfo>,
+    files: Vec<(Language, PathBuf)>,
+    fluent_file_handles: Vec<(Language, Handle<FluentFile>)>,
+    fluent_text_provider: FluentTextProvider,
+    has_fetched_files: bool,
+    event_cursor: Arc<RwLock<MessageCursor<AssetEvent<FluentFile>>>>,
+}
+
+impl FluentFileTextProvider {
+    /// Create a new text provider from a Yarn project. Add the `.ftl` files of the translations with [`FluentFileTextProvider::add_file`].
+    pub fn from_yarn_project(yarn_project: &YarnProject) -> Self {
+        Self {
+            asset_server: yarn_project.asset_server.clone(),
+            base_language: yarn_project
+                .localizations
+                .as_ref()
+                .map(|localizations| localizations.base_localization.language.clone()),
+            language: None,
+            base_string_table: yarn_project.compilation.string_table.clone(),
+            files: Vec::new(),
+            fluent_file_handles: Vec::new(),
+            fluent_text_provider: FluentTextProvider::new(),
+            has_fetched_files: false,
+            event_cursor: Default::default(),
+        }
+    }
+
+    /// Adds an `.ftl` file for the given language. The path is relative to the assets folder.
+    /// A language can have multiple files, in which case messages of files added later take precedence.
+    #[must_use]
+    pub fn add_file(mut self, language: impl Into<Language>, path: impl AsRef<Path>) -> Self {
+        self.files
+            .push((language.into(), path.as_ref().to_path_buf()));
+        self
+    }
+
+    fn load_files(&mut self) {
+        self.fluent_file_handles.clear();
+        self.has_fetched_files = false;
+        self.fluent_text_provider.clear_resources();
+        let Some(language) = self.language.clone() else {
+            return;
+        };
+        let languages: Vec<_> = std::iter::once(language.clone())
+            .chain(language.parents())
+            .collect();
+        self.fluent_file_handles = self
+            .files
+            .iter()
+            .filter(|(file_language, _)| languages.contains(file_language))
+            .map(|(language, path)| {
+                let asset_path = path.to_string_lossy().replace('\\', "/");
+                (language.clone(), self.asset_server.load(asset_path))
+            })
+            .collect();
+    }
+}
+
+impl UnderlyingTextProvider for FluentFileTextProvider {
+    fn clone_shallow(&self) -> Box<dyn UnderlyingTextProvider> {
+        Box::new(self.clone())
+    }
+
+    fn accept_line_hints(&mut self, _line_ids: &[LineId]) {
+        // no-op
+    }
+
+    fn get_text(&self, id: &LineId) -> Option<String> {
+        self.fluent_text_provider
+            .get_text(id)
+            .or_else(|| self.base_string_table.get(id).map(|info| info.text.clone()))
+    }
+
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.fluent_text_provider
+            .get_text_with_substitutions(id, substitutions)
+    }
+
+    fn set_language(&mut self, language: Option<Language>) {
+        if language == self.language {
+            return;
+        }
+        self.language = language;
+        self.load_files();
+        let has_files = !self.fluent_file_handles.is_empty();
+        self.fluent_text_provider
+            .set_language(self.language.clone().filter(|_| has_files));
+    }
+
+    fn get_language(&self) -> Option<Language> {
+        self.language.clone()
+    }
+
+    fn are_lines_available(&self) -> bool {
+        self.fluent_file_handles.is_empty() || self.has_fetched_files
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl TextProvider for FluentFileTextProvider {
+    fn set_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        self.base_string_table = string_table;
+    }
+
+    fn extend_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        self.base_string_table.extend(string_table);
+    }
+
+    fn take_fetched_assets(&mut self, asset: Box<dyn Any>) {
+        let sources: Box<Vec<(Language, String)>> = asset.downcast().unwrap();
+        self.fluent_text_provider.clear_resources();
+        for (language, source) in *sources {
+            if let Err(error) = self.fluent_text_provider.add_resource(language, source) {
+                warn!("{error}");
+            }
+        }
+        self.has_fetched_files = true;
+    }
+
+    fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>> {
+        if self.fluent_file_handles.is_empty() {
+            return None;
+        }
+        let all_loaded = self
+            .fluent_file_handles
+            .iter()
+            .all(|(_language, handle)| self.asset_server.is_loaded_with_dependencies(handle));
+        if !all_loaded {
+            return None;
+        }
+        let asset_events = world.resource::<Messages<AssetEvent<FluentFile>>>();
+        let fluent_file_has_changed = || {
+            let mut cursor = self.event_cursor.write().unwrap();
+            cursor.read(asset_events).any(|event| match event {
+                AssetEvent::Modified { id } => self
+                    .fluent_file_handles
+                    .iter()
+                    .any(|(_language, handle)| *id == handle.id()),
+                _ => false,
+            })
+        };
+        if !self.has_fetched_files || fluent_file_has_changed() {
+            let fluent_files = world.resource::<Assets<FluentFile>>();
+            let sources: Vec<(Language, String)> = self
+                .fluent_file_handles
+                .iter()
+                .map(|(language, handle)| {
+                    let fluent_file = fluent_files.get(handle).unwrap();
+                    (language.clone(), fluent_file.0.clone())
+                })
+                .collect();
+            Some(Box::new(sources))
+        } else {
+            None
+        }
+    }
+
+    fn get_text_language(&self, id: &LineId) -> Option<Language> {
+        self.fluent_text_provider
+            .get_text_language(id)
+            .or_else(|| self.base_language.clone())
+    }
+}
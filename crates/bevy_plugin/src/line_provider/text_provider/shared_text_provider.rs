@@ -43,6 +43,10 @@ impl TextProvider for SharedTextProvider {
     fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>> {
         self.0.read().unwrap().fetch_assets(world)
     }
+
+    fn get_text_language(&self, id: &LineId) -> Option<Language> {
+        self.0.read().unwrap().get_text_language(id)
+    }
 }
 
 impl UnderlyingTextProvider for SharedTextProvider {
@@ -58,6 +62,17 @@ impl UnderlyingTextProvider for SharedTextProvider {
         self.0.read().unwrap().get_text(id)
     }
 
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        self.0
+            .read()
+            .unwrap()
+            .get_text_with_substitutions(id, substitutions)
+    }
+
     fn set_language(&mut self, language: Option<Language>) {
         self.0.write().unwrap().set_language(language)
     }
@@ -13,16 +13,17 @@ pub(crate) fn strings_file_text_provider_plugin(_app: &mut App) {}
 /// The default [`TextProvider`] used by a [`DialogueRunner`] unless overridden with [`DialogueRunnerBuilder::with_text_provider`].
 /// If the [`DialogueRunner`]'s language is the base language, i.e. the one the Yarn files are written in,
 /// this will send the lines as they appear in the Yarn file. If [`DialogueRunner::set_language`] or [`DialogueRunner::set_text_language`] were used to
-/// set the language to a language supported by a translation in the [`Localizations`], this loads the strings file for that translation from the disk at the
-/// specified path. If this fails, the base language is used as a fallback.
+/// set the language to a language supported by a translation in the [`Localizations`], this loads the strings files for all translations in the
+/// [`Localizations::fallback_chain`] of that language from the disk at the specified paths. Lines that are missing from a strings file are looked up
+/// in the next one, using the base language as the last fallback.
 #[derive(Debug, Clone)]
 pub struct StringsFileTextProvider {
     asset_server: SkipDebug<AssetServer>,
     localizations: Option<Localizations>,
     language: Option<Language>,
     base_string_table: HashMap<LineId, StringInfo>,
-    strings_file_handle: Option<Handle<StringsFile>>,
-    translation_string_table: Option<HashMap<LineId, String>>,
+    strings_file_handles: Vec<(Language, Handle<StringsFile>)>,
+    translation_string_tables: Option<Vec<(Language, HashMap<LineId, String>)>>,
     event_cursor: Arc<RwLock<MessageCursor<AssetEvent<StringsFile>>>>,
 }
 
@@ -40,18 +41,33 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
             return self.base_string_table.get(id).map(|info| info.text.clone());
         }
 
-        self.translation_string_table
-            .as_ref()
-            .and_then(|table| table.get(id).cloned())
-            .or_else(|| {
-                let language = self.language.as_ref().unwrap();
-                if self.translation_string_table.is_some() {
-                    warn!("Did not find translation for line {id} in language {language} because it is untranslated, falling back to base language.");
-                } else {
-                    warn!("Did not find translation for line {id} in language {language} because the strings file has not been loaded yet, falling back to base language.");
+        let language = self.language.as_ref().unwrap();
+        let Some(translation_string_tables) = self.translation_string_tables.as_ref() else {
+            warn!(
+                "Did not find translation for line {id} in language {language} because the strings files have not been loaded yet, falling back to base language."
+            );
+            return self.base_string_table.get(id).map(|info| info.text.clone());
+        };
+        let translation = translation_string_tables
+            .iter()
+            .find_map(|(table_language, table)| Some((table_language, table.get(id)?)));
+        match translation {
+            Some((table_language, text)) => {
+                // Falling back along the chain is what the fallbacks are configured for, so this is no cause for a warning
+                if table_language != language {
+                    debug!(
+                        "Did not find translation for line {id} in language {language} falling back to language {table_language}."
+                    );
                 }
+                Some(text.clone())
+            }
+            None => {
+                warn!(
+                    "Did not find translation for line {id} in language {language} because it is untranslated, falling back to base language."
+                );
                 self.base_string_table.get(id).map(|info| info.text.clone())
-            })
+            }
+        }
     }
 
     fn set_language(&mut self, language: Option<Language>) {
@@ -74,7 +90,7 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
             self.set_language_invalidating_translation(None);
             return;
         }
-        let Some(localization) = localizations.translation(&language) else {
+        if !localizations.supports_language(&language) {
             let languages = localizations
                 .supported_languages()
                 .map(ToString::to_string)
@@ -84,10 +100,21 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
                 "Set language to {language}, but that language is not supported. Expected one of {languages}."
             );
         };
-        let path = localization.strings_file.as_path();
-        let asset_path = path.to_string_lossy().replace('\\', "/");
-        self.strings_file_handle
-            .replace(self.asset_server.load(asset_path));
+        self.strings_file_handles = localizations
+            .fallback_chain(&language)
+            .into_iter()
+            .filter(|localization| {
+                localization.language != localizations.base_localization.language
+            })
+            .map(|localization| {
+                let path = localization.strings_file.as_path();
+                let asset_path = path.to_string_lossy().replace('\\', "/");
+                (
+                    localization.language.clone(),
+                    self.asset_server.load(asset_path),
+                )
+            })
+            .collect();
     }
 
     fn get_language(&self) -> Option<Language> {
@@ -96,7 +123,7 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
 
     fn are_lines_available(&self) -> bool {
         let is_base_language = self.is_base_language();
-        let has_fetched_translation = || self.translation_string_table.is_some();
+        let has_fetched_translation = || self.translation_string_tables.is_some();
         is_base_language || has_fetched_translation()
     }
 
@@ -117,15 +144,15 @@ impl StringsFileTextProvider {
             localizations: yarn_project.localizations.clone(),
             language: None,
             base_string_table: yarn_project.compilation.string_table.clone(),
-            strings_file_handle: None,
-            translation_string_table: None,
+            strings_file_handles: Vec::new(),
+            translation_string_tables: None,
             event_cursor: Default::default(),
         }
     }
     fn set_language_invalidating_translation(&mut self, language: impl Into<Option<Language>>) {
         self.language = language.into();
-        self.translation_string_table = None;
-        self.strings_file_handle = None;
+        self.translation_string_tables = None;
+        self.strings_file_handles.clear();
     }
 
     fn is_base_language(&self) -> bool {
@@ -148,46 +175,78 @@ impl TextProvider for StringsFileTextProvider {
     }
 
     fn take_fetched_assets(&mut self, asset: Box<dyn Any>) {
-        let string_table: Box<HashMap<LineId, String>> = asset.downcast().unwrap();
-        self.translation_string_table.replace(*string_table);
+        let string_tables: Box<Vec<(Language, HashMap<LineId, String>)>> =
+            asset.downcast().unwrap();
+        self.translation_string_tables.replace(*string_tables);
     }
 
     fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>> {
-        if self.is_base_language() {
+        if self.is_base_language() || self.strings_file_handles.is_empty() {
             return None;
         }
-        let handle = self.strings_file_handle.as_ref()?;
-        if !self.asset_server.is_loaded_with_dependencies(handle) {
+        let all_loaded = self
+            .strings_file_handles
+            .iter()
+            .all(|(_language, handle)| self.asset_server.is_loaded_with_dependencies(handle));
+        if !all_loaded {
             return None;
         }
         let asset_events = world.resource::<Messages<AssetEvent<StringsFile>>>();
         let strings_file_has_changed = || {
             let mut cursor = self.event_cursor.write().unwrap();
             cursor.read(asset_events).any(|event| match event {
-                AssetEvent::Modified { id } => *id == handle.id(),
+                AssetEvent::Modified { id } => self
+                    .strings_file_handles
+                    .iter()
+                    .any(|(_language, handle)| *id == handle.id()),
                 _ => false,
             })
         };
-        let has_no_translation_yet = self.translation_string_table.is_none();
+        let has_no_translation_yet = self.translation_string_tables.is_none();
         if has_no_translation_yet || strings_file_has_changed() {
-            let strings_file = world.resource::<Assets<StringsFile>>().get(handle).unwrap();
-            let expected_language = self.language.as_ref().unwrap();
-            if let Some(record) = strings_file.get_offending_language(expected_language) {
-                let path = self.asset_server.get_path(handle).unwrap();
-                panic!(
-                    "Expected strings file at {path} to only contain language {expected_language}, but its entry with id \"{id}\" is for language {actual_language}.",
-                    path = path.path().display(),
-                    id = record.id,
-                    actual_language = record.language,
-                );
-            }
-            let string_table: HashMap<LineId, String> = strings_file
+            let strings_files = world.resource::<Assets<StringsFile>>();
+            let string_tables: Vec<(Language, HashMap<LineId, String>)> = self
+                .strings_file_handles
                 .iter()
-                .map(|(id, record)| (id.clone(), record.text.clone()))
+                .map(|(expected_language, handle)| {
+                    let strings_file = strings_files.get(handle).unwrap();
+                    if let Some(record) = strings_file.get_offending_language(expected_language) {
+                        let path = self.asset_server.get_path(handle).unwrap();
+                        panic!(
+                            "Expected strings file at {path} to only contain language {expected_language}, but its entry with id \"{id}\" is for language {actual_language}.",
+                            path = path.path().display(),
+                            id = record.id,
+                            actual_language = record.language,
+                        );
+                    }
+                    let string_table = strings_file
+                        .iter()
+                        .map(|(id, record)| (id.clone(), record.text.clone()))
+                        .collect();
+                    (expected_language.clone(), string_table)
+                })
                 .collect();
-            Some(Box::new(string_table))
+            Some(Box::new(string_tables))
         } else {
             None
         }
     }
+
+    fn get_text_language(&self, id: &LineId) -> Option<Language> {
+        let base_language = self
+            .localizations
+            .as_ref()
+            .map(|localizations| localizations.base_localization.language.clone());
+        if self.is_base_language() {
+            return base_language;
+        }
+        self.translation_string_tables
+            .as_ref()
+            .and_then(|tables| {
+                tables
+                    .iter()
+                    .find_map(|(language, table)| table.contains_key(id).then(|| language.clone()))
+            })
+            .or(base_language)
+    }
 }
@@ -1,4 +1,5 @@
 pub use self::localizations::*;
+pub use self::translation_status::*;
 pub(crate) use self::{
     line_id_generation::LineIdUpdateSystemSet,
     strings_file::UpdateAllStringsFilesForStringTableEvent, strings_file::*,
@@ -8,9 +9,11 @@ use bevy::prelude::*;
 mod line_id_generation;
 mod localizations;
 mod strings_file;
+mod translation_status;
 
 pub(crate) fn localization_plugin(app: &mut App) {
     app.add_plugins(localizations::localization_config_plugin)
         .add_plugins(line_id_generation::line_id_generation_plugin)
-        .add_plugins(strings_file::strings_file_plugin);
+        .add_plugins(strings_file::strings_file_plugin)
+        .add_plugins(translation_status::translation_status_plugin);
 }
@@ -30,10 +30,68 @@ pub struct Localizations {
 }
 
 impl Localizations {
-    /// Returns whether the given language is supported by these [`Localizations`] as either a base language or a translation.
+    /// Returns whether the given language is supported by these [`Localizations`] as either a base language or a translation,
+    /// or falls back to one of them. For example, `de-CH` is supported if there is a translation for `de`.
+    /// See [`Localizations::fallback_chain`] for how fallbacks are determined.
     pub fn supports_language(&self, language: &Language) -> bool {
-        self.supported_languages()
-            .any(|supported_language| supported_language == language)
+        self.fallback_candidates(language)
+            .iter()
+            .any(|candidate| self.supported_localization(candidate).is_some())
+    }
+
+    /// Returns the localizations that are searched, in order, for the text and assets of a line when the given language is selected.
+    ///
+    /// The chain starts with the localization of the language itself, if it is supported. It continues with the
+    /// [`Localization::fallbacks`] of that localization or, if there are none, the parent locales of the language as defined by
+    /// the [CLDR](https://cldr.unicode.org/), e.g. `de-CH` → `de`. Unsupported languages are skipped.
+    /// The chain always ends with the [`Localizations::base_localization`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy_yarnspinner::prelude::*;
+    /// let localizations = Localizations {
+    ///     base_localization: "en-US".into(),
+    ///     translations: vec![
+    ///         "de".into(),
+    ///         "pt-PT".into(),
+    ///         Localization::with_language("pt-BR").with_fallbacks(["pt-PT"]),
+    ///     ],
+    /// };
+    /// let languages = |language: &str| {
+    ///     localizations
+    ///         .fallback_chain(&language.into())
+    ///         .into_iter()
+    ///         .map(|localization| localization.language.to_string())
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(languages("de-CH"), ["de", "en-US"]);
+    /// assert_eq!(languages("pt-BR"), ["pt-BR", "pt-PT", "en-US"]);
+    /// ```
+    pub fn fallback_chain(&self, language: &Language) -> Vec<&Localization> {
+        let mut chain: Vec<&Localization> = Vec::new();
+        for candidate in self.fallback_candidates(language) {
+            if let Some(localization) = self.supported_localization(&candidate)
+                && !chain.contains(&localization)
+            {
+                chain.push(localization);
+            }
+            if candidate == self.base_localization.language {
+                break;
+            }
+        }
+        if !chain.contains(&&self.base_localization) {
+            chain.push(&self.base_localization);
+        }
+        chain
+    }
+
+    fn fallback_candidates(&self, language: &Language) -> Vec<Language> {
+        let fallbacks = match self.translation(language) {
+            Some(translation) if !translation.fallbacks.is_empty() => translation.fallbacks.clone(),
+            _ => language.parents(),
+        };
+        iter::once(language.clone()).chain(fallbacks).collect()
     }
 
     /// Returns the localization for the given translation, if it exists. Will return [`None`] if the given language is not supported or the base language.
@@ -84,6 +142,11 @@ pub struct Localization {
     /// The path to the subdirectory containing the assets for this localization inside the `assets` folder.
     /// Defaults to `dialogue/{language}/`.  So, for the language "de-CH", you'd end up with "assets/dialogue/de-CH/".
     pub assets_sub_folder: PathBuf,
+    /// The languages to look up lines and assets in, in order, if they are missing from this localization.
+    /// The base language is always used last. Defaults to none, which means the parent locales of [`Localization::language`] are used.
+    /// See [`Localizations::fallback_chain`] for details.
+    #[serde(default)]
+    pub fallbacks: Vec<Language>,
 }
 
 impl<T> From<T> for Localization
@@ -111,6 +174,7 @@ impl Localization {
             language,
             strings_file,
             assets_sub_folder,
+            fallbacks: Vec::new(),
         }
     }
 
@@ -125,4 +189,14 @@ impl Localization {
         self.assets_sub_folder = assets_sub_folder.into();
         self
     }
+
+    /// Sets the languages to fall back to, in order, when a line or asset is missing from this localization.
+    /// For example, a `pt-BR` localization could fall back to `pt-PT` before using the base language.
+    pub fn with_fallbacks(
+        mut self,
+        fallbacks: impl IntoIterator<Item = impl Into<Language>>,
+    ) -> Self {
+        self.fallbacks = fallbacks.into_iter().map(Into::into).collect();
+        self
+    }
 }
@@ -2,6 +2,7 @@ pub(crate) use self::{asset::StringsFile, updating::UpdateAllStringsFilesForStri
 use bevy::prelude::*;
 
 mod asset;
+mod reassociation;
 mod updating;
 
 pub(crate) fn strings_file_plugin(app: &mut App) {
@@ -1,15 +1,16 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Runtime/StringTableEntry.cs>
 
+use super::reassociation::{Reassociation, find_reassociations};
 use crate::prelude::*;
 use anyhow::{Result, anyhow, bail};
 use bevy::asset::{AssetLoader, LoadContext, io::Reader};
-use bevy::platform::collections::HashMap;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::reflect::TypePath;
-use sha2::{Digest, Sha256};
 use std::fs;
 use std::fs::File;
 use std::path::Path;
+use yarnspinner::compiler::compute_lock;
 
 pub(crate) fn strings_file_asset_plugin(app: &mut App) {
     app.init_asset::<StringsFile>()
@@ -31,10 +32,7 @@ impl AssetLoader for StringsFileAssetLoader {
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let mut csv_reader = csv::Reader::from_reader(bytes.as_slice());
-        let records: csv::Result<Vec<_>> = csv_reader.deserialize().collect();
-        let strings_file = StringsFile::new_with_single_language(records?)?;
-        Ok(strings_file)
+        StringsFile::from_csv(bytes.as_slice())
     }
 
     fn extensions(&self) -> &[&str] {
@@ -67,6 +65,19 @@ impl StringsFile {
         Ok(Self(records))
     }
 
+    pub(crate) fn from_csv(csv: impl std::io::Read) -> Result<Self> {
+        let mut csv_reader = csv::Reader::from_reader(csv);
+        let records: csv::Result<Vec<_>> = csv_reader.deserialize().collect();
+        Self::new_with_single_language(records?)
+    }
+
+    pub(crate) fn read_asset(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .map_err(|e| anyhow!("Failed to open strings file \"{}\": {e}", path.display()))?;
+        Self::from_csv(file)
+            .map_err(|e| anyhow!("Failed to read strings file \"{}\": {e}", path.display()))
+    }
+
     pub(crate) fn language(&self) -> Option<&Language> {
         self.0.iter().next().map(|(_id, record)| &record.language)
     }
@@ -140,6 +151,53 @@ impl StringsFile {
         changed
     }
 
+    /// Carries the translations of lines that are missing from `other` over to lines that are new in `other`,
+    /// as long as both are in the same node and are similar enough in text and position.
+    /// This keeps translations alive when a line gets a new ID, e.g. because it was deleted and typed again.
+    ///
+    /// `previous_base_texts` contains the base language texts of lines as they were before the change, if known.
+    /// The carried over texts are marked with [`UPDATE_PREFIX`] so that translators review them.
+    /// Call this before [`StringsFile::update_file`] so that it does not delete the translations first.
+    pub(crate) fn reassociate_translations(
+        &mut self,
+        other: &Self,
+        previous_base_texts: &HashMap<LineId, String>,
+    ) -> Vec<Reassociation> {
+        let files: HashSet<_> = other
+            .0
+            .values()
+            .map(|record| record.file.as_str())
+            .collect();
+        let removed = self.0.values().filter(|record| {
+            files.contains(record.file.as_str())
+                && !other.0.contains_key(&record.id)
+                && !record.is_untranslated()
+        });
+        let added = other
+            .0
+            .values()
+            .filter(|record| !self.0.contains_key(&record.id));
+        let reassociations = find_reassociations(removed, added, previous_base_texts);
+
+        for reassociation in &reassociations {
+            let old_record = self.0.remove(&reassociation.old_id).unwrap_or_bug();
+            let text = if old_record.text.starts_with(UPDATE_PREFIX) {
+                old_record.text
+            } else {
+                format!("{UPDATE_PREFIX}{}", old_record.text)
+            };
+            self.0.insert(
+                reassociation.new_id.clone(),
+                StringsFileRecord {
+                    id: reassociation.new_id.clone(),
+                    text,
+                    ..old_record
+                },
+            );
+        }
+        reassociations
+    }
+
     pub(crate) fn from_string_table(
         language: impl Into<Language>,
         string_table: impl IntoIterator<Item = (LineId, StringInfo)>,
@@ -207,6 +265,10 @@ impl StringsFile {
             .find(|record| &record.language != expected_language)
     }
 
+    pub(crate) fn get(&self, id: &LineId) -> Option<&StringsFileRecord> {
+        self.0.get(id)
+    }
+
     pub(crate) fn iter(&self) -> impl Iterator<Item = (&LineId, &StringsFileRecord)> {
         self.0.iter()
     }
@@ -286,18 +348,27 @@ pub(crate) struct StringsFileRecord {
     pub(crate) comment: String,
 }
 
+impl StringsFileRecord {
+    /// Returns `true` if the text is still the one copied from the base language, i.e. it was never translated.
+    pub(crate) fn is_untranslated(&self) -> bool {
+        Lock::compute_from(&self.text) == self.lock
+    }
+
+    /// Returns `true` if the text was translated from a different version of `base_text`
+    /// or was marked as outdated by a previous update of the strings file.
+    pub(crate) fn is_outdated(&self, base_text: &str) -> bool {
+        self.lock != Lock::compute_from(base_text) || self.text.starts_with(UPDATE_PREFIX)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
 #[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub(crate) struct Lock(String);
 
 impl Lock {
-    /// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
+    /// See [`compute_lock`].
     pub(crate) fn compute_from(text: &str) -> Self {
-        const MAX_CHARS: usize = 8;
-        let hash = Sha256::digest(text);
-        let hex = format!("{hash:x}");
-        let lock = hex.chars().take(MAX_CHARS).collect();
-        Self(lock)
+        Self(compute_lock(text))
     }
 }
 
@@ -328,6 +399,93 @@ fn read_comments(metadata: impl IntoIterator<Item = String>) -> String {
 mod test {
     use super::*;
 
+    fn translated_strings_file() -> StringsFile {
+        // "Hello" and "How are you?" were translated, "Bye" was not.
+        let csv = "\
+language,id,text,file,node,line_number,lock,comment
+de-CH,line:1,Hallo,main.yarn,Start,3,185f8db3,
+de-CH,line:2,Wie gehts?,main.yarn,Start,4,00000000,
+de-CH,line:3,Bye,main.yarn,Start,5,12890122,
+";
+        StringsFile::from_csv(csv.as_bytes()).unwrap()
+    }
+
+    fn base_strings_file(lines: &[(&str, &str, &str, usize)]) -> StringsFile {
+        let string_table = lines.iter().map(|(id, text, node_name, line_number)| {
+            let string_info = StringInfo {
+                text: (*text).to_owned(),
+                node_name: (*node_name).to_owned(),
+                line_number: *line_number,
+                file_name: "main.yarn".to_owned(),
+                is_implicit_tag: false,
+                metadata: vec![],
+            };
+            (LineId::from(*id), string_info)
+        });
+        StringsFile::from_string_table("de-CH", string_table).unwrap()
+    }
+
+    #[test]
+    fn reassociates_retagged_line_with_same_text() {
+        let mut strings_file = translated_strings_file();
+        let other = base_strings_file(&[
+            ("line:10", "Hello", "Start", 3),
+            ("line:2", "How are you?", "Start", 4),
+            ("line:3", "Bye", "Start", 5),
+        ]);
+
+        let reassociations = strings_file.reassociate_translations(&other, &HashMap::default());
+        assert_eq!(reassociations.len(), 1);
+        assert_eq!(reassociations[0].old_id, LineId::from("line:1"));
+        assert_eq!(reassociations[0].new_id, LineId::from("line:10"));
+
+        assert!(strings_file.update_file(other));
+        assert!(strings_file.get(&LineId::from("line:1")).is_none());
+        let record = strings_file.get(&LineId::from("line:10")).unwrap();
+        assert_eq!(record.text, "(NEEDS UPDATE) Hallo");
+        assert!(record.is_outdated("Hello"));
+    }
+
+    #[test]
+    fn reassociates_edited_line_with_known_previous_text() {
+        let mut strings_file = translated_strings_file();
+        let other = base_strings_file(&[
+            ("line:1", "Hello", "Start", 3),
+            ("line:20", "How are you today?", "Start", 4),
+            ("line:3", "Bye", "Start", 5),
+        ]);
+
+        let unknown_text = strings_file.reassociate_translations(&other, &HashMap::default());
+        assert!(unknown_text.is_empty());
+
+        let previous_base_texts =
+            HashMap::from_iter([(LineId::from("line:2"), "How are you?".to_owned())]);
+        let reassociations = strings_file.reassociate_translations(&other, &previous_base_texts);
+        assert_eq!(reassociations.len(), 1);
+        assert_eq!(reassociations[0].new_id, LineId::from("line:20"));
+
+        strings_file.update_file(other);
+        let record = strings_file.get(&LineId::from("line:20")).unwrap();
+        assert_eq!(record.text, "(NEEDS UPDATE) Wie gehts?");
+    }
+
+    #[test]
+    fn does_not_reassociate_untranslated_or_unrelated_lines() {
+        let mut strings_file = translated_strings_file();
+        let other = base_strings_file(&[
+            ("line:10", "Hello", "End", 3),
+            ("line:2", "How are you?", "Start", 4),
+            ("line:30", "Bye", "Start", 5),
+        ]);
+        let previous_base_texts = HashMap::from_iter([
+            (LineId::from("line:1"), "Hello".to_owned()),
+            (LineId::from("line:3"), "Bye".to_owned()),
+        ]);
+
+        let reassociations = strings_file.reassociate_translations(&other, &previous_base_texts);
+        assert!(reassociations.is_empty());
+    }
+
     #[test]
     fn combines_comments_without_change() {
         let old = "Foo, Line metadata: Bar";
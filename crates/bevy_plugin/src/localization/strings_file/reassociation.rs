@@ -0,0 +1,100 @@
+use super::asset::StringsFileRecord;
+use crate::prelude::*;
+use bevy::platform::collections::{HashMap, HashSet};
+
+/// A proposal to carry the translation of a line that was removed from a Yarn file over to a line that was added to the same node.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Reassociation {
+    pub(crate) old_id: LineId,
+    pub(crate) new_id: LineId,
+    /// How confident we are that both lines are the same, between `0.0` and `1.0`.
+    pub(crate) score: f32,
+}
+
+/// Pairs of lines that score below this are not considered to be the same line.
+const MIN_SCORE: f32 = 0.6;
+const TEXT_WEIGHT: f32 = 0.8;
+const POSITION_WEIGHT: f32 = 1.0 - TEXT_WEIGHT;
+
+/// Matches `removed` records against `added` records of the same file and node, preferring pairs with similar text that are close to each other.
+///
+/// The text of a removed line in the base language is only known through its lock, so unless `previous_base_texts` contains it,
+/// only lines with exactly the same text can be matched.
+pub(crate) fn find_reassociations<'a>(
+    removed: impl IntoIterator<Item = &'a StringsFileRecord>,
+    added: impl IntoIterator<Item = &'a StringsFileRecord>,
+    previous_base_texts: &HashMap<LineId, String>,
+) -> Vec<Reassociation> {
+    let added: Vec<_> = added.into_iter().collect();
+    let mut candidates: Vec<_> = removed
+        .into_iter()
+        .flat_map(|old| {
+            added
+                .iter()
+                .filter(move |new| old.file == new.file && old.node == new.node)
+                .map(move |new| Reassociation {
+                    old_id: old.id.clone(),
+                    new_id: new.id.clone(),
+                    score: score(old, new, previous_base_texts),
+                })
+        })
+        .filter(|candidate| candidate.score >= MIN_SCORE)
+        .collect();
+    candidates.sort_by(|lhs, rhs| {
+        rhs.score
+            .total_cmp(&lhs.score)
+            .then_with(|| lhs.old_id.0.cmp(&rhs.old_id.0))
+            .then_with(|| lhs.new_id.0.cmp(&rhs.new_id.0))
+    });
+
+    let mut used_old_ids: HashSet<LineId> = HashSet::default();
+    let mut used_new_ids: HashSet<LineId> = HashSet::default();
+    let mut reassociations = Vec::new();
+    for candidate in candidates {
+        if used_old_ids.contains(&candidate.old_id) || used_new_ids.contains(&candidate.new_id) {
+            continue;
+        }
+        used_old_ids.insert(candidate.old_id.clone());
+        used_new_ids.insert(candidate.new_id.clone());
+        reassociations.push(candidate);
+    }
+    reassociations
+}
+
+fn score(
+    old: &StringsFileRecord,
+    new: &StringsFileRecord,
+    previous_base_texts: &HashMap<LineId, String>,
+) -> f32 {
+    let text_similarity = if old.lock == new.lock {
+        1.0
+    } else if let Some(previous_text) = previous_base_texts.get(&old.id) {
+        text_similarity(previous_text, &new.text)
+    } else {
+        0.0
+    };
+    let line_distance = old.line_number.abs_diff(new.line_number);
+    let position_similarity = 1.0 / (1.0 + line_distance as f32);
+    TEXT_WEIGHT * text_similarity + POSITION_WEIGHT * position_similarity
+}
+
+fn text_similarity(lhs: &str, rhs: &str) -> f32 {
+    let max_len = lhs.chars().count().max(rhs.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein_distance(lhs, rhs) as f32 / max_len as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_text_similarity() {
+        assert_eq!(1.0, text_similarity("Hello", "Hello"));
+        assert_eq!(0.75, text_similarity("Hell", "Hall"));
+        assert_eq!(0.0, text_similarity("abc", "xyz"));
+        assert_eq!(1.0, text_similarity("", ""));
+    }
+}
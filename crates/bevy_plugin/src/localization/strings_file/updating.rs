@@ -34,6 +34,7 @@ fn update_all_strings_files_for_string_table(
     project: Res<YarnProject>,
     mut languages_to_handles: Local<HashMap<Language, Handle<StringsFile>>>,
     mut expected_file_names: Local<HashSet<String>>,
+    mut previous_base_texts: Local<HashMap<LineId, String>>,
     asset_root: Res<AssetRoot>,
 ) -> SystemResult {
     let localizations = project.localizations.as_ref().unwrap();
@@ -107,6 +108,18 @@ fn update_all_strings_files_for_string_table(
                     continue;
                 }
             };
+            if project.reassociate_translations {
+                for reassociation in
+                    strings_file.reassociate_translations(&new_strings_file, &previous_base_texts)
+                {
+                    info!(
+                        "Carried the translation of \"{}\" over to \"{}\" in \"{}\" (lang: {language}). It is marked as needing an update so that it gets reviewed.",
+                        reassociation.old_id,
+                        reassociation.new_id,
+                        strings_file_path.display(),
+                    );
+                }
+            }
             if strings_file.update_file(new_strings_file) {
                 dirty_paths.insert((strings_file_handle, strings_file_path));
 
@@ -116,6 +129,12 @@ fn update_all_strings_files_for_string_table(
                 );
             }
         }
+        previous_base_texts.extend(
+            string_table
+                .into_iter()
+                .filter(|(_id, string_info)| !string_info.is_implicit_tag)
+                .map(|(id, string_info)| (id, string_info.text)),
+        );
     }
     languages_to_handles.clear();
     for (handle, path) in &dirty_paths {
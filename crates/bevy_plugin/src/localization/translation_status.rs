@@ -0,0 +1,316 @@
+use crate::prelude::*;
+use anyhow::{Result, bail};
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+pub(crate) fn translation_status_plugin(_app: &mut App) {}
+
+/// How far the translation of a [`Localization`] has progressed, as reported by [`translation_status`].
+/// Creating this does not modify any files, so unlike [`DevelopmentFileGeneration::Full`], it can be used anywhere, e.g. in a CI job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationStatus {
+    /// The language of the translation.
+    pub language: Language,
+    /// The lines that are not in the strings file of the translation. Ordered by file and line number.
+    ///
+    /// Lines that are in the strings file count as translated as long as their `lock` matches the base language text,
+    /// even if their text is the same as in the base language, as is common for names or interjections.
+    pub missing_lines: Vec<LineId>,
+    /// The lines whose translation was made for a different version of the base language text, as detected by their `lock`.
+    /// Ordered by file and line number.
+    pub outdated_lines: Vec<LineId>,
+    /// The lines in the strings file of the translation that no longer exist in the Yarn files. Ordered by file and line number.
+    pub orphaned_lines: Vec<LineId>,
+    /// The completion of the translation per Yarn file.
+    pub files: BTreeMap<String, TranslationCompletion>,
+    /// The completion of the translation per node.
+    pub nodes: BTreeMap<String, TranslationCompletion>,
+}
+
+/// The number of lines of a set of lines that are translated and up to date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TranslationCompletion {
+    /// The number of lines that are translated and not outdated.
+    pub translated: usize,
+    /// The number of lines in total.
+    pub total: usize,
+}
+
+impl TranslationCompletion {
+    /// Returns the percentage of translated lines, between `0.0` and `100.0`. Is `100.0` if there are no lines.
+    pub fn percentage(&self) -> f32 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.translated as f32 / self.total as f32 * 100.0
+        }
+    }
+
+    fn add(&mut self, is_translated: bool) {
+        self.total += 1;
+        if is_translated {
+            self.translated += 1;
+        }
+    }
+}
+
+impl Display for TranslationCompletion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1}% ({}/{})",
+            self.percentage(),
+            self.translated,
+            self.total
+        )
+    }
+}
+
+impl TranslationStatus {
+    /// Returns the completion of the translation over all Yarn files.
+    pub fn completion(&self) -> TranslationCompletion {
+        self.files
+            .values()
+            .fold(TranslationCompletion::default(), |acc, completion| {
+                TranslationCompletion {
+                    translated: acc.translated + completion.translated,
+                    total: acc.total + completion.total,
+                }
+            })
+    }
+
+    /// Returns `true` if all lines are translated and up to date and the strings file contains no orphaned lines.
+    pub fn is_complete(&self) -> bool {
+        self.missing_lines.is_empty()
+            && self.outdated_lines.is_empty()
+            && self.orphaned_lines.is_empty()
+    }
+
+    pub(crate) fn compute(
+        language: Language,
+        string_table: &HashMap<LineId, StringInfo>,
+        strings_file: &StringsFile,
+    ) -> Self {
+        let mut lines: Vec<_> = string_table.iter().collect();
+        lines.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+            lhs.file_name
+                .cmp(&rhs.file_name)
+                .then(lhs.line_number.cmp(&rhs.line_number))
+                .then_with(|| lhs_id.0.cmp(&rhs_id.0))
+        });
+
+        let mut missing_lines = Vec::new();
+        let mut outdated_lines = Vec::new();
+        let mut files: BTreeMap<String, TranslationCompletion> = BTreeMap::new();
+        let mut nodes: BTreeMap<String, TranslationCompletion> = BTreeMap::new();
+        for (id, string_info) in lines {
+            let is_translated = match strings_file.get(id) {
+                Some(record) => {
+                    let is_outdated = record.is_outdated(&string_info.text);
+                    if is_outdated {
+                        outdated_lines.push(id.clone());
+                    }
+                    !is_outdated
+                }
+                _ => {
+                    missing_lines.push(id.clone());
+                    false
+                }
+            };
+            files
+                .entry(string_info.file_name.clone())
+                .or_default()
+                .add(is_translated);
+            nodes
+                .entry(string_info.node_name.clone())
+                .or_default()
+                .add(is_translated);
+        }
+
+        let mut orphaned_records: Vec<_> = strings_file
+            .records()
+            .filter(|record| !string_table.contains_key(&record.id))
+            .collect();
+        orphaned_records.sort_by(|lhs, rhs| {
+            lhs.file
+                .cmp(&rhs.file)
+                .then(lhs.line_number.cmp(&rhs.line_number))
+                .then_with(|| lhs.id.0.cmp(&rhs.id.0))
+        });
+        let orphaned_lines = orphaned_records
+            .into_iter()
+            .map(|record| record.id.clone())
+            .collect();
+
+        Self {
+            language,
+            missing_lines,
+            outdated_lines,
+            orphaned_lines,
+            files,
+            nodes,
+        }
+    }
+}
+
+impl Display for TranslationStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} translated, {} missing, {} outdated, {} orphaned",
+            self.language,
+            self.completion(),
+            self.missing_lines.len(),
+            self.outdated_lines.len(),
+            self.orphaned_lines.len(),
+        )?;
+        for (file, completion) in &self.files {
+            writeln!(f, "  {file}: {completion}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the string table of a [`Compilation`] against the strings file of every translation in the [`Localizations`]
+/// and reports which lines are missing, outdated or orphaned, without modifying any files.
+/// The paths of the strings files are resolved relative to `asset_root`, which is usually the `assets` folder of your game.
+/// A strings file that does not exist yet is treated as if it contained no translations.
+///
+/// This does not require a running Bevy app, so it can be called from a command-line tool or a test:
+///
+/// ```no_run
+/// # use bevy_yarnspinner::prelude::*;
+/// # use yarnspinner::prelude::YarnCompiler;
+/// # fn main() -> bevy_yarnspinner::Result<()> {
+/// let compilation = YarnCompiler::new()
+///     .read_file("assets/dialogue/main.yarn")
+///     .compile()?;
+/// let localizations = Localizations {
+///     base_localization: "en-US".into(),
+///     translations: vec!["de-CH".into()],
+/// };
+/// for status in translation_status(&compilation, &localizations, "assets")? {
+///     print!("{status}");
+///     for line_id in &status.outdated_lines {
+///         println!("  needs update: {line_id}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Inside a Bevy app, you can use [`YarnProject::translation_status`] instead.
+pub fn translation_status(
+    compilation: &Compilation,
+    localizations: &Localizations,
+    asset_root: impl AsRef<Path>,
+) -> Result<Vec<TranslationStatus>> {
+    let asset_root = asset_root.as_ref();
+    localizations
+        .translations
+        .iter()
+        .map(|localization| {
+            let path = asset_root.join(&localization.strings_file);
+            let strings_file = if path.exists() {
+                StringsFile::read_asset(&path)?
+            } else {
+                StringsFile::default()
+            };
+            if let Some(record) = strings_file.get_offending_language(&localization.language) {
+                bail!(
+                    "Expected strings file at {path} to only contain language {expected_language}, but its entry with id \"{id}\" is for language {actual_language}.",
+                    path = path.display(),
+                    expected_language = localization.language,
+                    id = record.id,
+                    actual_language = record.language,
+                );
+            }
+            Ok(TranslationStatus::compute(
+                localization.language.clone(),
+                &compilation.string_table,
+                &strings_file,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_table() -> HashMap<LineId, StringInfo> {
+        [
+            ("line:1", "Hello", "Start", 3),
+            ("line:2", "How are you?", "Start", 4),
+            ("line:3", "Fine, thanks", "Start", 5),
+            ("line:4", "Bye", "End", 10),
+        ]
+        .into_iter()
+        .map(|(id, text, node_name, line_number)| {
+            let string_info = StringInfo {
+                text: text.to_owned(),
+                node_name: node_name.to_owned(),
+                line_number,
+                file_name: "main.yarn".to_owned(),
+                is_implicit_tag: false,
+                metadata: vec![],
+            };
+            (LineId::from(id), string_info)
+        })
+        .collect()
+    }
+
+    fn strings_file() -> StringsFile {
+        // line:1 is translated, line:2 was translated before its text changed,
+        // line:3 is translated to the same text, line:4 is missing, and line:5 was deleted from the Yarn file.
+        let csv = "\
+language,id,text,file,node,line_number,lock,comment
+de-CH,line:1,Hallo,main.yarn,Start,3,185f8db3,
+de-CH,line:2,Wie gehts?,main.yarn,Start,4,00000000,
+de-CH,line:3,\"Fine, thanks\",main.yarn,Start,5,e8102ffc,
+de-CH,line:5,Tschau,main.yarn,End,11,12345678,
+";
+        StringsFile::from_csv(csv.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn reports_missing_outdated_and_orphaned_lines() {
+        let status = TranslationStatus::compute("de-CH".into(), &string_table(), &strings_file());
+        assert_eq!(status.missing_lines, vec![LineId::from("line:4")]);
+        assert_eq!(status.outdated_lines, vec![LineId::from("line:2")]);
+        assert_eq!(status.orphaned_lines, vec![LineId::from("line:5")]);
+        assert!(!status.is_complete());
+    }
+
+    #[test]
+    fn reports_completion_per_file_and_node() {
+        let status = TranslationStatus::compute("de-CH".into(), &string_table(), &strings_file());
+        assert_eq!(
+            status.files["main.yarn"],
+            TranslationCompletion {
+                translated: 2,
+                total: 4
+            }
+        );
+        assert_eq!(
+            status.nodes["Start"],
+            TranslationCompletion {
+                translated: 2,
+                total: 3
+            }
+        );
+        assert_eq!(status.nodes["End"].percentage(), 0.0);
+        assert_eq!(status.completion().percentage(), 50.0);
+    }
+
+    #[test]
+    fn treats_missing_strings_file_as_untranslated() {
+        let status =
+            TranslationStatus::compute("de-CH".into(), &string_table(), &StringsFile::default());
+        assert_eq!(status.missing_lines.len(), 4);
+        assert!(status.orphaned_lines.is_empty());
+        assert_eq!(status.completion().translated, 0);
+    }
+}
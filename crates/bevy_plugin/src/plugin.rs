@@ -151,6 +151,23 @@ impl YarnSpinnerPlugin {
             .with_development_file_generation(development_file_generation);
         self
     }
+
+    /// Sets whether translations should be carried over to lines whose ID changed, e.g. because a writer deleted a line and typed it again
+    /// or because a Yarn file was tagged anew. Only has an effect when using [`DevelopmentFileGeneration::Full`].
+    ///
+    /// When a line disappears from a Yarn file while a new line appears in the same node, the two are compared by text and position.
+    /// If they are similar enough, the translation of the old line is moved to the new line in the strings files and marked with
+    /// "NEEDS UPDATE", so that a translator can review it. Edited lines can only be matched if their old text was seen while the game was running,
+    /// i.e. when editing Yarn files with hot reloading. Otherwise, only lines whose text is unchanged are matched.
+    ///
+    /// Defaults to `false`, i.e. translations of lines that get a new ID are dropped.
+    #[must_use]
+    pub fn with_translation_reassociation(mut self, reassociate_translations: bool) -> Self {
+        self.project = self
+            .project
+            .with_translation_reassociation(reassociate_translations);
+        self
+    }
 }
 
 impl Plugin for YarnSpinnerPlugin {
@@ -198,6 +215,9 @@ impl YarnApp for App {
             .register_type::<CompilerError>()
             .register_type::<yarnspinner::compiler::Diagnostic>()
             .register_type::<yarnspinner::compiler::DiagnosticSeverity>()
+            .register_type::<yarnspinner::compiler::DiagnosticCode>()
+            .register_type::<yarnspinner::compiler::RelatedLocation>()
+            .register_type::<yarnspinner::compiler::SuggestedReplacement>()
             .register_type::<yarnspinner::compiler::DebugInfo>()
             .register_type::<LineInfo>()
             .register_type::<yarnspinner::compiler::Declaration>()
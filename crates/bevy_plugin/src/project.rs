@@ -40,6 +40,7 @@ pub struct YarnProject {
     pub(crate) metadata: HashMap<LineId, Vec<String>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) reassociate_translations: bool,
 }
 
 impl YarnProject {
@@ -58,6 +59,19 @@ impl YarnProject {
         self.localizations.as_ref()
     }
 
+    /// Reports how far each translation in the [`Localizations`] of this project has progressed by comparing the lines of the project
+    /// against the strings files found in `asset_root`, which is usually the `assets` folder of your game. No files are modified.
+    /// Returns an empty list if the project has no [`Localizations`]. See [`translation_status`] for details.
+    pub fn translation_status(
+        &self,
+        asset_root: impl AsRef<std::path::Path>,
+    ) -> crate::Result<Vec<TranslationStatus>> {
+        let Some(localizations) = self.localizations.as_ref() else {
+            return Ok(Vec::new());
+        };
+        translation_status(&self.compilation, localizations, asset_root)
+    }
+
     /// Constructs a [`DialogueRunner`] from this project using all defaults of [`DialogueRunnerBuilder`] .
     /// This is a convenience method for calling [`DialogueRunnerBuilder::build`] on an unconfigured builder returned by [`YarnProject::build_dialogue_runner`].
     pub fn create_dialogue_runner(&self, commands: &mut Commands) -> DialogueRunner {
@@ -101,6 +115,7 @@ pub struct LoadYarnProjectEvent {
     pub(crate) localizations: Option<Localizations>,
     pub(crate) yarn_files: HashSet<YarnFileSource>,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) reassociate_translations: bool,
 }
 
 #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
@@ -110,6 +125,7 @@ impl Default for LoadYarnProjectEvent {
             localizations: None,
             yarn_files: HashSet::from_iter([YarnFileSource::Folder(DEFAULT_ASSET_DIR.into())]),
             development_file_generation: default(),
+            reassociate_translations: false,
         }
     }
 }
@@ -137,6 +153,7 @@ impl LoadYarnProjectEvent {
             localizations: None,
             yarn_files,
             development_file_generation: default(),
+            reassociate_translations: false,
         }
     }
 
@@ -182,6 +199,13 @@ impl LoadYarnProjectEvent {
         self.development_file_generation = development_file_generation;
         self
     }
+
+    /// See [`YarnSpinnerPlugin::with_translation_reassociation`].
+    #[must_use]
+    pub fn with_translation_reassociation(mut self, reassociate_translations: bool) -> Self {
+        self.reassociate_translations = reassociate_translations;
+        self
+    }
 }
 
 impl<T, U> From<T> for LoadYarnProjectEvent
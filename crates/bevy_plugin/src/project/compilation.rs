@@ -7,11 +7,13 @@ use anyhow::{Result, anyhow, bail};
 use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use std::fmt::Debug;
+use yarnspinner::compiler::{ColorChoice, CompilationCache, DiagnosticRenderer};
 
 pub(crate) fn project_compilation_plugin(app: &mut App) {
     app.register_type::<YarnFilesToLoad>()
         .init_resource::<YarnFilesToLoad>()
         .init_resource::<YarnFilesBeingLoaded>()
+        .init_resource::<YarnCompilationCache>()
         .add_message::<RecompileLoadedYarnFilesEvent>()
         .add_systems(
             Update,
@@ -40,6 +42,7 @@ pub(crate) struct YarnProjectConfigToLoad {
     pub(crate) localizations: Option<Option<Localizations>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) reassociate_translations: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Resource, Reflect)]
@@ -50,6 +53,10 @@ pub(crate) struct YarnFilesToLoad(pub(crate) HashSet<YarnFileSource>);
 #[reflect(Debug, Resource, Default, PartialEq)]
 pub(crate) struct YarnFilesBeingLoaded(pub(crate) HashSet<Handle<YarnFile>>);
 
+/// Remembers the results of the last compilation so that hot reloading only recompiles the Yarn files that changed.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct YarnCompilationCache(pub(crate) CompilationCache);
+
 fn load_project(
     mut commands: Commands,
     mut events: ResMut<Messages<LoadYarnProjectEvent>>,
@@ -81,6 +88,7 @@ fn load_project(
             localizations: Some(event.localizations),
             watching_for_changes: is_watching_for_changes.0,
             development_file_generation: event.development_file_generation,
+            reassociate_translations: event.reassociate_translations,
         });
         commands.insert_resource(YarnFilesToLoad(event.yarn_files));
         *already_loaded = true;
@@ -123,6 +131,7 @@ fn recompile_loaded_yarn_files(
     yarn_project: Option<ResMut<YarnProject>>,
     mut dialogue_runners: Query<&mut DialogueRunner>,
     mut events: ResMut<Messages<RecompileLoadedYarnFilesEvent>>,
+    mut compilation_cache: ResMut<YarnCompilationCache>,
 ) -> SystemResult {
     let Some(mut yarn_project) = yarn_project else {
         return Ok(());
@@ -132,6 +141,7 @@ fn recompile_loaded_yarn_files(
         &yarn_files,
         yarn_project.localizations.as_ref(),
         yarn_project.development_file_generation,
+        &mut compilation_cache.0,
     )?
     else {
         return Ok(());
@@ -178,6 +188,7 @@ fn compile_loaded_yarn_files(
     yarn_project_config_to_load: Option<Res<YarnProjectConfigToLoad>>,
     asset_server: Res<AssetServer>,
     asset_root: Res<AssetRoot>,
+    mut compilation_cache: ResMut<YarnCompilationCache>,
 ) -> SystemResult {
     if yarn_files_being_loaded.is_changed() {
         *dirty = true;
@@ -208,6 +219,7 @@ fn compile_loaded_yarn_files(
         &yarn_files,
         localizations,
         development_file_generation,
+        &mut compilation_cache.0,
     )?
     else {
         return Ok(());
@@ -254,6 +266,7 @@ fn compile_loaded_yarn_files(
         asset_server: SkipDebug(asset_server.clone()),
         watching_for_changes: yarn_project_config_to_load.watching_for_changes,
         development_file_generation,
+        reassociate_translations: yarn_project_config_to_load.reassociate_translations,
         metadata,
     });
 
@@ -274,6 +287,7 @@ fn compile_yarn_files(
     yarn_files: &Res<Assets<YarnFile>>,
     localizations: Option<&Localizations>,
     development_file_generation: DevelopmentFileGeneration,
+    compilation_cache: &mut CompilationCache,
 ) -> Result<Option<Compilation>> {
     let yarn_files = yarn_file_handles
         .iter()
@@ -297,7 +311,24 @@ fn compile_yarn_files(
             );
         }
     }
-    let inner_yarn_files = yarn_files.map(|file| file.file.clone());
-    let compilation = YarnCompiler::new().add_files(inner_yarn_files).compile()?;
+    let mut inner_yarn_files: Vec<_> = yarn_files.map(|file| file.file.clone()).collect();
+    // The cache can only be reused if the files are passed to the compiler in the same order every time
+    inner_yarn_files.sort_by(|lhs, rhs| lhs.file_name.cmp(&rhs.file_name));
+    // The diagnostics end up in logs and errors, which can't display ANSI colors
+    let renderer = DiagnosticRenderer::new()
+        .with_sources(&inner_yarn_files)
+        .with_color_choice(ColorChoice::Never);
+    let compilation = YarnCompiler::new()
+        .add_files(inner_yarn_files.iter().cloned())
+        .compile_incremental(compilation_cache)
+        .map_err(|error| {
+            anyhow!(
+                "Failed to compile Yarn files:\n{}",
+                renderer.render_all(&error.0)
+            )
+        })?;
+    for warning in &compilation.warnings {
+        warn!("{}", renderer.render(warning));
+    }
     Ok(Some(compilation))
 }
@@ -0,0 +1,365 @@
+//! Conversion of the [`MarkupAttribute`]s of a line into styled [`TextSpan`]s.
+
+use crate::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use core::cmp::Reverse;
+use unicode_segmentation::UnicodeSegmentation;
+use yarnspinner::runtime::CHARACTER_ATTRIBUTE;
+
+/// Changes the [`TextFont`] and [`TextColor`] of the text covered by a markup attribute, e.g. the `world` in `Hello [b]world[/b]!`.
+/// Fields that are `None` keep the value of the surrounding text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkupStyle {
+    /// The font to use instead of [`TextFont::font`].
+    pub font: Option<Handle<Font>>,
+    /// The font size to use instead of [`TextFont::font_size`].
+    pub font_size: Option<f32>,
+    /// The color to use instead of [`TextColor`].
+    pub color: Option<Color>,
+}
+
+impl MarkupStyle {
+    /// Creates a style that does not change anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`MarkupStyle::font`].
+    #[must_use]
+    pub fn with_font(mut self, font: Handle<Font>) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets [`MarkupStyle::font_size`].
+    #[must_use]
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Sets [`MarkupStyle::color`].
+    #[must_use]
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    fn apply(&self, font: &mut TextFont, color: &mut TextColor) {
+        if let Some(handle) = &self.font {
+            font.font = handle.clone();
+        }
+        if let Some(font_size) = self.font_size {
+            font.font_size = font_size;
+        }
+        if let Some(new_color) = self.color {
+            color.0 = new_color;
+        }
+    }
+}
+
+/// The styles used to create [`RichText`]: a base style for all text and a [`MarkupStyle`] per attribute name.
+/// Attributes without a style, such as `character`, do not change the look of the text they cover.
+///
+/// ## Example
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::prelude::*;
+/// let styles = MarkupStyles::new(TextFont::from_font_size(20.0), TextColor(Color::WHITE))
+///     .with_style("b", MarkupStyle::new().with_color(Color::srgb(1.0, 0.84, 0.0)))
+///     .with_style("shout", MarkupStyle::new().with_font_size(28.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkupStyles {
+    /// The font of text that is not covered by any attribute with a style.
+    pub base_font: TextFont,
+    /// The color of text that is not covered by any attribute with a style.
+    pub base_color: TextColor,
+    /// The styles for attribute names, e.g. `b` for `[b]`.
+    pub styles: HashMap<String, MarkupStyle>,
+}
+
+impl MarkupStyles {
+    /// Creates styles that render all text in the given font and color.
+    pub fn new(base_font: TextFont, base_color: TextColor) -> Self {
+        Self {
+            base_font,
+            base_color,
+            styles: HashMap::default(),
+        }
+    }
+
+    /// Sets the style for all attributes named `attribute_name`, replacing any previous style for it.
+    #[must_use]
+    pub fn with_style(mut self, attribute_name: impl Into<String>, style: MarkupStyle) -> Self {
+        self.styles.insert(attribute_name.into(), style);
+        self
+    }
+}
+
+/// A piece of [`RichText`] that has the same style throughout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RichTextSegment {
+    /// The text of this segment.
+    pub text: String,
+    /// The font of this segment.
+    pub font: TextFont,
+    /// The color of this segment.
+    pub color: TextColor,
+}
+
+impl RichTextSegment {
+    /// The components needed to spawn this segment as a child of a `Text` or `Text2d` entity.
+    pub fn to_span(&self) -> (TextSpan, TextFont, TextColor) {
+        (TextSpan(self.text.clone()), self.font.clone(), self.color)
+    }
+
+    fn grapheme_count(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+}
+
+/// The text of a line split into [`RichTextSegment`]s according to its [`MarkupAttribute`]s and a set of [`MarkupStyles`].
+///
+/// Attributes may overlap or be nested. Text covered by multiple attributes gets the styles of all of them,
+/// with attributes that start later, or are shorter if they start at the same position, taking precedence.
+/// The text is only ever split between graphemes, i.e. visible characters, so multibyte characters like `é` or emoji stay intact.
+/// Accordingly, [`MarkupAttribute::position`] and [`MarkupAttribute::length`] are read as grapheme counts, which is the unit the runtime documents for them.
+///
+/// ## Example
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::{events::PresentLine, prelude::*};
+/// /// Marks the entity with the `Text` or `Text2d` component that shows the dialogue.
+/// #[derive(Component)]
+/// struct DialogueText;
+///
+/// fn present_line(
+///     event: On<PresentLine>,
+///     mut commands: Commands,
+///     text: Single<Entity, With<DialogueText>>,
+/// ) {
+///     let styles = MarkupStyles::new(TextFont::default(), TextColor(Color::WHITE))
+///         .with_style("b", MarkupStyle::new().with_color(Color::srgb(1.0, 0.84, 0.0)));
+///     let rich_text = RichText::from_line_without_character_name(&event.line, &styles);
+///     commands
+///         .entity(*text)
+///         .despawn_related::<Children>()
+///         .with_children(|parent| {
+///             for span in rich_text.spans() {
+///                 parent.spawn(span);
+///             }
+///         });
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    segments: Vec<RichTextSegment>,
+}
+
+impl RichText {
+    /// Styles `text` according to `attributes`, whose positions must refer to `text`.
+    pub fn new(text: &str, attributes: &[MarkupAttribute], styles: &MarkupStyles) -> Self {
+        let graphemes: Vec<_> = text.graphemes(true).collect();
+        let mut styled_attributes: Vec<_> = attributes
+            .iter()
+            .filter_map(|attribute| {
+                let style = styles.styles.get(&attribute.name)?;
+                let start = attribute.position.min(graphemes.len());
+                let end = (attribute.position + attribute.length).min(graphemes.len());
+                Some((start, end, style))
+            })
+            .collect();
+        // Outer attributes are applied first so that the ones nested inside them win
+        styled_attributes.sort_by_key(|&(start, end, _)| (start, Reverse(end)));
+
+        let mut boundaries: Vec<_> = styled_attributes
+            .iter()
+            .flat_map(|&(start, end, _)| [start, end])
+            .chain([0, graphemes.len()])
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut segments: Vec<RichTextSegment> = Vec::new();
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let mut font = styles.base_font.clone();
+            let mut color = styles.base_color;
+            for (_, _, style) in
+                styled_attributes
+                    .iter()
+                    .filter(|&&(attribute_start, attribute_end, _)| {
+                        attribute_start <= start && end <= attribute_end
+                    })
+            {
+                style.apply(&mut font, &mut color);
+            }
+            let text = graphemes[start..end].concat();
+            match segments.last_mut() {
+                Some(last) if last.font == font && last.color == color => last.text.push_str(&text),
+                _ => segments.push(RichTextSegment { text, font, color }),
+            }
+        }
+        Self { segments }
+    }
+
+    /// Styles the [`LocalizedLine::text`] of `line`.
+    pub fn from_line(line: &LocalizedLine, styles: &MarkupStyles) -> Self {
+        Self::new(&line.text, &line.attributes, styles)
+    }
+
+    /// Styles the [`LocalizedLine::text_without_character_name`] of `line`.
+    pub fn from_line_without_character_name(line: &LocalizedLine, styles: &MarkupStyles) -> Self {
+        match line.attribute(CHARACTER_ATTRIBUTE) {
+            Some(character) => Self::from_line(&line.delete_range(character), styles),
+            None => Self::from_line(line, styles),
+        }
+    }
+
+    /// The segments of this text, in order. Adjacent segments always differ in style.
+    pub fn segments(&self) -> &[RichTextSegment] {
+        &self.segments
+    }
+
+    /// The unstyled text.
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+
+    /// The number of graphemes, i.e. visible characters, in this text.
+    pub fn grapheme_count(&self) -> usize {
+        self.segments
+            .iter()
+            .map(RichTextSegment::grapheme_count)
+            .sum()
+    }
+
+    /// The components of all segments, to be spawned as children of a `Text` or `Text2d` entity.
+    pub fn spans(&self) -> impl Iterator<Item = (TextSpan, TextFont, TextColor)> + '_ {
+        self.segments.iter().map(RichTextSegment::to_span)
+    }
+
+    /// Like [`RichText::spans`], but only the first `revealed_graphemes` graphemes are visible, which is useful for typewriter effects.
+    /// The rest of the text is still laid out, but with a transparent color, so that words don't jump to the next line while they are being written.
+    pub fn revealed_spans(
+        &self,
+        revealed_graphemes: usize,
+    ) -> Vec<(TextSpan, TextFont, TextColor)> {
+        let mut graphemes_left = revealed_graphemes;
+        let mut spans = Vec::with_capacity(self.segments.len() + 1);
+        for segment in &self.segments {
+            let grapheme_count = segment.grapheme_count();
+            if graphemes_left >= grapheme_count {
+                spans.push(segment.to_span());
+                graphemes_left -= grapheme_count;
+                continue;
+            }
+            let split = segment
+                .text
+                .grapheme_indices(true)
+                .nth(graphemes_left)
+                .map_or(segment.text.len(), |(index, _)| index);
+            let (revealed, hidden) = segment.text.split_at(split);
+            if !revealed.is_empty() {
+                spans.push((
+                    TextSpan(revealed.to_owned()),
+                    segment.font.clone(),
+                    segment.color,
+                ));
+            }
+            spans.push((
+                TextSpan(hidden.to_owned()),
+                segment.font.clone(),
+                TextColor(Color::NONE),
+            ));
+            graphemes_left = 0;
+        }
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(name: &str, position: usize, length: usize) -> MarkupAttribute {
+        MarkupAttribute {
+            name: name.to_owned(),
+            position,
+            length,
+            properties: HashMap::default(),
+            source_position: 0,
+        }
+    }
+
+    fn styles() -> MarkupStyles {
+        MarkupStyles::new(TextFont::from_font_size(10.0), TextColor(Color::WHITE))
+            .with_style("red", MarkupStyle::new().with_color(Color::BLACK))
+            .with_style("big", MarkupStyle::new().with_font_size(20.0))
+    }
+
+    fn summary(rich_text: &RichText) -> Vec<(&str, f32, Color)> {
+        rich_text
+            .segments()
+            .iter()
+            .map(|segment| {
+                (
+                    segment.text.as_str(),
+                    segment.font.font_size,
+                    segment.color.0,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_overlapping_attributes_by_graphemes() {
+        // "Héllo 👋🏽 wörld" with `red` over "llo 👋🏽" and `big` over "👋🏽 wö"
+        let text = "Héllo 👋🏽 wörld";
+        let attributes = [
+            attribute("red", 2, 5),
+            attribute("big", 6, 4),
+            attribute("character", 0, 2),
+        ];
+        let rich_text = RichText::new(text, &attributes, &styles());
+        assert_eq!(
+            summary(&rich_text),
+            [
+                ("Hé", 10.0, Color::WHITE),
+                ("llo ", 10.0, Color::BLACK),
+                ("👋🏽", 20.0, Color::BLACK),
+                (" wö", 20.0, Color::WHITE),
+                ("rld", 10.0, Color::WHITE),
+            ]
+        );
+        assert_eq!(rich_text.text(), text);
+        assert_eq!(rich_text.grapheme_count(), 13);
+    }
+
+    #[test]
+    fn reveals_graphemes_while_keeping_styles() {
+        let rich_text = RichText::new("ab👋🏽cd", &[attribute("red", 1, 2)], &styles());
+        let spans: Vec<_> = rich_text
+            .revealed_spans(2)
+            .into_iter()
+            .map(|(span, _, color)| (span.0, color.0))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("a".to_owned(), Color::WHITE),
+                ("b".to_owned(), Color::BLACK),
+                ("👋🏽".to_owned(), Color::NONE),
+                ("cd".to_owned(), Color::NONE),
+            ]
+        );
+        assert_eq!(rich_text.revealed_spans(5).len(), 3);
+    }
+}
@@ -143,6 +143,42 @@ fn loads_asset_from_translated_localization() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn loads_asset_from_fallback_language() -> Result<()> {
+    let mut app = App::new();
+    let mut world = World::default();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    let project = app.load_project();
+    let mut dialogue_runner = project
+        .build_dialogue_runner(&mut world.commands())
+        .add_asset_provider(AudioAssetProvider::new())
+        .build();
+    dialogue_runner
+        .set_asset_language("de-CH")
+        .start_node("Start");
+    app.world_mut().spawn(dialogue_runner);
+    app.load_lines();
+
+    let assets = app.dialogue_runner().get_assets_for_id("line:9");
+    assert_eq!(1, assets.len());
+    let asset: Handle<AudioSource> = assets.get_handle().unwrap();
+    let asset_server = app.world().resource::<AssetServer>();
+    let path = asset_server.get_path(asset.id()).unwrap();
+
+    // Note that this does not contain backslashes on Windows
+    assert_eq!("dialogue/en-US/9.ogg", path.path().to_str().unwrap());
+    Ok(())
+}
+
 #[test]
 #[should_panic]
 fn panics_on_invalid_language() {
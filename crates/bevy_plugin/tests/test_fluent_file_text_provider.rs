@@ -0,0 +1,72 @@
+#![cfg(feature = "fluent")]
+use anyhow::{Result, bail};
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn updates_text_when_fluent_file_is_modified() -> Result<()> {
+    let dir = tempdir()?;
+    let original_yarn_path = project_root_path().join("assets/lines_with_ids.yarn");
+    fs::copy(original_yarn_path, dir.path().join("lines_with_ids.yarn"))?;
+    let fluent_file_path = dir.path().join("dialogue/de-CH.ftl");
+    fs::create_dir_all(fluent_file_path.parent().unwrap())?;
+    fs::write(&fluent_file_path, "line-9 = Mann: Also gut.\n")?;
+
+    let mut app = App::new();
+    let mut world = World::default();
+
+    app.setup_default_plugins_for_path(dir.path()).add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    let project = app.load_project();
+    let text_provider =
+        FluentFileTextProvider::from_yarn_project(project).add_file("de-CH", "dialogue/de-CH.ftl");
+    let dialogue_runner = project
+        .build_dialogue_runner(&mut world.commands())
+        .with_text_provider(text_provider)
+        .build();
+    app.world_mut().spawn(dialogue_runner);
+    app.dialogue_runner_mut().set_text_language("de-CH");
+    app.load_lines();
+
+    let line_id = LineId("line:9".to_owned());
+    assert_eq!(
+        "Mann: Also gut.",
+        app.dialogue_runner()
+            .text_provider()
+            .get_text(&line_id)
+            .unwrap()
+    );
+
+    fs::write(&fluent_file_path, "line-9 = Mann: Na gut.\n")?;
+    app.world()
+        .resource::<AssetServer>()
+        .reload("dialogue/de-CH.ftl");
+
+    let start = Instant::now();
+    while app
+        .dialogue_runner()
+        .text_provider()
+        .get_text(&line_id)
+        .unwrap()
+        != "Mann: Na gut."
+    {
+        if start.elapsed().as_secs() > 2 {
+            bail!("The text provider did not pick up the modified Fluent file");
+        }
+        app.update();
+    }
+    Ok(())
+}
@@ -306,3 +306,42 @@ fn does_not_panic_on_missing_language_when_not_selected() {
 
     app.load_project();
 }
+
+#[test]
+fn reports_translation_status_without_modifying_files() -> anyhow::Result<()> {
+    let strings_file_path = project_root_path().join("assets/dialogue/de-CH.strings.csv");
+    let original_strings_file = fs::read_to_string(&strings_file_path)?;
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into(), "fr-FR".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    let statuses = app
+        .load_project()
+        .translation_status(project_root_path().join("assets"))?;
+    assert_eq!(2, statuses.len());
+
+    let german = &statuses[0];
+    assert_eq!(Language::from("de-CH"), german.language);
+    assert_eq!(vec![LineId::from("line:10")], german.missing_lines);
+    assert!(german.outdated_lines.is_empty());
+    assert!(german.orphaned_lines.is_empty());
+    assert_eq!(11, german.files["lines_with_ids.yarn"].translated);
+    assert_eq!(12, german.nodes["Start"].total);
+
+    let french = &statuses[1];
+    assert_eq!(12, french.missing_lines.len());
+    assert_eq!(0.0, french.completion().percentage());
+
+    assert_eq!(
+        original_strings_file,
+        fs::read_to_string(&strings_file_path)?
+    );
+    Ok(())
+}
@@ -165,3 +165,60 @@ fn loads_line_from_translated_language() {
         line
     );
 }
+
+#[test]
+fn loads_line_from_parent_language() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    app.dialogue_runner_mut().set_text_language("de-CH-1996");
+
+    app.load_lines();
+
+    let text_provider = app.dialogue_runner().text_provider();
+    let line_id = LineId("line:9".to_owned());
+    assert_eq!(
+        "Mann: Also gut. Ich glaub das zwar nicht, aber es kann ja nicht schaden, wenn ich mir was wünsche. Ich möchte wissen, wer ich bin.",
+        text_provider.get_text(&line_id).unwrap()
+    );
+    assert_eq!(
+        Some(Language::from("de-CH")),
+        text_provider.get_text_language(&line_id)
+    );
+}
+
+#[test]
+fn reports_language_of_fallback_line() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    app.dialogue_runner_mut().set_text_language("de-CH");
+
+    app.load_lines();
+
+    let text_provider = app.dialogue_runner().text_provider();
+    assert_eq!(
+        Some(Language::from("de-CH")),
+        text_provider.get_text_language(&LineId("line:9".to_owned()))
+    );
+    assert_eq!(
+        Some(Language::from("en-US")),
+        text_provider.get_text_language(&LineId("line:10".to_owned()))
+    );
+}
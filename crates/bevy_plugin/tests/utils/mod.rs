@@ -184,11 +184,7 @@ pub trait DialogueRunnerExt {
 
 impl DialogueRunnerExt for DialogueRunner {
     fn get_assets_for_id(&self, line_id: &str) -> LineAssets {
-        let line_id = UnderlyingYarnLine {
-            id: LineId(line_id.to_string()),
-            text: String::new(),
-            attributes: vec![],
-        };
+        let line_id = UnderlyingYarnLine::new(LineId(line_id.to_string()), "", vec![]);
         self.asset_providers()
             .map(|p| p.get_assets(&line_id))
             .collect()
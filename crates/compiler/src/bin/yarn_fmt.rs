@@ -0,0 +1,99 @@
+//! Formats Yarn files in place.
+//!
+//! Usage: `yarn_fmt [--check] <PATH>...`
+//!
+//! Every path may be a `.yarn` file or a directory, which is searched recursively for `.yarn` files.
+//! With `--check`, no files are written. Instead, the names of all files that are not formatted are printed
+//! and the process exits with a non-zero status if there are any, which makes it suitable for CI.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use yarnspinner_compiler::prelude::*;
+
+const USAGE: &str = "Usage: yarn_fmt [--check] <PATH>...";
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut paths = Vec::new();
+    for argument in std::env::args().skip(1) {
+        match argument.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(argument)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut yarn_files = Vec::new();
+    for path in &paths {
+        if let Err(error) = collect_yarn_files(path, &mut yarn_files) {
+            eprintln!("Failed to read {}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut success = true;
+    for path in yarn_files {
+        if let Err(error) = format_path(&path, check) {
+            eprintln!("{error}");
+            success = false;
+        }
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn format_path(path: &Path, check: bool) -> Result<(), String> {
+    let source = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let file = File {
+        file_name: path.display().to_string(),
+        source,
+    };
+    let formatted = format_file(&file).map_err(|error| {
+        DiagnosticRenderer::new()
+            .with_source(&file)
+            .render_all(&error.0)
+    })?;
+    if formatted == file.source {
+        return Ok(());
+    }
+    if check {
+        return Err(format!("{} is not formatted", file.file_name));
+    }
+    fs::write(path, formatted)
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+    println!("Formatted {}", file.file_name);
+    Ok(())
+}
+
+fn collect_yarn_files(path: &Path, yarn_files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        yarn_files.push(path.to_owned());
+        return Ok(());
+    }
+    let mut entries: Vec<_> = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir()
+            || entry
+                .extension()
+                .is_some_and(|extension| extension == "yarn")
+        {
+            collect_yarn_files(&entry, yarn_files)?;
+        }
+    }
+    Ok(())
+}
@@ -7,16 +7,19 @@ mod early_breaks;
 mod find_tracking_nodes;
 mod generate_code;
 mod get_declarations;
+mod optimize_program;
 mod parse_files;
 mod register_initial_variables;
 mod register_strings;
 mod resolve_deferred_type_diagnostic;
+mod validate_jump_destinations;
 mod validate_unique_node_names;
 
 pub(crate) use self::{
     add_initial_value_registrations::*, add_tracking_declarations::*, check_types::*,
     clean_up_diagnostics::*, create_declarations_for_tracking_nodes::*, early_breaks::*,
-    find_tracking_nodes::*, generate_code::*, get_declarations::*, parse_files::*,
-    register_initial_variables::*, register_strings::*, resolve_deferred_type_diagnostic::*,
+    find_tracking_nodes::*, generate_code::*, get_declarations::*, optimize_program::*,
+    parse_files::*, register_initial_variables::*, register_strings::*,
+    resolve_deferred_type_diagnostic::*, validate_jump_destinations::*,
     validate_unique_node_names::*,
 };
@@ -10,6 +10,12 @@ pub(crate) fn check_types(mut state: CompilationIntermediate) -> CompilationInte
         state
             .known_variable_declarations
             .extend(visitor.new_declarations.clone());
+        state
+            .file_results
+            .entry(file.name.clone())
+            .or_default()
+            .implicit_declarations
+            .clone_from(&visitor.new_declarations);
         state
             .derived_variable_declarations
             .extend(visitor.new_declarations);
@@ -7,13 +7,16 @@ pub(crate) fn find_tracking_nodes(mut state: CompilationIntermediate) -> Compila
     // determining the nodes we need to track visits on
     // this needs to be done before we finish up with declarations
     // so that any tracking variables are included in the compiled declarations
-    let mut tracking_nodes = HashSet::new();
-    let mut ignore_nodes = HashSet::new();
+    let mut tracking_nodes = state.external_files.tracking_nodes.clone();
+    let mut ignore_nodes = state.external_files.ignoring_nodes.clone();
     for (file, _) in &state.parsed_files {
         let mut visitor = NodeTrackingVisitor::new();
         visitor.visit(file.tree.as_ref());
-        tracking_nodes.extend(visitor.tracking_nodes);
-        ignore_nodes.extend(visitor.ignoring_nodes);
+        tracking_nodes.extend(visitor.tracking_nodes.iter().cloned());
+        ignore_nodes.extend(visitor.ignoring_nodes.iter().cloned());
+        let file_results = state.file_results.entry(file.name.clone()).or_default();
+        file_results.tracking_nodes = visitor.tracking_nodes;
+        file_results.ignoring_nodes = visitor.ignoring_nodes;
     }
     state.tracking_nodes = tracking_nodes.difference(&ignore_nodes).cloned().collect();
     state
@@ -22,12 +22,24 @@ pub(crate) fn generate_code(mut state: CompilationIntermediate) -> CompilationIn
             .parsed_files
             .iter()
             .map(|(file, known_types)| {
-                generate_code_for_file(
+                let result = generate_code_for_file(
                     &mut state.tracking_nodes,
                     known_types.clone(),
                     template.clone(),
                     file,
-                )
+                );
+                if let Ok(compilation) = &result {
+                    let file_results = state.file_results.entry(file.name.clone()).or_default();
+                    file_results.node_names = compilation
+                        .program
+                        .iter()
+                        .flat_map(|program| program.nodes.keys().cloned())
+                        .collect();
+                    file_results
+                        .code_generation_warnings
+                        .clone_from(&compilation.warnings);
+                }
+                result
             })
             .collect()
     };
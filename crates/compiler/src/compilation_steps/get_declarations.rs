@@ -13,6 +13,12 @@ pub(crate) fn get_declarations(mut state: CompilationIntermediate) -> Compilatio
         state
             .known_variable_declarations
             .extend(variable_declaration_visitor.new_declarations.clone());
+        state
+            .file_results
+            .entry(file.name.clone())
+            .or_default()
+            .explicit_declarations
+            .clone_from(&variable_declaration_visitor.new_declarations);
         state
             .derived_variable_declarations
             .extend(variable_declaration_visitor.new_declarations);
@@ -0,0 +1,473 @@
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use yarnspinner_core::prelude::*;
+
+/// Optimizes the instructions of every node in the compiled [`Program`] if it was enabled with [`Compiler::with_optimizations`].
+///
+/// The optimizations never change the observable behaviour of a program. They consist of
+/// - folding operators applied to constants, e.g. `1 + 2` is compiled to a single push of `3`,
+/// - replacing branches whose condition is a constant boolean with either nothing or an unconditional jump,
+/// - removing constants that are pushed only to be popped right away,
+/// - removing instructions that can never be executed,
+/// - removing jumps to the instruction that would be executed next anyway,
+/// - removing labels that are never jumped to.
+///
+/// The [`DebugInfo`] of each node is updated so that it keeps pointing to the right source positions.
+pub(crate) fn optimize_program(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    if !state.job.optimize {
+        return state;
+    }
+    let Some(Ok(compilation)) = state.result.as_mut() else {
+        return state;
+    };
+    let Some(program) = compilation.program.as_mut() else {
+        return state;
+    };
+
+    // Only the operators of the built-in types are folded, as they are guaranteed to be free of side effects.
+    let operators = Library::standard_library();
+    for (node_name, node) in program.nodes.iter_mut() {
+        let debug_info = compilation.debug_info.get_mut(node_name);
+        let mut optimizer = NodeOptimizer::new(node, debug_info.as_deref(), &operators);
+        optimizer.optimize();
+        optimizer.write_to(node, debug_info);
+    }
+    state
+}
+
+struct NodeOptimizer<'a> {
+    instructions: Vec<Instruction>,
+    positions: Vec<Option<Position>>,
+    labels: BTreeMap<String, usize>,
+    operators: &'a Library,
+}
+
+impl<'a> NodeOptimizer<'a> {
+    fn new(node: &Node, debug_info: Option<&DebugInfo>, operators: &'a Library) -> Self {
+        let positions = (0..node.instructions.len())
+            .map(|index| {
+                debug_info
+                    .and_then(|debug_info| debug_info.line_positions.get(&index))
+                    .copied()
+                    .flatten()
+            })
+            .collect();
+        let labels = node
+            .labels
+            .iter()
+            .map(|(label, &index)| (label.clone(), index as usize))
+            .collect();
+        Self {
+            instructions: node.instructions.clone(),
+            positions,
+            labels,
+            operators,
+        }
+    }
+
+    fn optimize(&mut self) {
+        // Every pass may open up opportunities for the others, e.g. folding `1 < 2` produces a constant branch,
+        // which in turn makes one of its clauses unreachable. Each pass only ever shrinks the node, so this terminates.
+        loop {
+            let changed = self.fold_constant_expressions()
+                | self.fold_constant_branches()
+                | self.remove_discarded_constants()
+                | self.eliminate_unreachable_instructions()
+                | self.remove_redundant_jumps()
+                | self.remove_unused_labels();
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    fn write_to(self, node: &mut Node, debug_info: Option<&mut DebugInfo>) {
+        if let Some(debug_info) = debug_info {
+            debug_info.line_positions = self.positions.into_iter().enumerate().collect();
+        }
+        node.instructions = self.instructions;
+        node.labels = self
+            .labels
+            .into_iter()
+            .map(|(label, index)| (label, index as i32))
+            .collect();
+    }
+
+    /// Replaces calls to operators whose operands are all constants with the result of the call.
+    fn fold_constant_expressions(&mut self) -> bool {
+        let mut changed = false;
+        while let Some((start, end, value)) = self.find_foldable_expression() {
+            self.instructions[start] = push_instruction(value);
+            let keep: Vec<_> = (0..self.instructions.len())
+                .map(|index| index <= start || index > end)
+                .collect();
+            self.retain(&keep);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Finds a sequence of instructions that pushes constants, pushes the number of parameters and then calls an operator.
+    /// Returns the indices of the first and last instruction of that sequence as well as the value it evaluates to.
+    fn find_foldable_expression(&self) -> Option<(usize, usize, YarnValue)> {
+        let jump_targets = self.jump_targets();
+        (0..self.instructions.len()).find_map(|call_index| {
+            let call = &self.instructions[call_index];
+            if op_code(call) != OpCode::CallFunc || call_index == 0 {
+                return None;
+            }
+            let function_name: String = call.read_operand(0);
+            // Operators are the only functions with a canonical name like `Number.Add`
+            if !function_name.contains('.') {
+                return None;
+            }
+            let function = self.operators.get(&function_name)?;
+
+            let count_instruction = &self.instructions[call_index - 1];
+            if op_code(count_instruction) != OpCode::PushFloat {
+                return None;
+            }
+            let parameter_count: usize = count_instruction.read_operand(0);
+            if parameter_count != function.parameter_types().len() || call_index <= parameter_count
+            {
+                return None;
+            }
+            let start = call_index - 1 - parameter_count;
+            // If anything jumps into the middle of the expression, it cannot be collapsed into a single instruction.
+            if (start + 1..=call_index).any(|index| jump_targets.contains(&index)) {
+                return None;
+            }
+            let parameters = self.instructions[start..call_index - 1]
+                .iter()
+                .map(constant_value)
+                .collect::<Option<Vec<_>>>()?;
+            Some((start, call_index, function.call(parameters)))
+        })
+    }
+
+    /// Replaces jumps that depend on a constant condition.
+    ///
+    /// Since [`OpCode::JumpIfFalse`] leaves the condition on the stack, a constant `false` is kept for the instructions at the target to pop.
+    fn fold_constant_branches(&mut self) -> bool {
+        let jump_targets = self.jump_targets();
+        let mut keep = vec![true; self.instructions.len()];
+        let mut changed = false;
+        for index in 1..self.instructions.len() {
+            let push = &self.instructions[index - 1];
+            if op_code(&self.instructions[index]) != OpCode::JumpIfFalse
+                || op_code(push) != OpCode::PushBool
+                || jump_targets.contains(&index)
+                || !keep[index - 1]
+            {
+                continue;
+            }
+            let condition: bool = push.read_operand(0);
+            if condition {
+                keep[index - 1] = false;
+                keep[index] = false;
+            } else {
+                self.instructions[index].opcode = OpCode::JumpTo.into();
+            }
+            changed = true;
+        }
+        self.retain(&keep);
+        changed
+    }
+
+    /// Removes pairs of instructions that push a constant and immediately pop it again,
+    /// which are left behind by [`NodeOptimizer::fold_constant_branches`].
+    fn remove_discarded_constants(&mut self) -> bool {
+        let jump_targets = self.jump_targets();
+        let mut keep = vec![true; self.instructions.len()];
+        let mut changed = false;
+        for index in 1..self.instructions.len() {
+            if op_code(&self.instructions[index]) == OpCode::Pop
+                && constant_value(&self.instructions[index - 1]).is_some()
+                && !jump_targets.contains(&index)
+                && keep[index - 1]
+            {
+                keep[index - 1] = false;
+                keep[index] = false;
+                changed = true;
+            }
+        }
+        self.retain(&keep);
+        changed
+    }
+
+    fn eliminate_unreachable_instructions(&mut self) -> bool {
+        let len = self.instructions.len();
+        let mut reachable = vec![false; len];
+        // Execution starts at the first instruction. The destinations of options are jumped to through the stack.
+        let mut pending: Vec<_> = self
+            .instructions
+            .iter()
+            .filter(|instruction| op_code(instruction) == OpCode::AddOption)
+            .filter_map(|instruction| self.label_target(&instruction.read_operand::<String>(1)))
+            .chain(Some(0))
+            .collect();
+        while let Some(index) = pending.pop() {
+            if index >= len || reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+            let instruction = &self.instructions[index];
+            match op_code(instruction) {
+                OpCode::JumpTo => {
+                    pending.extend(self.label_target(&instruction.read_operand::<String>(0)))
+                }
+                OpCode::JumpIfFalse => {
+                    pending.extend(self.label_target(&instruction.read_operand::<String>(0)));
+                    pending.push(index + 1);
+                }
+                // The destination of `Jump` is pushed by `ShowOptions`, whose options were already taken into account above.
+                // When no option is available, the runtime may instead continue at the end of the option group.
+                OpCode::ShowOptions => {
+                    pending.extend(
+                        self.option_group_end(index)
+                            .and_then(|label| self.label_target(&label)),
+                    );
+                    pending.push(index + 1);
+                }
+                OpCode::Jump | OpCode::Stop | OpCode::RunNode => {}
+                _ => pending.push(index + 1),
+            }
+        }
+        let changed = reachable.contains(&false);
+        self.retain(&reachable);
+        changed
+    }
+
+    /// Removes unconditional jumps to the instruction that follows them.
+    fn remove_redundant_jumps(&mut self) -> bool {
+        let keep: Vec<_> = self
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| {
+                op_code(instruction) != OpCode::JumpTo
+                    || self.label_target(&instruction.read_operand::<String>(0)) != Some(index + 1)
+            })
+            .collect();
+        let changed = keep.contains(&false);
+        self.retain(&keep);
+        changed
+    }
+
+    fn remove_unused_labels(&mut self) -> bool {
+        let used_labels: HashSet<String> = self
+            .instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, instruction)| match op_code(instruction) {
+                OpCode::JumpTo | OpCode::JumpIfFalse => Some(instruction.read_operand(0)),
+                OpCode::AddOption => Some(instruction.read_operand(1)),
+                OpCode::ShowOptions => self.option_group_end(index),
+                _ => None,
+            })
+            .collect();
+        let label_count = self.labels.len();
+        self.labels.retain(|label, _| used_labels.contains(label));
+        label_count != self.labels.len()
+    }
+
+    /// Removes all instructions for which `keep` is `false`.
+    /// Labels pointing to a removed instruction are moved to the next instruction that is kept.
+    fn retain(&mut self, keep: &[bool]) {
+        // The new index of every old index, including the one past the end
+        let new_indices: Vec<_> = keep
+            .iter()
+            .scan(0, |kept_count, &keep| {
+                let new_index = *kept_count;
+                *kept_count += usize::from(keep);
+                Some(new_index)
+            })
+            .chain(Some(keep.iter().filter(|&&keep| keep).count()))
+            .collect();
+        for index in self.labels.values_mut() {
+            *index = new_indices[(*index).min(keep.len())];
+        }
+        let mut keep_iter = keep.iter();
+        self.instructions.retain(|_| *keep_iter.next().unwrap());
+        let mut keep_iter = keep.iter();
+        self.positions.retain(|_| *keep_iter.next().unwrap());
+    }
+
+    /// The label at the end of the option group shown by the `ShowOptions` at `index`, which is pushed right before it.
+    fn option_group_end(&self, index: usize) -> Option<String> {
+        let push = &self.instructions[index.checked_sub(1)?];
+        (op_code(push) == OpCode::PushString).then(|| push.read_operand(0))
+    }
+
+    fn label_target(&self, label: &str) -> Option<usize> {
+        self.labels.get(label).copied()
+    }
+
+    fn jump_targets(&self) -> HashSet<usize> {
+        self.labels.values().copied().collect()
+    }
+}
+
+fn op_code(instruction: &Instruction) -> OpCode {
+    instruction
+        .opcode
+        .try_into()
+        .unwrap_or_else(|e| bug!("Compiler generated an invalid instruction: {e}"))
+}
+
+fn constant_value(instruction: &Instruction) -> Option<YarnValue> {
+    match op_code(instruction) {
+        OpCode::PushFloat => Some(YarnValue::Number(instruction.read_operand(0))),
+        OpCode::PushString => Some(YarnValue::String(instruction.read_operand(0))),
+        OpCode::PushBool => Some(YarnValue::Boolean(instruction.read_operand(0))),
+        _ => None,
+    }
+}
+
+fn push_instruction(value: YarnValue) -> Instruction {
+    let (op_code, operand) = match value {
+        YarnValue::Number(value) => (OpCode::PushFloat, Operand::from(value)),
+        YarnValue::String(value) => (OpCode::PushString, Operand::from(value)),
+        YarnValue::Boolean(value) => (OpCode::PushBool, Operand::from(value)),
+    };
+    Instruction {
+        opcode: op_code.into(),
+        operands: vec![operand],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(source: &str, optimize: bool) -> Compilation {
+        Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_owned(),
+                source: source.to_owned(),
+            })
+            .with_optimizations(optimize)
+            .compile()
+            .unwrap()
+    }
+
+    fn op_codes(compilation: &Compilation, node_name: &str) -> Vec<OpCode> {
+        compilation.program.as_ref().unwrap().nodes[node_name]
+            .instructions
+            .iter()
+            .map(op_code)
+            .collect()
+    }
+
+    #[test]
+    fn does_nothing_when_disabled() {
+        let source = "title: Start\n---\n<<if 1 + 2 > 2>>\nA\n<<endif>>\n===\n";
+        let unoptimized = compile(source, false);
+        assert!(op_codes(&unoptimized, "Start").contains(&OpCode::CallFunc));
+        assert!(op_codes(&unoptimized, "Start").contains(&OpCode::JumpIfFalse));
+    }
+
+    #[test]
+    fn folds_constant_expressions() {
+        let source = "title: Start\n---\n<<declare $x = 0>>\n<<set $x to 1 + 2 * 3>>\n===\n";
+        let compilation = compile(source, true);
+        let node = &compilation.program.as_ref().unwrap().nodes["Start"];
+        assert_eq!(
+            vec![
+                OpCode::PushFloat,
+                OpCode::StoreVariable,
+                OpCode::Pop,
+                OpCode::Stop
+            ],
+            op_codes(&compilation, "Start")
+        );
+        assert_eq!(7.0, node.instructions[0].read_operand::<f32>(0));
+    }
+
+    #[test]
+    fn removes_constant_branches() {
+        let source = "title: Start\n---\n<<if 1 < 2>>\nA\n<<else>>\nB\n<<endif>>\n===\n";
+        let compilation = compile(source, true);
+        let node = &compilation.program.as_ref().unwrap().nodes["Start"];
+        assert_eq!(
+            vec![OpCode::RunLine, OpCode::Stop],
+            op_codes(&compilation, "Start")
+        );
+        assert!(node.labels.is_empty());
+    }
+
+    #[test]
+    fn keeps_branches_on_variables() {
+        let source = "title: Start\n---\n<<declare $x = true>>\n<<if $x>>\nA\n<<endif>>\nB\n===\n";
+        let compilation = compile(source, true);
+        let op_codes = op_codes(&compilation, "Start");
+        assert!(op_codes.contains(&OpCode::JumpIfFalse));
+        assert_eq!(
+            2,
+            op_codes.iter().filter(|&&op| op == OpCode::RunLine).count()
+        );
+    }
+
+    #[test]
+    fn keeps_option_destinations() {
+        let source = "title: Start\n---\n-> A\n    A1\n-> B\n    B1\n===\n";
+        let compilation = compile(source, true);
+        let node = &compilation.program.as_ref().unwrap().nodes["Start"];
+        for instruction in &node.instructions {
+            for label in match op_code(instruction) {
+                OpCode::AddOption => vec![instruction.read_operand::<String>(1)],
+                OpCode::JumpTo | OpCode::JumpIfFalse => vec![instruction.read_operand(0)],
+                _ => vec![],
+            } {
+                assert!(node.labels.contains_key(&label), "Missing label {label}");
+            }
+        }
+        assert_eq!(
+            4,
+            op_codes(&compilation, "Start")
+                .iter()
+                .filter(|&&op| op == OpCode::RunLine || op == OpCode::AddOption)
+                .count()
+        );
+    }
+
+    #[test]
+    fn keeps_end_of_option_groups() {
+        let source = "title: Start\n---\n-> A\n    <<jump Other>>\n-> B\n    <<jump Other>>\n===\ntitle: Other\n---\nC\n===\n";
+        let compilation = compile(source, true);
+        let node = &compilation.program.as_ref().unwrap().nodes["Start"];
+        let show_options = node
+            .instructions
+            .iter()
+            .position(|instruction| op_code(instruction) == OpCode::ShowOptions)
+            .unwrap();
+        assert!(node.instructions[show_options].operands.is_empty());
+        // Only the runtime's fallthrough can reach the end, as both options jump to another node
+        let push = &node.instructions[show_options - 1];
+        assert_eq!(OpCode::PushString, op_code(push));
+        let end_of_group = node.labels[&push.read_operand::<String>(0)] as usize;
+        assert_eq!(
+            vec![OpCode::Pop, OpCode::Pop],
+            op_codes(&compilation, "Start")[end_of_group..end_of_group + 2]
+        );
+    }
+
+    #[test]
+    fn updates_debug_info() {
+        let source = "title: Start\n---\n<<if false>>\nA\n<<endif>>\nB\n===\n";
+        let compilation = compile(source, true);
+        assert_eq!(
+            vec![OpCode::RunLine, OpCode::Stop],
+            op_codes(&compilation, "Start")
+        );
+        let node = &compilation.program.as_ref().unwrap().nodes["Start"];
+        let debug_info = &compilation.debug_info["Start"];
+        assert_eq!(node.instructions.len(), debug_info.line_positions.len());
+        let run_line = node
+            .instructions
+            .iter()
+            .position(|instruction| op_code(instruction) == OpCode::RunLine)
+            .unwrap();
+        assert_eq!(5, debug_info.get_line_info(run_line).position.unwrap().line);
+    }
+}
@@ -5,7 +5,22 @@ use antlr_rust::tree::ParseTreeVisitorCompat;
 pub(crate) fn register_strings(mut state: CompilationIntermediate) -> CompilationIntermediate {
     // First pass: parse all files, generate their syntax trees,
     // and figure out what variables they've declared
+    let file_order = &state.external_files.file_order;
+    let mut merged_external_files = 0;
     for (file, _) in &state.parsed_files {
+        // Implicit line IDs depend on the size of the string table,
+        // so the lines of files that precede this one but are not compiled again need to be accounted for
+        let position = file_order
+            .iter()
+            .position(|name| *name == file.name)
+            .unwrap_or(file_order.len());
+        for name in file_order.iter().take(position).skip(merged_external_files) {
+            if let Some(string_table) = state.external_files.string_tables.get(name) {
+                state.string_table.extend(string_table.clone().into());
+            }
+        }
+        merged_external_files = merged_external_files.max(position);
+
         // ok now we will add in our lastline tags
         // we do this BEFORE we build our strings table otherwise the tags will get missed
         // this should probably be a flag instead of every time though
@@ -16,6 +31,14 @@ pub(crate) fn register_strings(mut state: CompilationIntermediate) -> Compilatio
             StringTableGeneratorVisitor::new(state.string_table.clone(), file.clone());
         visitor.visit(file.tree.as_ref());
         state.diagnostics.extend(visitor.diagnostics);
+        let file_results = state.file_results.entry(file.name.clone()).or_default();
+        file_results.string_table_offset = state.string_table.len();
+        file_results.string_table = visitor
+            .string_table_manager
+            .iter()
+            .filter(|(line_id, _)| !state.string_table.contains_key(*line_id))
+            .map(|(line_id, string_info)| (line_id.clone(), string_info.clone()))
+            .collect();
         state.string_table.extend(visitor.string_table_manager);
     }
 
@@ -1,5 +1,7 @@
+use crate::listeners::closest_match;
 use crate::prelude::*;
 use std::collections::HashSet;
+use yarnspinner_core::types::Type;
 
 pub(crate) fn resolve_deferred_type_diagnostic(
     mut state: CompilationIntermediate,
@@ -9,13 +11,32 @@ pub(crate) fn resolve_deferred_type_diagnostic(
         .iter()
         .map(|decl| &decl.name)
         .collect();
+    let known_variables: Vec<_> = state
+        .known_variable_declarations
+        .iter()
+        .filter(|decl| !matches!(decl.r#type, Type::Function(_)))
+        .map(|decl| decl.name.as_str())
+        .collect();
 
     for deferred_type_diagnostic in &state.potential_issues {
         let resolved = known_declarations.contains(&deferred_type_diagnostic.name);
         if !resolved {
-            state
-                .diagnostics
-                .push(deferred_type_diagnostic.diagnostic.clone())
+            let mut diagnostic = deferred_type_diagnostic.diagnostic.clone();
+            // The variable is most likely a typo of a declared one.
+            if let Some(range) = diagnostic.range.clone()
+                && let Some(candidate) = closest_match(
+                    &deferred_type_diagnostic.name,
+                    known_variables.iter().copied(),
+                )
+            {
+                let suggestion = SuggestedReplacement::did_you_mean(
+                    diagnostic.file_name.clone(),
+                    range,
+                    candidate,
+                );
+                diagnostic = diagnostic.with_suggestion(suggestion);
+            }
+            state.diagnostics.push(diagnostic)
         }
     }
     state
@@ -0,0 +1,33 @@
+use crate::prelude::generated::yarnspinnerparser::{DialogueContextAttrs, NodeContextAttrs};
+use crate::prelude::*;
+use crate::visitors::JumpDestinationVisitor;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+use std::collections::HashSet;
+
+pub(crate) fn validate_jump_destinations(
+    mut state: CompilationIntermediate,
+) -> CompilationIntermediate {
+    // Jumping to a node that doesn't exist fails at runtime, so warn about it early.
+    // This is not an error because programs may be combined with others after compilation.
+    let mut node_names: HashSet<_> = state
+        .parsed_files
+        .iter()
+        .flat_map(|(file, _)| file.tree.node_all())
+        .filter_map(|node| {
+            node.header_all()
+                .iter()
+                .find(|header| header.header_key.as_ref().unwrap().get_text() == "title")
+                .and_then(|title_header| title_header.header_value.as_ref())
+                .map(|title| title.get_text().to_owned())
+        })
+        .collect();
+    node_names.extend(state.external_files.node_names.iter().cloned());
+
+    for (file, _) in &state.parsed_files {
+        let mut visitor = JumpDestinationVisitor::new(node_names.clone(), file.clone());
+        visitor.visit(file.tree.as_ref());
+        state.diagnostics.extend(visitor.diagnostics);
+    }
+    state
+}
@@ -49,11 +49,29 @@ pub(crate) fn validate_unique_node_names(
         .filter(|(_, nodes)| nodes.len() > 1)
     {
         // More than one node has this name! Report an error on both.
+        let locations: Vec<_> = nodes
+            .iter()
+            .map(|(header_context, file)| (file.name.clone(), header_context.range()))
+            .collect();
         for (header_context, file) in nodes {
-            state.diagnostics.push(
+            let range = header_context.range();
+            let related_locations = locations
+                .iter()
+                .filter(|(file_name, other_range)| *file_name != file.name || *other_range != range)
+                .map(|(file_name, other_range)| {
+                    RelatedLocation::new(format!("{name} is also defined here"))
+                        .with_file_name(file_name)
+                        .with_range(other_range.clone())
+                });
+            let diagnostic =
                 Diagnostic::from_message(format!("More than one node is named {name}",))
+                    .with_code(DiagnosticCode::DuplicateNodeName)
                     .with_file_name(file.name.clone())
-                    .with_parser_context(header_context.as_ref(), file.tokens()),
+                    .with_parser_context(header_context.as_ref(), file.tokens());
+            state.diagnostics.push(
+                related_locations.fold(diagnostic, |diagnostic, related_location| {
+                    diagnostic.with_related_location(related_location)
+                }),
             );
         }
     }
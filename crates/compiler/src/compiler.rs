@@ -2,11 +2,13 @@
 //! and <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/CompilationJob.cs>
 
 use crate::prelude::*;
+pub use incremental::CompilationCache;
 use std::path::Path;
 use yarnspinner_core::prelude::*;
 
 mod add_tags_to_lines;
 pub(crate) mod antlr_rust_ext;
+pub(crate) mod incremental;
 pub(crate) mod run_compilation;
 pub(crate) mod utils;
 
@@ -43,6 +45,9 @@ pub struct Compiler {
 
     /// The declarations for variables.
     pub variable_declarations: Vec<Declaration>,
+
+    /// Whether the generated [`Program`] should be optimized. Set with [`Compiler::with_optimizations`].
+    pub(crate) optimize: bool,
 }
 
 impl Compiler {
@@ -91,6 +96,16 @@ impl Compiler {
         self
     }
 
+    /// Sets whether the generated [`Program`] should be optimized. By default, this is `false`.
+    ///
+    /// Optimizing folds constant expressions, removes branches whose condition is constant
+    /// and drops instructions that can never be executed. The optimized program behaves exactly like the unoptimized one,
+    /// and the [`Compilation::debug_info`] is updated to match the optimized instructions.
+    pub fn with_optimizations(&mut self, optimize: bool) -> &mut Self {
+        self.optimize = optimize;
+        self
+    }
+
     /// Adds a variable declaration to the compilation.
     pub fn declare_variable(&mut self, declaration: Declaration) -> &mut Self {
         self.variable_declarations.push(declaration);
@@ -101,6 +116,23 @@ impl Compiler {
     pub fn compile(&self) -> Result<Compilation> {
         run_compilation::compile(self)
     }
+
+    /// Compiles the Yarn files previously added into a [`Compilation`], reusing the results of previous calls stored in the `cache`.
+    ///
+    /// Only files whose contents changed since the last successful call are compiled again, together with the files
+    /// that depend on them, e.g. because they use a variable, function, node or line ID whose definition changed.
+    /// The resulting [`Compilation`] is the same as the one returned by [`Compiler::compile`],
+    /// except for the order of the generated declarations for tracking node visits, which is not stable between compilations anyway.
+    ///
+    /// If the compilation fails, all files are compiled again to report the same diagnostics as [`Compiler::compile`] would, and the cache keeps the results of the last successful compilation.
+    /// Changing any setting of the [`Compiler`] or the order of its files invalidates the cache.
+    /// Compilations that are not [`CompilationType::FullCompilation`] are never cached.
+    ///
+    /// Note that implicit line IDs depend on the number of lines in all preceding files, so adding or removing lines
+    /// causes all following files with implicit line IDs to be compiled again. Add `#line:` tags to avoid this.
+    pub fn compile_incremental(&self, cache: &mut CompilationCache) -> Result<Compilation> {
+        incremental::compile_incremental(self, cache)
+    }
 }
 
 /// Represents the contents of a file to compile.
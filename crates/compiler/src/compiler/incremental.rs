@@ -0,0 +1,720 @@
+//! Incremental compilation. See [`Compiler::compile_incremental`].
+
+use crate::prelude::generated::yarnspinnerlexer;
+use crate::prelude::*;
+use antlr_rust::int_stream::IntStream;
+use antlr_rust::token::Token;
+use antlr_rust::token_stream::TokenStream;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::Type;
+
+/// Remembers what every file contributed to the last successful call to [`Compiler::compile_incremental`],
+/// so that the next call only needs to recompile the files that changed and the files that depend on them.
+///
+/// A cache is meant to be used for a single project. It is reset automatically whenever
+/// the settings of the [`Compiler`] or the order of its [`Compiler::files`] change.
+#[derive(Debug, Clone, Default)]
+pub struct CompilationCache {
+    settings: Option<Compiler>,
+    file_order: Vec<String>,
+    files: HashMap<String, CachedFile>,
+    initial_values: BTreeMap<String, Operand>,
+    tracking_declarations: Vec<Declaration>,
+    global_diagnostics: Vec<(usize, Diagnostic)>,
+    recompiled_files: Vec<String>,
+}
+
+impl CompilationCache {
+    /// Creates a new, empty [`CompilationCache`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes everything from the cache, so that the next compilation recompiles all files.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns `true` if nothing has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The names of the files that were compiled from source during the last call to [`Compiler::compile_incremental`], in order.
+    /// All other files were taken from the cache.
+    pub fn recompiled_files(&self) -> &[String] {
+        &self.recompiled_files
+    }
+
+    fn is_compatible_with(&self, settings: &Compiler, file_names: &[String]) -> bool {
+        if self.settings.as_ref() != Some(settings) {
+            return false;
+        }
+        // Files may come and go, but the ones that stay must keep their order
+        let remaining_files = file_names
+            .iter()
+            .filter(|name| self.files.contains_key(*name));
+        let previous_files = self
+            .file_order
+            .iter()
+            .filter(|name| file_names.contains(name));
+        remaining_files.eq(previous_files)
+    }
+
+    fn external_files(&self, file_names: &[String], recompiled: &HashSet<String>) -> ExternalFiles {
+        let mut external_files = ExternalFiles {
+            file_order: file_names.to_vec(),
+            ..Default::default()
+        };
+        for (name, file) in self.cached_files(file_names, recompiled) {
+            let results = &file.results;
+            external_files
+                .string_tables
+                .insert(name.clone(), results.string_table.clone());
+            external_files
+                .node_names
+                .extend(results.node_names.iter().cloned());
+            external_files
+                .tracking_nodes
+                .extend(results.tracking_nodes.iter().cloned());
+            external_files
+                .ignoring_nodes
+                .extend(results.ignoring_nodes.iter().cloned());
+        }
+        external_files
+    }
+
+    /// The files that are taken from the cache instead of being recompiled, in order.
+    fn cached_files<'a>(
+        &'a self,
+        file_names: &'a [String],
+        recompiled: &'a HashSet<String>,
+    ) -> impl Iterator<Item = (&'a String, &'a CachedFile)> + 'a {
+        file_names
+            .iter()
+            .filter(move |name| !recompiled.contains(*name))
+            .filter_map(move |name| self.files.get(name).map(|file| (name, file)))
+    }
+
+    fn assemble(&self) -> Compilation {
+        let files: Vec<_> = self
+            .file_order
+            .iter()
+            .map(|name| (name, &self.files[name]))
+            .collect();
+
+        let program = Program {
+            nodes: files
+                .iter()
+                .flat_map(|(_, file)| file.nodes.iter())
+                .map(|node| (node.name.clone(), node.clone()))
+                .collect(),
+            initial_values: self.initial_values.clone(),
+            ..Default::default()
+        };
+        let string_table: HashMap<_, _> = files
+            .iter()
+            .flat_map(|(_, file)| file.results.string_table.clone())
+            .collect();
+        let contains_implicit_string_tags = string_table.values().any(|info| info.is_implicit_tag);
+        let declarations = files
+            .iter()
+            .flat_map(|(_, file)| file.results.explicit_declarations.iter())
+            .chain(
+                files
+                    .iter()
+                    .flat_map(|(_, file)| file.results.implicit_declarations.iter()),
+            )
+            .chain(self.tracking_declarations.iter())
+            .cloned()
+            .collect();
+
+        // Mirror the order in which a full compilation reports warnings:
+        // first the ones found during code generation, then the others by compilation step and file.
+        let mut staged_diagnostics: Vec<_> = files
+            .iter()
+            .flat_map(|(_, file)| file.diagnostics.iter())
+            .chain(self.global_diagnostics.iter())
+            .collect();
+        staged_diagnostics.sort_by_key(|(stage, _)| *stage);
+        let mut unique_warnings = HashSet::new();
+        let warnings = files
+            .iter()
+            .flat_map(|(_, file)| file.results.code_generation_warnings.iter())
+            .chain(
+                staged_diagnostics
+                    .into_iter()
+                    .map(|(_, diagnostic)| diagnostic),
+            )
+            .filter(|diagnostic| unique_warnings.insert(*diagnostic))
+            .cloned()
+            .collect();
+
+        Compilation {
+            program: Some(program),
+            string_table,
+            declarations,
+            contains_implicit_string_tags,
+            file_tags: files
+                .iter()
+                .map(|(name, file)| ((*name).clone(), file.file_tags.clone()))
+                .collect(),
+            warnings,
+            debug_info: files
+                .iter()
+                .flat_map(|(_, file)| file.debug_info.clone())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedFile {
+    hash: u64,
+    results: FileResults,
+    interface: FileInterface,
+    nodes: Vec<Node>,
+    debug_info: HashMap<String, DebugInfo>,
+    file_tags: Vec<String>,
+    /// The diagnostics that are not found during code generation, together with the compilation step that emitted them.
+    diagnostics: Vec<(usize, Diagnostic)>,
+}
+
+impl CachedFile {
+    fn is_affected_by(&self, changed_names: &HashSet<String>, node_names_changed: bool) -> bool {
+        // Unknown jump destinations are reported together with the most similar node name,
+        // so they depend on the names of all nodes
+        let has_unknown_jump_destinations = self
+            .diagnostics
+            .iter()
+            .any(|(_, diagnostic)| diagnostic.code == Some(DiagnosticCode::UnknownNode));
+        !self.results.symbols.is_disjoint(changed_names)
+            || (node_names_changed && has_unknown_jump_destinations)
+    }
+}
+
+/// Everything about a file that can influence how other files are compiled.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FileInterface {
+    declarations: HashMap<String, (Type, Option<YarnValue>)>,
+    node_names: HashSet<String>,
+    tracking_nodes: HashSet<String>,
+    ignoring_nodes: HashSet<String>,
+    line_ids: HashSet<String>,
+    /// The symbols a file uses also decide which file is the first to use an undeclared variable,
+    /// and thereby which file owns its implicit declaration.
+    symbols: HashSet<String>,
+}
+
+impl FileInterface {
+    fn new(results: &FileResults) -> Self {
+        Self {
+            declarations: results
+                .explicit_declarations
+                .iter()
+                .chain(results.implicit_declarations.iter())
+                .map(|declaration| {
+                    let signature = (
+                        declaration.r#type.clone(),
+                        declaration.default_value.clone(),
+                    );
+                    (declaration.name.clone(), signature)
+                })
+                .collect(),
+            node_names: results.node_names.iter().cloned().collect(),
+            tracking_nodes: results.tracking_nodes.clone(),
+            ignoring_nodes: results.ignoring_nodes.clone(),
+            line_ids: results
+                .string_table
+                .keys()
+                .map(|line_id| line_id.0.clone())
+                .collect(),
+            symbols: results.symbols.clone(),
+        }
+    }
+
+    fn names(&self) -> HashSet<String> {
+        self.declarations
+            .keys()
+            .chain(&self.node_names)
+            .chain(&self.tracking_nodes)
+            .chain(&self.ignoring_nodes)
+            .chain(&self.line_ids)
+            .chain(&self.symbols)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the names whose meaning differs between the two interfaces.
+    fn changed_names(&self, other: &Self) -> HashSet<String> {
+        let declarations = self
+            .declarations
+            .keys()
+            .chain(other.declarations.keys())
+            .filter(|name| self.declarations.get(*name) != other.declarations.get(*name));
+        let sets = [
+            (&self.node_names, &other.node_names),
+            (&self.tracking_nodes, &other.tracking_nodes),
+            (&self.ignoring_nodes, &other.ignoring_nodes),
+            (&self.line_ids, &other.line_ids),
+            (&self.symbols, &other.symbols),
+        ];
+        let sets = sets
+            .into_iter()
+            .flat_map(|(own, other)| own.symmetric_difference(other));
+        declarations.chain(sets).cloned().collect()
+    }
+}
+
+pub(crate) fn compile_incremental(
+    compiler: &Compiler,
+    cache: &mut CompilationCache,
+) -> Result<Compilation> {
+    let file_names: Vec<String> = compiler
+        .files
+        .iter()
+        .map(|file| file.file_name.clone())
+        .collect();
+    let has_duplicate_file_names =
+        file_names.iter().collect::<HashSet<_>>().len() != file_names.len();
+    if compiler.compilation_type != CompilationType::FullCompilation
+        || compiler.files.is_empty()
+        || has_duplicate_file_names
+    {
+        cache.clear();
+        cache.recompiled_files = file_names;
+        return compiler.compile();
+    }
+
+    let settings = Compiler {
+        files: Vec::new(),
+        ..compiler.clone()
+    };
+    if !cache.is_compatible_with(&settings, &file_names) {
+        cache.clear();
+    }
+
+    let hashes: HashMap<_, _> = compiler
+        .files
+        .iter()
+        .map(|file| {
+            let mut hasher = DefaultHasher::new();
+            file.source.hash(&mut hasher);
+            (file.file_name.clone(), hasher.finish())
+        })
+        .collect();
+    let mut recompiled: HashSet<String> = file_names
+        .iter()
+        .filter(|name| cache.files.get(*name).map(|file| file.hash) != Some(hashes[*name]))
+        .cloned()
+        .collect();
+    let removed_files: Vec<_> = cache
+        .file_order
+        .iter()
+        .filter(|name| !hashes.contains_key(*name))
+        .collect();
+    if recompiled.is_empty() && removed_files.is_empty() {
+        cache.recompiled_files.clear();
+        return Ok(cache.assemble());
+    }
+    let mut node_names_changed = removed_files
+        .iter()
+        .any(|name| !cache.files[*name].interface.node_names.is_empty());
+    let mut changed_names: HashSet<String> = removed_files
+        .iter()
+        .flat_map(|name| cache.files[*name].interface.names())
+        .collect();
+
+    let output = loop {
+        let affected_files: Vec<_> = cache
+            .cached_files(&file_names, &recompiled)
+            .filter(|(_, file)| file.is_affected_by(&changed_names, node_names_changed))
+            .map(|(name, _)| name.clone())
+            .collect();
+        recompiled.extend(affected_files);
+        if recompiled.is_empty() {
+            // Only files were removed, but we still need to compile something to get the initial values and tracking variables
+            recompiled.insert(file_names[0].clone());
+        }
+
+        let mut job = compiler.clone();
+        job.files
+            .retain(|file| recompiled.contains(&file.file_name));
+        let cached_files: Vec<_> = cache.cached_files(&file_names, &recompiled).collect();
+        job.variable_declarations.extend(
+            cached_files
+                .iter()
+                .flat_map(|(_, file)| file.results.explicit_declarations.iter())
+                .chain(
+                    cached_files
+                        .iter()
+                        .flat_map(|(_, file)| file.results.implicit_declarations.iter()),
+                )
+                .cloned(),
+        );
+        let external_files = cache.external_files(&file_names, &recompiled);
+        let output = compile_with_external_files(&job, external_files);
+        if output.result.is_err() {
+            // Errors in the context of files that were not recompiled could be reported differently than in a full compilation,
+            // so we let a full compilation produce them.
+            cache.recompiled_files = file_names;
+            return compiler.compile();
+        }
+
+        for name in &recompiled {
+            let interface = FileInterface::new(&output.file_results[name]);
+            let changes = match cache.files.get(name) {
+                Some(file) => {
+                    node_names_changed |= file.interface.node_names != interface.node_names;
+                    file.interface.changed_names(&interface)
+                }
+                None => {
+                    node_names_changed |= !interface.node_names.is_empty();
+                    interface.names()
+                }
+            };
+            changed_names.extend(changes);
+        }
+
+        let mut string_table_offset = 0;
+        let mut affected_files = Vec::new();
+        for name in &file_names {
+            let results = match output.file_results.get(name) {
+                Some(results) => results,
+                None => {
+                    let file = &cache.files[name];
+                    let results = &file.results;
+                    let has_implicit_line_ids = results
+                        .string_table
+                        .values()
+                        .any(|info| info.is_implicit_tag);
+                    if file.is_affected_by(&changed_names, node_names_changed)
+                        || (has_implicit_line_ids
+                            && results.string_table_offset != string_table_offset)
+                    {
+                        affected_files.push(name.clone());
+                    }
+                    results
+                }
+            };
+            string_table_offset += results.string_table.len();
+        }
+        if affected_files.is_empty() {
+            break output;
+        }
+        recompiled.extend(affected_files);
+    };
+
+    let CompilationOutput {
+        result,
+        mut file_results,
+        staged_diagnostics,
+    } = output;
+    let compilation = result.unwrap_or_bug();
+    let program = compilation.program.unwrap_or_bug();
+    let declaration_count: usize = file_results
+        .values()
+        .map(|results| results.explicit_declarations.len() + results.implicit_declarations.len())
+        .sum();
+    cache.tracking_declarations = compilation.declarations[declaration_count..].to_vec();
+    cache.initial_values = program.initial_values.clone();
+    cache.global_diagnostics = staged_diagnostics
+        .iter()
+        .filter(|(_, diagnostic)| {
+            !diagnostic
+                .file_name
+                .as_ref()
+                .is_some_and(|file_name| recompiled.contains(file_name))
+        })
+        .cloned()
+        .collect();
+    for name in &recompiled {
+        let results = file_results.remove(name).unwrap_or_bug();
+        let cached_file = CachedFile {
+            hash: hashes[name],
+            interface: FileInterface::new(&results),
+            nodes: results
+                .node_names
+                .iter()
+                .map(|node_name| program.nodes[node_name].clone())
+                .collect(),
+            debug_info: results
+                .node_names
+                .iter()
+                .filter_map(|node_name| {
+                    compilation
+                        .debug_info
+                        .get(node_name)
+                        .map(|debug_info| (node_name.clone(), debug_info.clone()))
+                })
+                .collect(),
+            file_tags: compilation.file_tags.get(name).cloned().unwrap_or_default(),
+            diagnostics: staged_diagnostics
+                .iter()
+                .filter(|(_, diagnostic)| diagnostic.file_name.as_ref() == Some(name))
+                .cloned()
+                .collect(),
+            results,
+        };
+        cache.files.insert(name.clone(), cached_file);
+    }
+    cache.files.retain(|name, _| hashes.contains_key(name));
+    cache.recompiled_files = file_names
+        .iter()
+        .filter(|name| recompiled.contains(*name))
+        .cloned()
+        .collect();
+    cache.file_order = file_names;
+    cache.settings = Some(settings);
+    Ok(cache.assemble())
+}
+
+/// Collects everything in a file that could refer to something defined in another file:
+/// identifiers, variables, functions, tags, header values and strings.
+pub(crate) fn collect_symbols(file: &FileParseResult) -> HashSet<String> {
+    let tokens = file.tokens();
+    (0..tokens.size())
+        .map(|index| tokens.get(index))
+        .filter_map(|token| match token.get_token_type() {
+            yarnspinnerlexer::ID
+            | yarnspinnerlexer::VAR_ID
+            | yarnspinnerlexer::FUNC_ID
+            | yarnspinnerlexer::HASHTAG_TEXT => Some(token.get_text().to_owned()),
+            yarnspinnerlexer::REST_OF_LINE => Some(token.get_text().trim().to_owned()),
+            yarnspinnerlexer::STRING => Some(token.get_text().trim_matches('"').to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(file_name: &str, source: &str) -> File {
+        File {
+            file_name: file_name.to_owned(),
+            source: source.to_owned(),
+        }
+    }
+
+    fn compiler(files: &[File]) -> Compiler {
+        let mut compiler = Compiler::new();
+        compiler.add_files(files.iter().cloned());
+        compiler
+    }
+
+    /// The generated declarations for tracking node visits come from a `HashSet`, so their order is arbitrary.
+    fn normalized(mut compilation: Compilation) -> Compilation {
+        compilation
+            .declarations
+            .sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        compilation
+    }
+
+    fn compile_and_compare(compiler: &Compiler, cache: &mut CompilationCache) -> Vec<String> {
+        let incremental = compiler.compile_incremental(cache).unwrap();
+        let full = compiler.compile().unwrap();
+        assert_eq!(normalized(incremental), normalized(full));
+        cache.recompiled_files().to_vec()
+    }
+
+    const FIRST: &str = "title: First
+---
+<<declare $gold = 10>>
+Hello there #line:first_1
+<<if visited(\"Second\")>>
+    You have {$gold} gold. #line:first_2
+<<endif>>
+<<jump Second>>
+===
+";
+
+    const SECOND: &str = "title: Second
+---
+You're back! #line:second_1
+<<set $gold to $gold + 1>>
+-> Buy something #line:second_2
+    <<set $potions to 1>>
+-> Leave #line:second_3
+===
+";
+
+    const THIRD: &str = "title: Third
+---
+This node stands on its own. #line:third_1
+===
+";
+
+    #[test]
+    fn first_compilation_compiles_all_files() {
+        let files = [file("first.yarn", FIRST), file("second.yarn", SECOND)];
+        let mut cache = CompilationCache::new();
+
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert_eq!(recompiled, ["first.yarn", "second.yarn"]);
+        assert!(!cache.is_empty());
+    }
+
+    #[test]
+    fn unchanged_files_are_not_recompiled() {
+        let files = [file("first.yarn", FIRST), file("second.yarn", SECOND)];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert!(recompiled.is_empty());
+    }
+
+    #[test]
+    fn only_recompiles_changed_file_when_nothing_depends_on_the_change() {
+        let files = [
+            file("first.yarn", FIRST),
+            file("second.yarn", SECOND),
+            file("third.yarn", THIRD),
+        ];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let files = [
+            file("first.yarn", FIRST),
+            file(
+                "second.yarn",
+                &SECOND.replace("You're back!", "Welcome back!"),
+            ),
+            file("third.yarn", THIRD),
+        ];
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert_eq!(recompiled, ["second.yarn"]);
+    }
+
+    #[test]
+    fn recompiles_files_using_changed_variable() {
+        let files = [
+            file("first.yarn", FIRST),
+            file("second.yarn", SECOND),
+            file("third.yarn", THIRD),
+        ];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let files = [
+            file("first.yarn", &FIRST.replace("$gold = 10", "$gold = 20")),
+            file("second.yarn", SECOND),
+            file("third.yarn", THIRD),
+        ];
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert_eq!(recompiled, ["first.yarn", "second.yarn"]);
+    }
+
+    #[test]
+    fn recompiles_files_jumping_to_renamed_node() {
+        let files = [
+            file("first.yarn", FIRST),
+            file("second.yarn", SECOND),
+            file("third.yarn", THIRD),
+        ];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let files = [
+            file("first.yarn", FIRST),
+            file(
+                "second.yarn",
+                &SECOND.replace("title: Second", "title: Shop"),
+            ),
+            file("third.yarn", THIRD),
+        ];
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert_eq!(recompiled, ["first.yarn", "second.yarn"]);
+    }
+
+    #[test]
+    fn recompiles_following_files_with_implicit_line_ids_when_line_count_changes() {
+        let without_line_ids = |source: &str| {
+            source
+                .lines()
+                .map(|line| line.split(" #line:").next().unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let files = [
+            file("first.yarn", &without_line_ids(FIRST)),
+            file("third.yarn", &without_line_ids(THIRD)),
+        ];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let files = [
+            file(
+                "first.yarn",
+                &without_line_ids(&FIRST.replace("Hello there", "Hello there\nHow are you?")),
+            ),
+            file("third.yarn", &without_line_ids(THIRD)),
+        ];
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+
+        assert_eq!(recompiled, ["first.yarn", "third.yarn"]);
+    }
+
+    #[test]
+    fn handles_added_and_removed_files() {
+        let files = [file("first.yarn", FIRST), file("second.yarn", SECOND)];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let files = [
+            file("first.yarn", FIRST),
+            file("second.yarn", SECOND),
+            file("third.yarn", THIRD),
+        ];
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+        assert_eq!(recompiled, ["third.yarn"]);
+
+        let files = [file("first.yarn", FIRST), file("third.yarn", THIRD)];
+        compile_and_compare(&compiler(&files), &mut cache);
+    }
+
+    #[test]
+    fn reports_same_errors_as_full_compilation_and_keeps_cache() {
+        let files = [file("first.yarn", FIRST), file("second.yarn", SECOND)];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let broken_files = [
+            file("first.yarn", FIRST),
+            file(
+                "second.yarn",
+                &SECOND.replace("$gold + 1", "$gold + \"one\""),
+            ),
+        ];
+        let broken_compiler = compiler(&broken_files);
+        let incremental = broken_compiler.compile_incremental(&mut cache).unwrap_err();
+        let full = broken_compiler.compile().unwrap_err();
+        assert_eq!(incremental, full);
+
+        let recompiled = compile_and_compare(&compiler(&files), &mut cache);
+        assert!(recompiled.is_empty());
+    }
+
+    #[test]
+    fn changing_settings_invalidates_cache() {
+        let files = [file("first.yarn", FIRST), file("second.yarn", SECOND)];
+        let mut cache = CompilationCache::new();
+        compile_and_compare(&compiler(&files), &mut cache);
+
+        let mut optimizing_compiler = compiler(&files);
+        optimizing_compiler.with_optimizations(true);
+        let recompiled = compile_and_compare(&optimizing_compiler, &mut cache);
+
+        assert_eq!(recompiled, ["first.yarn", "second.yarn"]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Result;
 use crate::compilation_steps::*;
+use crate::compiler::incremental::collect_symbols;
 use crate::output::*;
 use crate::prelude::*;
 use crate::string_table_manager::StringTableManager;
@@ -8,12 +9,22 @@ use std::collections::{HashMap, HashSet};
 
 /// Compile Yarn code, as specified by a compilation job.
 pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
+    compile_with_external_files(compiler, ExternalFiles::default()).result
+}
+
+/// Compile Yarn code while treating the given [`ExternalFiles`] as part of the compilation without recompiling them.
+/// Also returns the per-file information needed by [`CompilationCache`].
+pub(crate) fn compile_with_external_files(
+    compiler: &Compiler,
+    external_files: ExternalFiles,
+) -> CompilationOutput {
     let compiler_steps: Vec<&CompilationStep> = vec![
         &register_initial_variables,
         &parse_files,
         &register_strings,
         &validate_unique_node_names,
         &break_on_job_with_only_strings,
+        &validate_jump_destinations,
         &get_declarations,
         &check_types,
         &find_tracking_nodes,
@@ -22,6 +33,7 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
         &resolve_deferred_type_diagnostic,
         &break_on_job_with_only_declarations,
         &generate_code,
+        &optimize_program,
         &add_initial_value_registrations,
     ];
 
@@ -40,19 +52,82 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
         })
         .collect();
     let chars: Vec<_> = chars.iter().map(|c| c.as_slice()).collect();
-    let initial = CompilationIntermediate::from_job(compiler, chars);
-    let intermediate = compiler_steps.into_iter().fold(initial, |state, step| {
-        if state.early_break {
-            state
-        } else {
-            step(state)
-        }
-    });
+    let initial = CompilationIntermediate {
+        external_files,
+        ..CompilationIntermediate::from_job(compiler, chars)
+    };
+    let mut diagnostic_stages = Vec::new();
+    let mut intermediate =
+        compiler_steps
+            .into_iter()
+            .enumerate()
+            .fold(initial, |state, (stage, step)| {
+                if state.early_break {
+                    state
+                } else {
+                    let state = step(state);
+                    diagnostic_stages.resize(state.diagnostics.len(), stage);
+                    state
+                }
+            });
+    for (file, _) in &intermediate.parsed_files {
+        let symbols = collect_symbols(file);
+        intermediate
+            .file_results
+            .entry(file.name.clone())
+            .or_default()
+            .symbols = symbols;
+    }
+    let staged_diagnostics = diagnostic_stages
+        .into_iter()
+        .zip(intermediate.diagnostics.iter().cloned())
+        .collect();
+    let file_results = std::mem::take(&mut intermediate.file_results);
+
     // Cleaning up diagnostics doesn't change the state but makes sure
     // that diagnostics are unique, there are no errors in the warnings, etc.
     // So we execute it even if we've had early breaks.
+    let result = clean_up_diagnostics(intermediate).result.unwrap();
+    CompilationOutput {
+        result,
+        file_results,
+        staged_diagnostics,
+    }
+}
+
+/// The result of [`compile_with_external_files`].
+pub(crate) struct CompilationOutput {
+    pub(crate) result: Result<Compilation>,
+    pub(crate) file_results: HashMap<String, FileResults>,
+    /// All diagnostics before they were cleaned up, together with the index of the step that emitted them.
+    pub(crate) staged_diagnostics: Vec<(usize, Diagnostic)>,
+}
 
-    clean_up_diagnostics(intermediate).result.unwrap()
+/// Information about files that belong to the compilation, but are not compiled again.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExternalFiles {
+    /// The names of all files of the compilation, including the ones that are compiled, in order.
+    pub(crate) file_order: Vec<String>,
+    pub(crate) string_tables: HashMap<String, HashMap<LineId, StringInfo>>,
+    pub(crate) node_names: HashSet<String>,
+    pub(crate) tracking_nodes: HashSet<String>,
+    pub(crate) ignoring_nodes: HashSet<String>,
+}
+
+/// What a single file contributed to the compilation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileResults {
+    /// The size of the string table before this file's lines were added. Implicit line IDs depend on it.
+    pub(crate) string_table_offset: usize,
+    pub(crate) string_table: HashMap<LineId, StringInfo>,
+    pub(crate) explicit_declarations: Vec<Declaration>,
+    pub(crate) implicit_declarations: Vec<Declaration>,
+    pub(crate) tracking_nodes: HashSet<String>,
+    pub(crate) ignoring_nodes: HashSet<String>,
+    pub(crate) node_names: Vec<String>,
+    pub(crate) code_generation_warnings: Vec<Diagnostic>,
+    /// Every identifier, variable, function, tag and string in the file, i.e. everything it could depend on.
+    pub(crate) symbols: HashSet<String>,
 }
 
 type CompilationStep = dyn Fn(CompilationIntermediate) -> CompilationIntermediate;
@@ -71,6 +146,8 @@ pub(crate) struct CompilationIntermediate<'input> {
     pub(crate) string_table: StringTableManager,
     pub(crate) diagnostics: Vec<Diagnostic>,
     pub(crate) file_tags: HashMap<String, Vec<String>>,
+    pub(crate) external_files: ExternalFiles,
+    pub(crate) file_results: HashMap<String, FileResults>,
     pub(crate) early_break: bool,
 }
 
@@ -88,6 +165,8 @@ impl<'input> CompilationIntermediate<'input> {
             string_table: Default::default(),
             diagnostics: Default::default(),
             file_tags: Default::default(),
+            external_files: Default::default(),
+            file_results: Default::default(),
             early_break: Default::default(),
         }
     }
@@ -45,6 +45,7 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
     let lexer_error_listener = LexerErrorListener::new(file_name.clone());
     let lexer_error_listener_diagnostics = lexer_error_listener.diagnostics.clone();
     let lexer_diagnostics = lexer.diagnostics.clone();
+    let lexer_format_specifiers = lexer.format_specifiers.clone();
     lexer.remove_error_listeners();
     lexer.add_error_listener(Box::new(lexer_error_listener));
 
@@ -70,7 +71,11 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
         .cloned();
     diagnostics.extend(new_diagnostics);
 
-    FileParseResult::new(file_name, tree, Rc::new(parser))
+    let source = file.source.strip_prefix('\u{feff}').unwrap_or(&file.source);
+    let format_specifiers = lexer_format_specifiers.take();
+    diagnostics.extend(format_specifiers.diagnostics(&file_name, source));
+
+    FileParseResult::new(file_name, tree, Rc::new(parser), format_specifiers)
 }
 
 pub(crate) fn get_line_id_for_node_name(name: &str) -> LineId {
@@ -253,6 +258,7 @@ mod tests {
         assert_eq!(1, diagnostics.len());
         assert_eq!(
             Diagnostic::from_message("Indentation contains tabs and spaces")
+                .with_code(DiagnosticCode::MixedIndentation)
                 .with_context("\t   ")
                 .with_start_line(3)
                 .with_file_name("test.yarn")
@@ -19,6 +19,10 @@ pub(crate) struct FileParseResult<'input> {
     /// We also end up leading the `ErrorStrategy` into the public interface, but using generics here makes
     /// the code a lot more complicated without actually providing much benefit.
     pub parser: Rc<ActualYarnSpinnerParser<'input>>,
+
+    /// Not part of the original implementation. The format specifiers of inline expressions,
+    /// which are not part of the grammar and thus not in the parse tree.
+    pub format_specifiers: Rc<FormatSpecifiers>,
 }
 
 impl<'input> FileParseResult<'input> {
@@ -26,8 +30,14 @@ impl<'input> FileParseResult<'input> {
         name: String,
         tree: Rc<DialogueContextAll<'input>>,
         parser: Rc<ActualYarnSpinnerParser<'input>>,
+        format_specifiers: FormatSpecifiers,
     ) -> Self {
-        Self { name, tree, parser }
+        Self {
+            name,
+            tree,
+            parser,
+            format_specifiers: Rc::new(format_specifiers),
+        }
     }
 
     pub(crate) fn tokens(&self) -> &ActualTokenStream<'input> {
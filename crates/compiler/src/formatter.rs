@@ -0,0 +1,659 @@
+//! A source formatter for Yarn files, used to keep scripts consistent and diffs small.
+//!
+//! The formatter works on the same parse tree and token stream as the [`Compiler`], so it only ever changes
+//! whitespace and layout, never what the dialogue means:
+//! - Shortcut option bodies and the bodies of `<<if>>` blocks are indented by four spaces per level.
+//! - Commands and inline expressions have exactly one space around binary operators, and none inside of braces and parentheses,
+//!   e.g. `<< if $gold>=10&&!$met_merchant >>` becomes `<<if $gold >= 10 && !$met_merchant>>`.
+//! - Headers are written as `key: value`, with the `title` header first.
+//! - Line conditions and hashtags follow the line text, separated by single spaces, e.g. `Hi! #line:abc123`.
+//! - Runs of blank lines are collapsed, and nodes are separated by exactly one blank line.
+//! - Comments are kept, with whole-line comments indented like the statement that follows them.
+//!
+//! The text of lines and commands is kept as-is, which means that `#line:` IDs and escaped characters are preserved.
+//! Formatting an already formatted file does not change it.
+
+use crate::prelude::generated::yarnspinnerlexer;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use antlr_rust::int_stream::IntStream;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::{CommonToken, TOKEN_DEFAULT_CHANNEL, Token};
+use antlr_rust::token_stream::TokenStream;
+use std::rc::Rc;
+
+const INDENTATION: &str = "    ";
+
+/// Formats the source code of a Yarn file.
+///
+/// Returns the formatted source, or a [`CompilerError`] if the file contains syntax errors. See the [module-level documentation](self)
+/// for what is normalized. Line endings and a leading byte order mark are kept as they were in the original file.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use yarnspinner_compiler::prelude::*;
+/// let file = File {
+///     file_name: "intro.yarn".to_owned(),
+///     source: "title:Start\n---\n<<set $gold=10>>\n-> Buy\n<<set $gold-=5>>\n===\n".to_owned(),
+/// };
+/// let formatted = format_file(&file).unwrap();
+/// assert_eq!(
+///     "title: Start\n---\n<<set $gold = 10>>\n-> Buy\n<<set $gold -= 5>>\n===\n",
+///     formatted
+/// );
+/// ```
+pub fn format_file(file: &File) -> Result<String> {
+    let (byte_order_mark, source) = match file.source.strip_prefix('\u{feff}') {
+        Some(source) => ("\u{feff}", source),
+        None => ("", file.source.as_str()),
+    };
+    let file_chars: Vec<u32> = source.chars().map(|c| c as u32).collect();
+    let mut diagnostics = Vec::new();
+    let parse_result = parse_syntax_tree(file, &file_chars, &mut diagnostics);
+    if diagnostics.has_errors() {
+        return Err(CompilerError(diagnostics));
+    }
+
+    let mut formatter = SourceFormatter::new(&parse_result, source);
+    formatter.dialogue(&parse_result.tree);
+    let line_ending = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    Ok(format!(
+        "{byte_order_mark}{}",
+        formatter.into_source(line_ending)
+    ))
+}
+
+/// Returns whether formatting the file with [`format_file`] would leave it unchanged.
+/// This is what CI should check to reject unformatted scripts.
+pub fn is_formatted(file: &File) -> Result<bool> {
+    Ok(format_file(file)? == file.source)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Blank,
+    BodyStart,
+    BodyEnd,
+    Other,
+}
+
+struct SourceFormatter<'a, 'input> {
+    file: &'a FileParseResult<'input>,
+    chars: Vec<char>,
+    source_lines: Vec<&'a str>,
+    /// The first line of the original source that has not been formatted yet.
+    next_source_line: usize,
+    output: Vec<(LineKind, String)>,
+}
+
+impl<'a, 'input> SourceFormatter<'a, 'input> {
+    fn new(file: &'a FileParseResult<'input>, source: &'a str) -> Self {
+        Self {
+            file,
+            chars: source.chars().collect(),
+            source_lines: source.lines().collect(),
+            next_source_line: 0,
+            output: Vec::new(),
+        }
+    }
+
+    fn dialogue(&mut self, dialogue: &DialogueContextAll<'input>) {
+        for hashtag in dialogue.file_hashtag_all() {
+            let text = hashtag.text.as_ref().unwrap_or_bug().get_text();
+            self.emit(
+                0,
+                0,
+                &*hashtag.start(),
+                &*hashtag.stop(),
+                format!("#{text}"),
+            );
+        }
+        for node in dialogue.node_all() {
+            self.push(LineKind::Blank, String::new());
+            self.node(&node);
+        }
+        self.emit_comments_before(0, self.source_lines.len());
+    }
+
+    fn node(&mut self, node: &NodeContextAll<'input>) {
+        // Headers are reordered so that the title comes first. Comments are kept above the header they precede.
+        let mut headers: Vec<_> = node
+            .header_all()
+            .iter()
+            .map(|header| {
+                let line = source_line(&*header.start());
+                let comments = self.take_comments_before(line);
+                self.next_source_line = line + 1;
+                let key = header.header_key.as_ref().unwrap_or_bug().get_text();
+                let value = header
+                    .header_value
+                    .as_ref()
+                    .map(|value| value.get_text().trim().to_owned())
+                    .unwrap_or_default();
+                let text = if value.is_empty() {
+                    format!("{key}:")
+                } else {
+                    format!("{key}: {value}")
+                };
+                (key == "title", comments, text)
+            })
+            .collect();
+        headers.sort_by_key(|(is_title, ..)| !is_title);
+        for (_, comments, text) in headers {
+            for comment in comments {
+                self.push(LineKind::Other, comment);
+            }
+            self.push(LineKind::Other, text);
+        }
+
+        let body_start = node.BODY_START().unwrap_or_bug();
+        let body_start_line = source_line(&*body_start.symbol);
+        for comment in self.take_comments_before(body_start_line) {
+            self.push(LineKind::Other, comment);
+        }
+        self.next_source_line = body_start_line + 1;
+        self.push(LineKind::BodyStart, "---".to_owned());
+
+        if let Some(body) = node.body() {
+            for statement in body.statement_all() {
+                self.statement(&statement, 0);
+            }
+        }
+
+        let body_end = node.BODY_END().unwrap_or_bug();
+        let body_end_line = source_line(&*body_end.symbol);
+        self.emit_comments_before(0, body_end_line);
+        self.next_source_line = body_end_line + 1;
+        self.push(LineKind::BodyEnd, "===".to_owned());
+    }
+
+    fn statement(&mut self, statement: &StatementContextAll<'input>, depth: usize) {
+        if let Some(line_statement) = statement.line_statement() {
+            let line = self.line_statement(&line_statement);
+            self.emit_line_statement(depth, &line_statement, line);
+        } else if let Some(if_statement) = statement.if_statement() {
+            self.if_statement(&if_statement, depth);
+        } else if let Some(shortcut_option_statement) = statement.shortcut_option_statement() {
+            for shortcut_option in shortcut_option_statement.shortcut_option_all() {
+                let line_statement = shortcut_option.line_statement().unwrap_or_bug();
+                let line = format!("-> {}", self.line_statement(&line_statement));
+                let arrow = shortcut_option.SHORTCUT_ARROW().unwrap_or_bug();
+                self.emit_comments_before(depth, source_line(&*arrow.symbol));
+                self.emit_line_statement(depth, &line_statement, line);
+                for statement in shortcut_option.statement_all() {
+                    self.statement(&statement, depth + 1);
+                }
+            }
+        } else if let Some(command_statement) = statement.command_statement() {
+            let command = self.command_statement(&command_statement);
+            self.emit(
+                depth,
+                depth,
+                &*command_statement.start(),
+                &*command_statement.stop(),
+                command,
+            );
+        } else if let Some(set_statement) = statement.set_statement() {
+            self.emit_tokens(depth, &*set_statement.start(), &*set_statement.stop());
+        } else if let Some(call_statement) = statement.call_statement() {
+            self.emit_tokens(depth, &*call_statement.start(), &*call_statement.stop());
+        } else if let Some(declare_statement) = statement.declare_statement() {
+            self.emit_tokens(
+                depth,
+                &*declare_statement.start(),
+                &*declare_statement.stop(),
+            );
+        } else if let Some(jump_statement) = statement.jump_statement() {
+            self.emit_tokens(depth, &*jump_statement.start(), &*jump_statement.stop());
+        } else {
+            // An indented block that does not belong to a shortcut option has no meaning, so it is flattened.
+            for statement in statement.statement_all() {
+                self.statement(&statement, depth);
+            }
+        }
+    }
+
+    fn if_statement(&mut self, if_statement: &If_statementContextAll<'input>, depth: usize) {
+        let if_clause = if_statement.if_clause().unwrap_or_bug();
+        self.emit_command(
+            depth,
+            depth,
+            &if_clause.COMMAND_START().unwrap_or_bug(),
+            &if_clause.COMMAND_END().unwrap_or_bug(),
+        );
+        for statement in if_clause.statement_all() {
+            self.statement(&statement, depth + 1);
+        }
+        for else_if_clause in if_statement.else_if_clause_all() {
+            self.emit_command(
+                depth,
+                depth + 1,
+                &else_if_clause.COMMAND_START().unwrap_or_bug(),
+                &else_if_clause.COMMAND_END().unwrap_or_bug(),
+            );
+            for statement in else_if_clause.statement_all() {
+                self.statement(&statement, depth + 1);
+            }
+        }
+        if let Some(else_clause) = if_statement.else_clause() {
+            self.emit_command(
+                depth,
+                depth + 1,
+                &else_clause.COMMAND_START().unwrap_or_bug(),
+                &else_clause.COMMAND_END().unwrap_or_bug(),
+            );
+            for statement in else_clause.statement_all() {
+                self.statement(&statement, depth + 1);
+            }
+        }
+        self.emit_command(
+            depth,
+            depth + 1,
+            &if_statement.COMMAND_START().unwrap_or_bug(),
+            &if_statement.COMMAND_END().unwrap_or_bug(),
+        );
+    }
+
+    /// Formats the text, condition and hashtags of a line, e.g. `Hello, {$name}! <<if $met>> #line:abc`.
+    fn line_statement(&self, line_statement: &Line_statementContextAll<'input>) -> String {
+        let formatted_text = line_statement.line_formatted_text().unwrap_or_bug();
+        let mut start = formatted_text.start().get_start();
+        // An escaped character at the very start of the line, e.g. `\-> Not an option`,
+        // is preceded by a hidden escape token that is not part of the formatted text.
+        for hidden in self
+            .file
+            .tokens()
+            .get_hidden_tokens_to_left(formatted_text.start().get_token_index(), -1)
+            .iter()
+            .rev()
+        {
+            if hidden.get_token_type() == yarnspinnerlexer::TEXT_ESCAPE
+                && hidden.get_stop() + 1 == start
+            {
+                start = hidden.get_start();
+            }
+        }
+        let expressions = formatted_text.expression_all();
+        let mut line =
+            self.text_with_expressions(start, formatted_text.stop().get_stop(), &expressions);
+        if let Some(line_condition) = line_statement.line_condition() {
+            line.push(' ');
+            line.push_str(&self.join_tokens(&*line_condition.start(), &*line_condition.stop()));
+        }
+        for hashtag in line_statement.hashtag_all() {
+            let text = hashtag.text.as_ref().unwrap_or_bug().get_text();
+            line.push_str(&format!(" #{text}"));
+        }
+        line
+    }
+
+    fn emit_line_statement(
+        &mut self,
+        depth: usize,
+        line_statement: &Line_statementContextAll<'input>,
+        line: String,
+    ) {
+        let newline = line_statement.NEWLINE().unwrap_or_bug();
+        let last_token = self.previous_default_token(newline.symbol.get_token_index());
+        let first_token = line_statement.start();
+        self.emit(depth, depth, &*first_token, &last_token, line);
+    }
+
+    /// Formats a generic command such as `<<wait 2>>`, whose text is kept as-is apart from inline expressions.
+    fn command_statement(&self, command_statement: &Command_statementContextAll<'input>) -> String {
+        let command_start = command_statement.COMMAND_START().unwrap_or_bug();
+        let command_end = command_statement.COMMAND_TEXT_END().unwrap_or_bug();
+        let expressions = command_statement
+            .command_formatted_text()
+            .map(|text| text.expression_all())
+            .unwrap_or_default();
+        let text = self.text_with_expressions(
+            command_start.symbol.get_stop() + 1,
+            command_end.symbol.get_start() - 1,
+            &expressions,
+        );
+        let mut command = format!("<<{text}>>");
+        for hashtag in command_statement.hashtag_all() {
+            let text = hashtag.text.as_ref().unwrap_or_bug().get_text();
+            command.push_str(&format!(" #{text}"));
+        }
+        command
+    }
+
+    fn emit_command(
+        &mut self,
+        depth: usize,
+        comment_depth: usize,
+        command_start: &Rc<TerminalNode<'input, YarnSpinnerParserContextType>>,
+        command_end: &Rc<TerminalNode<'input, YarnSpinnerParserContextType>>,
+    ) {
+        let text = self.join_tokens(&*command_start.symbol, &*command_end.symbol);
+        self.emit(
+            depth,
+            comment_depth,
+            &*command_start.symbol,
+            &*command_end.symbol,
+            text,
+        );
+    }
+
+    fn emit_tokens(
+        &mut self,
+        depth: usize,
+        first: &(impl Token + ?Sized),
+        last: &(impl Token + ?Sized),
+    ) {
+        let text = self.join_tokens(first, last);
+        self.emit(depth, depth, first, last, text);
+    }
+
+    /// Emits a formatted line spanning the original source from `first` to `last`, together with any comments that precede it
+    /// or follow it on the same line.
+    fn emit(
+        &mut self,
+        depth: usize,
+        comment_depth: usize,
+        first: &(impl Token + ?Sized),
+        last: &(impl Token + ?Sized),
+        mut text: String,
+    ) {
+        self.emit_comments_before(comment_depth, source_line(first));
+        let last_line = source_line(last);
+        let trailing_comment = self
+            .file
+            .tokens()
+            .get_hidden_tokens_to_right(last.get_token_index(), -1)
+            .into_iter()
+            .find(|token| is_comment(token.get_token_type()) && source_line(token) == last_line);
+        if let Some(comment) = trailing_comment {
+            text.push(' ');
+            text.push_str(comment.get_text().trim());
+        }
+        self.push(LineKind::Other, indent(depth, &text));
+        self.next_source_line = self.next_source_line.max(last_line + 1);
+    }
+
+    /// Emits the comments and blank lines between the last formatted line and `line`.
+    fn emit_comments_before(&mut self, depth: usize, line: usize) {
+        for index in self.next_source_line..line.min(self.source_lines.len()) {
+            let text = self.source_lines[index].trim();
+            if text.is_empty() {
+                self.push(LineKind::Blank, String::new());
+            } else {
+                self.push(LineKind::Other, indent(depth, text));
+            }
+        }
+        self.next_source_line = self.next_source_line.max(line);
+    }
+
+    /// Takes the comments between the last formatted line and `line`, dropping blank lines.
+    fn take_comments_before(&mut self, line: usize) -> Vec<String> {
+        let comments = (self.next_source_line..line.min(self.source_lines.len()))
+            .map(|index| self.source_lines[index].trim())
+            .filter(|text| !text.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+        self.next_source_line = self.next_source_line.max(line);
+        comments
+    }
+
+    /// Returns the original text between the chars `start` and `stop` (inclusive),
+    /// with every inline expression and its braces replaced by its formatted version.
+    fn text_with_expressions(
+        &self,
+        start: isize,
+        stop: isize,
+        expressions: &[Rc<ExpressionContextAll<'input>>],
+    ) -> String {
+        let mut replacements: Vec<_> = expressions
+            .iter()
+            .map(|expression| {
+                let open_brace = self.previous_default_token(expression.start().get_token_index());
+                let close_brace = self.next_default_token(expression.stop().get_token_index());
+                let mut replacement = self.join_tokens(&open_brace, &close_brace);
+                if let Some(format_specifier) = self
+                    .file
+                    .format_specifiers
+                    .get(expression.stop().get_stop())
+                {
+                    // Right before the closing brace
+                    replacement.insert_str(replacement.len() - 1, &format!(":{format_specifier}"));
+                }
+                (open_brace.get_start(), close_brace.get_stop(), replacement)
+            })
+            .collect();
+        replacements.sort_by_key(|(start, ..)| *start);
+
+        let mut text = String::new();
+        let mut position = start.max(0);
+        let mut replacements = replacements.into_iter().peekable();
+        while position <= stop {
+            if let Some((_, replacement_stop, replacement)) =
+                replacements.next_if(|(replacement_start, ..)| *replacement_start == position)
+            {
+                text.push_str(&replacement);
+                position = replacement_stop + 1;
+            } else {
+                text.extend(self.chars.get(position as usize));
+                position += 1;
+            }
+        }
+        text.trim().to_owned()
+    }
+
+    /// Joins the tokens from `first` to `last` (inclusive) on the default channel, normalizing the whitespace between them.
+    fn join_tokens(&self, first: &(impl Token + ?Sized), last: &(impl Token + ?Sized)) -> String {
+        let tokens = self.file.tokens();
+        let mut text = String::new();
+        let mut previous: Option<(isize, bool)> = None;
+        for index in first.get_token_index()..=last.get_token_index() {
+            let token = tokens.get(index);
+            let token_text = token.get_text().trim();
+            if token.get_channel() != TOKEN_DEFAULT_CHANNEL || token_text.is_empty() {
+                continue;
+            }
+            let token_type = token.get_token_type();
+            let is_unary_symbol = match token_type {
+                yarnspinnerlexer::OPERATOR_LOGICAL_NOT => token_text == "!",
+                yarnspinnerlexer::OPERATOR_MATHS_SUBTRACTION => {
+                    !previous.is_some_and(|(previous_type, _)| ends_operand(previous_type))
+                }
+                _ => false,
+            };
+            if let Some((previous_type, previous_is_unary_symbol)) = previous
+                && !previous_is_unary_symbol
+                && separated_by_space(previous_type, token_type)
+            {
+                text.push(' ');
+            }
+            text.push_str(token_text);
+            previous = Some((token_type, is_unary_symbol));
+        }
+        text
+    }
+
+    fn previous_default_token(&self, token_index: isize) -> CommonToken<'input> {
+        let tokens = self.file.tokens();
+        let index = (0..token_index)
+            .rev()
+            .find(|&index| tokens.get(index).get_channel() == TOKEN_DEFAULT_CHANNEL)
+            .unwrap_or_bug();
+        *tokens.get(index).clone()
+    }
+
+    fn next_default_token(&self, token_index: isize) -> CommonToken<'input> {
+        let tokens = self.file.tokens();
+        let index = (token_index + 1..tokens.size())
+            .find(|&index| tokens.get(index).get_channel() == TOKEN_DEFAULT_CHANNEL)
+            .unwrap_or_bug();
+        *tokens.get(index).clone()
+    }
+
+    fn push(&mut self, kind: LineKind, text: String) {
+        self.output.push((kind, text));
+    }
+
+    /// Joins the output, collapsing runs of blank lines and removing the ones at the start and end of the file and bodies.
+    fn into_source(self, line_ending: &str) -> String {
+        let mut lines: Vec<(LineKind, String)> = Vec::with_capacity(self.output.len());
+        for (kind, text) in self.output {
+            let previous = lines.last().map(|(kind, _)| *kind);
+            match (previous, kind) {
+                (None | Some(LineKind::Blank | LineKind::BodyStart), LineKind::Blank) => {}
+                (Some(LineKind::Blank), LineKind::BodyEnd) => {
+                    lines.pop();
+                    lines.push((kind, text));
+                }
+                _ => lines.push((kind, text)),
+            }
+        }
+        if lines
+            .last()
+            .is_some_and(|(kind, _)| *kind == LineKind::Blank)
+        {
+            lines.pop();
+        }
+        let mut source = lines
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect::<Vec<_>>()
+            .join(line_ending);
+        source.push_str(line_ending);
+        source
+    }
+}
+
+fn source_line(token: &(impl Token + ?Sized)) -> usize {
+    token.get_line_as_usize().saturating_sub(1)
+}
+
+fn indent(depth: usize, text: &str) -> String {
+    format!("{}{text}", INDENTATION.repeat(depth))
+}
+
+fn is_comment(token_type: isize) -> bool {
+    matches!(
+        token_type,
+        yarnspinnerlexer::COMMENT
+            | yarnspinnerlexer::TEXT_COMMENT
+            | yarnspinnerlexer::TEXT_COMMANDHASHTAG_COMMENT
+    )
+}
+
+/// Whether a token of this type can be the last token of an operand, in which case a following `-` is a binary minus.
+fn ends_operand(token_type: isize) -> bool {
+    matches!(
+        token_type,
+        yarnspinnerlexer::NUMBER
+            | yarnspinnerlexer::STRING
+            | yarnspinnerlexer::VAR_ID
+            | yarnspinnerlexer::KEYWORD_TRUE
+            | yarnspinnerlexer::KEYWORD_FALSE
+            | yarnspinnerlexer::KEYWORD_NULL
+            | yarnspinnerlexer::RPAREN
+            | yarnspinnerlexer::ID
+    )
+}
+
+fn separated_by_space(previous: isize, current: isize) -> bool {
+    let opens = matches!(
+        previous,
+        yarnspinnerlexer::COMMAND_START
+            | yarnspinnerlexer::EXPRESSION_START
+            | yarnspinnerlexer::COMMAND_EXPRESSION_START
+            | yarnspinnerlexer::LPAREN
+            | yarnspinnerlexer::DOT
+    );
+    let closes = matches!(
+        current,
+        yarnspinnerlexer::COMMAND_END
+            | yarnspinnerlexer::COMMAND_TEXT_END
+            | yarnspinnerlexer::EXPRESSION_END
+            | yarnspinnerlexer::RPAREN
+            | yarnspinnerlexer::COMMA
+            | yarnspinnerlexer::DOT
+    );
+    let is_call = previous == yarnspinnerlexer::FUNC_ID && current == yarnspinnerlexer::LPAREN;
+    !(opens || closes || is_call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        let file = File {
+            file_name: "test.yarn".to_owned(),
+            source: source.to_owned(),
+        };
+        format_file(&file).unwrap()
+    }
+
+    fn assert_formats_to(expected: &str, source: &str) {
+        let formatted = format(source);
+        assert_eq!(expected, formatted);
+        assert_eq!(expected, format(&formatted), "Formatting is not idempotent");
+    }
+
+    #[test]
+    fn normalizes_headers() {
+        assert_formats_to(
+            "title: Start\ntags: intro\ncolor:\n---\nHello\n===\n",
+            "tags:intro\ncolor:\ntitle:   Start  \n---\nHello\n===\n",
+        );
+    }
+
+    #[test]
+    fn indents_options_and_if_blocks() {
+        assert_formats_to(
+            "title: Start\n---\n<<if $a>>\n    -> One\n        Hi\n    -> Two\n<<else>>\n    Bye\n<<endif>>\n===\n",
+            "title: Start\n---\n<<if $a>>\n-> One\n  Hi\n-> Two\n<<else>>\n      Bye\n   <<endif>>\n===\n",
+        );
+    }
+
+    #[test]
+    fn normalizes_spacing_in_expressions() {
+        assert_formats_to(
+            "title: Start\n---\n<<set $gold = ($gold + 1) * -2>>\n<<if !$met && visited(\"Shop\", 1) >= -1>>\nYou have {$gold * 2} coins.\n<<endif>>\n===\n",
+            "title: Start\n---\n<<set $gold=( $gold+1 )*-2>>\n<<if !$met&&visited( \"Shop\" ,1)>=-1 >>\nYou have {  $gold*2 } coins.\n<<endif>>\n===\n",
+        );
+    }
+
+    #[test]
+    fn keeps_format_specifiers() {
+        assert_formats_to(
+            "title: Start\n---\nYou have {$gold + 1:N0} coins.\n===\n",
+            "title: Start\n---\nYou have {  $gold+1 :N0} coins.\n===\n",
+        );
+    }
+
+    #[test]
+    fn keeps_line_ids_hashtags_and_comments() {
+        assert_formats_to(
+            "// A comment\ntitle: Start\n---\n// Greeting\nHi! <<if $a>> #line:abc #happy // trailing\n<<wait 2>> #tag\n===\n\ntitle: Other\n---\n\\-> Not an option\n===\n",
+            "// A comment\ntitle: Start\n---\n\n\n   // Greeting\nHi!   <<if $a>>   #line:abc    #happy // trailing\n<<  wait 2 >>   #tag\n\n===\ntitle: Other\n---\n\\-> Not an option\n===",
+        );
+    }
+
+    #[test]
+    fn keeps_blank_lines_that_separate_option_groups() {
+        assert_formats_to(
+            "title: Start\n---\n-> A\n-> B\n\n-> C\n===\n",
+            "title: Start\n---\n-> A\n-> B\n\n\n-> C\n===\n",
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let file = File {
+            file_name: "test.yarn".to_owned(),
+            source: "title: Start\n---\n<<if true>>\n===\n".to_owned(),
+        };
+        assert!(format_file(&file).is_err());
+    }
+}
@@ -13,27 +13,40 @@ pub(crate) mod compilation_steps;
 pub(crate) mod compiler;
 pub(crate) mod error_strategy;
 mod file_parse_result;
+pub mod formatter;
 pub(crate) mod listeners;
 mod output;
 mod parser;
 pub(crate) mod parser_rule_context_ext;
 mod string_table_manager;
 pub(crate) mod token_ext;
+#[cfg(feature = "translation")]
+pub mod translation;
 pub(crate) mod visitors;
+pub mod voice_over;
 
 pub use crate::compiler::Result;
 
 pub mod prelude {
     //! Everything you need to get started with the Yarn Spinner compiler.
+    #[cfg(feature = "translation")]
+    pub use crate::translation::{TranslationCatalog, TranslationError, TranslationUnit};
     pub(crate) use crate::{
         compiler::antlr_rust_ext::*, compiler::run_compilation::*, compiler::utils::*,
         file_parse_result::*, parser::*, parser_rule_context_ext::*, string_table_manager::*,
         token_ext::*,
     };
     pub use crate::{
-        compiler::{CompilationType, Compiler, File},
-        listeners::{Diagnostic, DiagnosticSeverity, DiagnosticVec},
+        compiler::{CompilationCache, CompilationType, Compiler, File},
+        formatter::{format_file, is_formatted},
+        listeners::{
+            ColorChoice, Diagnostic, DiagnosticCode, DiagnosticRenderer, DiagnosticSeverity,
+            DiagnosticVec, RelatedLocation, SuggestedReplacement, UnknownDiagnosticCodeError,
+        },
         output::*,
+        voice_over::{
+            CharacterScript, RecordingReport, VoiceOverError, VoiceOverLine, VoiceOverScript,
+        },
     };
     pub(crate) use yarnspinner_core::prelude::*;
     pub(crate) use yarnspinner_internal_shared::prelude::*;
@@ -51,6 +51,7 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for LexerErrorListe
         };
         self.diagnostics.borrow_mut().push(
             Diagnostic::from_message(msg)
+                .with_code(DiagnosticCode::InvalidToken)
                 .with_range(range)
                 .with_file_name(&self.file_name),
         );
@@ -94,6 +95,7 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
             character: (column + 1) as usize,
         };
         let mut diagnostic = Diagnostic::from_message(msg)
+            .with_code(DiagnosticCode::SyntaxError)
             .with_file_name(&self.file.file_name)
             .with_range(range);
         if let Some(offending_symbol) = offending_symbol {
@@ -1,14 +1,22 @@
 use crate::parser_rule_context_ext::ParserRuleContextExt;
 use crate::prelude::*;
-use annotate_snippets::{Annotation, AnnotationType, Renderer, Slice, Snippet, SourceAnnotation};
 use antlr_rust::rule_context::CustomRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::token_factory::TokenFactory;
+pub use code::*;
 use core::fmt;
+pub use related::*;
+pub use renderer::*;
 use std::fmt::{Display, Formatter};
 use std::ops::Range;
 use yarnspinner_core::prelude::*;
 
+mod code;
+mod related;
+mod renderer;
+#[cfg(feature = "serde")]
+mod serialization;
+
 /// A diagnostic message that describes an error, warning or informational
 /// message that the user can take action on.
 ///
@@ -46,6 +54,17 @@ pub struct Diagnostic {
 
     /// The line the context starts on.
     pub start_line: usize,
+
+    /// The stable code identifying the kind of issue, e.g. `YS0001`.
+    ///
+    /// All diagnostics emitted by the [`Compiler`] have a code.
+    pub code: Option<DiagnosticCode>,
+
+    /// Other locations in the source code that help explain the issue.
+    pub related_locations: Vec<RelatedLocation>,
+
+    /// Edits that would fix the issue.
+    pub suggestions: Vec<SuggestedReplacement>,
 }
 
 impl Diagnostic {
@@ -57,6 +76,9 @@ impl Diagnostic {
             context: Default::default(),
             severity: Default::default(),
             start_line: Default::default(),
+            code: Default::default(),
+            related_locations: Default::default(),
+            suggestions: Default::default(),
         }
     }
 
@@ -103,81 +125,63 @@ impl Diagnostic {
         self.severity = severity;
         self
     }
+
+    pub(crate) fn with_code(mut self, code: DiagnosticCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub(crate) fn with_related_location(mut self, related_location: RelatedLocation) -> Self {
+        self.related_locations.push(related_location);
+        self
+    }
+
+    pub(crate) fn with_suggestion(mut self, suggestion: SuggestedReplacement) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
 }
 
 impl Display for Diagnostic {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let label = &self.message;
-        let annotation_type = match self.severity {
-            DiagnosticSeverity::Error => AnnotationType::Error,
-            DiagnosticSeverity::Warning => AnnotationType::Warning,
-        };
-        let snippet = Snippet {
-            title: Some(Annotation {
-                label: Some(label),
-                id: None,
-                annotation_type,
-            }),
-            footer: vec![],
-            slices: vec![Slice {
-                source: self.context.as_deref().unwrap_or("<unknown line>"),
-                line_start: self.start_line + 1,
-                origin: self.file_name.as_deref(),
-                fold: false,
-                annotations: vec![SourceAnnotation {
-                    label: "",
-                    annotation_type,
-                    range: convert_absolute_range_to_relative(self),
-                }],
-            }],
-        };
-        let renderer = Renderer::styled();
-        let annotations = renderer.render(snippet);
-        writeln!(f, "{annotations}")?;
-
-        Ok(())
+        let rendered = DiagnosticRenderer::new()
+            .with_color_choice(ColorChoice::Never)
+            .render(self);
+        writeln!(f, "{rendered}")
     }
 }
 
-fn convert_absolute_range_to_relative(diagnostic: &Diagnostic) -> (usize, usize) {
-    let Some(range) = diagnostic.range.as_ref() else {
-        return (0, 0);
-    };
-    let Some(context) = diagnostic.context.as_ref() else {
-        return (0, 0);
-    };
-
-    let relative_start_line = range.start.line - diagnostic.start_line;
-    let annotated_lines = range.end.line - range.start.line;
-    let line_lengths: Vec<_> = context
-        .lines()
-        .map(|line| line.chars().count() + 1)
-        .collect();
-    let relative_start =
-        line_lengths.iter().take(relative_start_line).sum::<usize>() + range.start.character;
-    let relative_end: usize = line_lengths
-        .iter()
-        .take(relative_start_line + annotated_lines)
-        .sum::<usize>()
-        + range.end.character
-        // - 1 because the Diagnostic range is exclusive, but the annotation range is inclusive
-        - 1;
-    let mut char_indices = context.char_indices().map(|(i, _)| i);
-    let byte_start = char_indices.clone().nth(relative_start).unwrap();
-    let byte_end = char_indices.nth(relative_end).unwrap_or(byte_start);
-    (byte_start, byte_end)
-}
-
 /// Trait implemented for `Vec<Diagnostic>` to provide utility methods.
 pub trait DiagnosticVec {
     /// Returns `true` if any of the [`Diagnostic`]s in the vector are of [`DiagnosticSeverity::Error`].
     fn has_errors(&self) -> bool;
+
+    /// Serializes the [`Diagnostic`]s into a pretty-printed JSON array.
+    /// The [`Diagnostic::code`]s are written as strings, e.g. `"YS0001"`.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> String;
+
+    /// Serializes the [`Diagnostic`]s into a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+    /// which is understood by most CI systems for annotating code reviews.
+    /// Related locations and suggested replacements are emitted as SARIF related locations and fixes.
+    #[cfg(feature = "serde")]
+    fn to_sarif(&self) -> String;
 }
 
 impl DiagnosticVec for Vec<Diagnostic> {
     fn has_errors(&self) -> bool {
         self.iter().any(|d| d.severity == DiagnosticSeverity::Error)
     }
+
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> String {
+        serialization::diagnostics_to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    fn to_sarif(&self) -> String {
+        serialization::diagnostics_to_sarif(self)
+    }
 }
 
 /// The severity of the issue.
@@ -0,0 +1,266 @@
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use core::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A stable identifier for the kind of issue a [`Diagnostic`](crate::prelude::Diagnostic) describes.
+///
+/// Codes never change their meaning between versions, so tooling can rely on them to categorize diagnostics
+/// instead of parsing [`Diagnostic::message`](crate::prelude::Diagnostic::message). They are rendered as `YS` followed by a four-digit number, e.g. `YS0001`.
+/// New codes may be added in the future, so matches on this type should include a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+#[non_exhaustive]
+pub enum DiagnosticCode {
+    /// `YS0001`: The parser encountered input that does not match the Yarn grammar.
+    SyntaxError,
+    /// `YS0002`: The lexer encountered characters it could not turn into a token.
+    InvalidToken,
+    /// `YS0003`: A line is indented with a mix of tabs and spaces.
+    MixedIndentation,
+    /// `YS0004`: A command spans multiple lines.
+    NewlineInCommand,
+    /// `YS0005`: A node has no `title` header.
+    MissingNodeTitle,
+    /// `YS0006`: More than one node has the same title.
+    DuplicateNodeName,
+    /// `YS0007`: A node title contains characters that are not allowed.
+    InvalidNodeName,
+    /// `YS0008`: The same `#line:` ID is used by more than one line.
+    DuplicateLineId,
+    /// `YS0009`: A variable is explicitly declared more than once.
+    DuplicateVariableDeclaration,
+    /// `YS0010`: A declaration names a type that does not exist.
+    UnknownType,
+    /// `YS0011`: The explicit type of a declaration does not match its default value.
+    DeclarationTypeMismatch,
+    /// `YS0012`: The default value of a declaration is not a constant.
+    NonConstantDeclaration,
+    /// `YS0013`: A number literal could not be parsed.
+    InvalidNumber,
+    /// `YS0014`: The `null` literal was used.
+    NullValue,
+    /// `YS0015`: The type of a variable could not be inferred from its usage.
+    UndeterminedVariableType,
+    /// `YS0016`: The type of an expression could not be inferred from its terms.
+    UndeterminedExpressionType,
+    /// `YS0017`: A variable was assigned a value of a different type.
+    InvalidAssignment,
+    /// `YS0018`: A function was called with the wrong number of parameters.
+    ParameterCountMismatch,
+    /// `YS0019`: A function was called with a parameter of the wrong type.
+    ParameterTypeMismatch,
+    /// `YS0020`: The terms of an operation have different types.
+    MixedTermTypes,
+    /// `YS0021`: An operator or statement was used with a type that does not support it.
+    UnsupportedOperation,
+    /// `YS0022`: A declaration has no default value.
+    MissingDefaultValue,
+    /// `YS0023`: A `<<jump>>` statement refers to a node that does not exist in the compilation.
+    UnknownNode,
+    /// `YS0024`: An inline expression in a line has a format specifier that is not supported.
+    InvalidFormatSpecifier,
+    /// `YS0025`: Every option of an option group has a condition, so there may be no option to choose.
+    NoUnconditionalOption,
+}
+
+impl DiagnosticCode {
+    /// All codes, in ascending order.
+    pub const ALL: &'static [DiagnosticCode] = &[
+        Self::SyntaxError,
+        Self::InvalidToken,
+        Self::MixedIndentation,
+        Self::NewlineInCommand,
+        Self::MissingNodeTitle,
+        Self::DuplicateNodeName,
+        Self::InvalidNodeName,
+        Self::DuplicateLineId,
+        Self::DuplicateVariableDeclaration,
+        Self::UnknownType,
+        Self::DeclarationTypeMismatch,
+        Self::NonConstantDeclaration,
+        Self::InvalidNumber,
+        Self::NullValue,
+        Self::UndeterminedVariableType,
+        Self::UndeterminedExpressionType,
+        Self::InvalidAssignment,
+        Self::ParameterCountMismatch,
+        Self::ParameterTypeMismatch,
+        Self::MixedTermTypes,
+        Self::UnsupportedOperation,
+        Self::MissingDefaultValue,
+        Self::UnknownNode,
+        Self::InvalidFormatSpecifier,
+        Self::NoUnconditionalOption,
+    ];
+
+    /// The stable textual representation of this code, e.g. `YS0001`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SyntaxError => "YS0001",
+            Self::InvalidToken => "YS0002",
+            Self::MixedIndentation => "YS0003",
+            Self::NewlineInCommand => "YS0004",
+            Self::MissingNodeTitle => "YS0005",
+            Self::DuplicateNodeName => "YS0006",
+            Self::InvalidNodeName => "YS0007",
+            Self::DuplicateLineId => "YS0008",
+            Self::DuplicateVariableDeclaration => "YS0009",
+            Self::UnknownType => "YS0010",
+            Self::DeclarationTypeMismatch => "YS0011",
+            Self::NonConstantDeclaration => "YS0012",
+            Self::InvalidNumber => "YS0013",
+            Self::NullValue => "YS0014",
+            Self::UndeterminedVariableType => "YS0015",
+            Self::UndeterminedExpressionType => "YS0016",
+            Self::InvalidAssignment => "YS0017",
+            Self::ParameterCountMismatch => "YS0018",
+            Self::ParameterTypeMismatch => "YS0019",
+            Self::MixedTermTypes => "YS0020",
+            Self::UnsupportedOperation => "YS0021",
+            Self::MissingDefaultValue => "YS0022",
+            Self::UnknownNode => "YS0023",
+            Self::InvalidFormatSpecifier => "YS0024",
+            Self::NoUnconditionalOption => "YS0025",
+        }
+    }
+
+    /// A short, human-readable name of this code in `PascalCase`, e.g. `SyntaxError`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SyntaxError => "SyntaxError",
+            Self::InvalidToken => "InvalidToken",
+            Self::MixedIndentation => "MixedIndentation",
+            Self::NewlineInCommand => "NewlineInCommand",
+            Self::MissingNodeTitle => "MissingNodeTitle",
+            Self::DuplicateNodeName => "DuplicateNodeName",
+            Self::InvalidNodeName => "InvalidNodeName",
+            Self::DuplicateLineId => "DuplicateLineId",
+            Self::DuplicateVariableDeclaration => "DuplicateVariableDeclaration",
+            Self::UnknownType => "UnknownType",
+            Self::DeclarationTypeMismatch => "DeclarationTypeMismatch",
+            Self::NonConstantDeclaration => "NonConstantDeclaration",
+            Self::InvalidNumber => "InvalidNumber",
+            Self::NullValue => "NullValue",
+            Self::UndeterminedVariableType => "UndeterminedVariableType",
+            Self::UndeterminedExpressionType => "UndeterminedExpressionType",
+            Self::InvalidAssignment => "InvalidAssignment",
+            Self::ParameterCountMismatch => "ParameterCountMismatch",
+            Self::ParameterTypeMismatch => "ParameterTypeMismatch",
+            Self::MixedTermTypes => "MixedTermTypes",
+            Self::UnsupportedOperation => "UnsupportedOperation",
+            Self::MissingDefaultValue => "MissingDefaultValue",
+            Self::UnknownNode => "UnknownNode",
+            Self::InvalidFormatSpecifier => "InvalidFormatSpecifier",
+            Self::NoUnconditionalOption => "NoUnconditionalOption",
+        }
+    }
+
+    /// A one-sentence description of the issue this code stands for.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::SyntaxError => "The input does not match the Yarn grammar.",
+            Self::InvalidToken => "The input contains characters that cannot be tokenized.",
+            Self::MixedIndentation => "A line is indented with both tabs and spaces.",
+            Self::NewlineInCommand => "Commands must not span multiple lines.",
+            Self::MissingNodeTitle => "Every node needs a title header.",
+            Self::DuplicateNodeName => "Node titles must be unique.",
+            Self::InvalidNodeName => "A node title contains illegal characters.",
+            Self::DuplicateLineId => "Line IDs must be unique.",
+            Self::DuplicateVariableDeclaration => "A variable may only be declared once.",
+            Self::UnknownType => "A declaration uses a type that does not exist.",
+            Self::DeclarationTypeMismatch => {
+                "The explicit type of a declaration does not match its value."
+            }
+            Self::NonConstantDeclaration => "Variable declarations must use constant values.",
+            Self::InvalidNumber => "A number literal could not be parsed.",
+            Self::NullValue => "Null is not a permitted value.",
+            Self::UndeterminedVariableType => "The type of a variable cannot be inferred.",
+            Self::UndeterminedExpressionType => "The type of an expression cannot be inferred.",
+            Self::InvalidAssignment => "A variable was assigned a value of the wrong type.",
+            Self::ParameterCountMismatch => "A function received the wrong number of parameters.",
+            Self::ParameterTypeMismatch => "A function received a parameter of the wrong type.",
+            Self::MixedTermTypes => "All terms of an operation must have the same type.",
+            Self::UnsupportedOperation => "An operation is not supported by the given type.",
+            Self::MissingDefaultValue => "A declaration has no default value.",
+            Self::UnknownNode => "A jump refers to a node that does not exist.",
+            Self::InvalidFormatSpecifier => {
+                "An inline expression uses a format specifier that is not supported."
+            }
+            Self::NoUnconditionalOption => {
+                "All options of an option group have conditions that might all fail."
+            }
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DiagnosticCode {
+    type Err = UnknownDiagnosticCodeError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|code| code.as_str() == s)
+            .copied()
+            .ok_or_else(|| UnknownDiagnosticCodeError(s.to_owned()))
+    }
+}
+
+/// The error returned when parsing a string that is not a known [`DiagnosticCode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownDiagnosticCodeError(pub String);
+
+impl Display for UnknownDiagnosticCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown diagnostic code: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownDiagnosticCodeError {}
+
+#[cfg(feature = "serde")]
+impl Serialize for DiagnosticCode {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DiagnosticCode {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_and_round_trip() {
+        let codes: HashSet<_> = DiagnosticCode::ALL.iter().map(|c| c.as_str()).collect();
+        assert_eq!(DiagnosticCode::ALL.len(), codes.len());
+        for code in DiagnosticCode::ALL {
+            assert_eq!(Ok(*code), code.as_str().parse());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::prelude::*;
+use std::ops::Range;
+use yarnspinner_core::prelude::*;
+
+/// A location in the source code that is relevant to a [`Diagnostic`] without being the place where the issue occurred,
+/// e.g. the first declaration of a variable that was declared twice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct RelatedLocation {
+    /// The path, URI or file-name of the location.
+    pub file_name: Option<String>,
+
+    /// The range of the file indicated by [`RelatedLocation::file_name`] this location refers to.
+    pub range: Option<Range<Position>>,
+
+    /// Explains why this location is relevant.
+    pub message: String,
+}
+
+impl RelatedLocation {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            file_name: Default::default(),
+            range: Default::default(),
+        }
+    }
+
+    pub(crate) fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    pub(crate) fn with_range(mut self, range: impl Into<Range<Position>>) -> Self {
+        self.range = Some(range.into());
+        self
+    }
+}
+
+/// A machine-applicable edit that would fix the issue described by a [`Diagnostic`],
+/// e.g. replacing a misspelled variable name with the declared one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct SuggestedReplacement {
+    /// A human-readable description of the edit, e.g. ``"Did you mean `$gold`?"``.
+    pub message: String,
+
+    /// The path, URI or file-name of the file to edit.
+    pub file_name: Option<String>,
+
+    /// The range of text that should be replaced.
+    pub range: Range<Position>,
+
+    /// The text to insert in place of [`SuggestedReplacement::range`].
+    pub replacement: String,
+}
+
+impl SuggestedReplacement {
+    pub(crate) fn did_you_mean(
+        file_name: Option<String>,
+        range: Range<Position>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        let replacement = replacement.into();
+        Self {
+            message: format!("Did you mean `{replacement}`?"),
+            file_name,
+            range,
+            replacement,
+        }
+    }
+}
+
+/// Returns the candidate that is most similar to `name`, as long as it is similar enough to plausibly be a typo.
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    // Allow roughly one typo for every three characters, but always at least one.
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (levenshtein_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|(a_distance, a), (b_distance, b)| a_distance.cmp(b_distance).then(a.cmp(b)))
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_closest_match() {
+        let candidates = ["$gold", "$silver", "$golden_key"];
+        assert_eq!(Some("$gold"), closest_match("$gol", candidates));
+        assert_eq!(Some("$silver"), closest_match("$silvr", candidates));
+        assert_eq!(None, closest_match("$reputation", candidates));
+        assert_eq!(None, closest_match("$gold", candidates.into_iter().take(1)));
+    }
+}
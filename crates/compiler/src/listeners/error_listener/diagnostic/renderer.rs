@@ -0,0 +1,424 @@
+use crate::prelude::*;
+use annotate_snippets::{Annotation, AnnotationType, Renderer, Slice, Snippet, SourceAnnotation};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::ops::Range;
+use yarnspinner_core::prelude::*;
+
+/// Renders [`Diagnostic`]s for humans, in the style of `rustc`: a header with the [`DiagnosticCode`] and message,
+/// `file:line:column`, a snippet of the source code with the offending range underlined, and labels for any
+/// [`RelatedLocation`]s and [`SuggestedReplacement`]s.
+///
+/// When the sources of the compiled files are registered through [`DiagnosticRenderer::with_sources`],
+/// the snippet is cut directly out of the file using [`Diagnostic::range`], which also allows related locations in
+/// other files to be shown with their own snippets. Otherwise, [`Diagnostic::context`] is used.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use yarnspinner_compiler::prelude::*;
+/// let file = File {
+///     file_name: "intro.yarn".to_owned(),
+///     source: "title: Start\n---\n{$gold}\n===\n".to_owned(),
+/// };
+/// if let Err(error) = Compiler::new().add_file(file.clone()).compile() {
+///     let renderer = DiagnosticRenderer::new().with_source(&file);
+///     eprintln!("{}", renderer.render_all(&error.0));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiagnosticRenderer<'a> {
+    sources: HashMap<&'a str, &'a str>,
+    color_choice: ColorChoice,
+    context_lines: usize,
+}
+
+/// Whether a [`DiagnosticRenderer`] emits ANSI color codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ColorChoice {
+    /// Use colors if stderr is a terminal and the [`NO_COLOR`](https://no-color.org/) environment variable is not set.
+    #[default]
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors. The output is plain ASCII apart from the source code itself.
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves [`ColorChoice::Auto`] for the current environment.
+    pub fn use_colors(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_color && std::io::stderr().is_terminal()
+            }
+        }
+    }
+}
+
+impl Default for DiagnosticRenderer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> DiagnosticRenderer<'a> {
+    /// Creates a new renderer without any registered sources, using [`ColorChoice::Auto`]
+    /// and showing two lines of context above and below each issue.
+    pub fn new() -> Self {
+        Self {
+            sources: Default::default(),
+            color_choice: Default::default(),
+            context_lines: 2,
+        }
+    }
+
+    /// Registers the source of a file so that snippets can be cut from it.
+    /// The file is matched against [`Diagnostic::file_name`] and [`RelatedLocation::file_name`].
+    pub fn with_source(mut self, file: &'a File) -> Self {
+        self.sources.insert(&file.file_name, &file.source);
+        self
+    }
+
+    /// Registers the sources of multiple files. See [`DiagnosticRenderer::with_source`].
+    pub fn with_sources(mut self, files: impl IntoIterator<Item = &'a File>) -> Self {
+        for file in files {
+            self = self.with_source(file);
+        }
+        self
+    }
+
+    /// Sets whether ANSI color codes are emitted. Defaults to [`ColorChoice::Auto`].
+    pub fn with_color_choice(mut self, color_choice: ColorChoice) -> Self {
+        self.color_choice = color_choice;
+        self
+    }
+
+    /// Sets how many lines are shown above and below the offending range when cutting snippets from a registered source.
+    pub fn with_context_lines(mut self, context_lines: usize) -> Self {
+        self.context_lines = context_lines;
+        self
+    }
+
+    /// Renders a single [`Diagnostic`].
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let annotation_type = match diagnostic.severity {
+            DiagnosticSeverity::Error => AnnotationType::Error,
+            DiagnosticSeverity::Warning => AnnotationType::Warning,
+        };
+        let mut slices = Vec::new();
+        let mut footer = Vec::new();
+
+        let primary_slice = self.primary_slice(diagnostic).map(|mut slice| {
+            if let Some(range) = diagnostic.range.as_ref() {
+                slice.annotate(range, "", annotation_type);
+            }
+            slice
+        });
+        slices.extend(primary_slice);
+
+        for related in &diagnostic.related_locations {
+            let label = related.message.as_str();
+            let same_file = related.file_name == diagnostic.file_name;
+            let annotated_in_primary_slice = same_file
+                && related.range.as_ref().is_some_and(|range| {
+                    slices
+                        .first_mut()
+                        .is_some_and(|slice| slice.annotate(range, label, AnnotationType::Info))
+                });
+            if annotated_in_primary_slice {
+                continue;
+            }
+            let related_slice = related
+                .range
+                .as_ref()
+                .zip(related.file_name.as_deref())
+                .and_then(|(range, file_name)| {
+                    let mut slice = self.slice_from_source(file_name, range)?;
+                    slice
+                        .annotate(range, label, AnnotationType::Info)
+                        .then_some(slice)
+                });
+            match related_slice {
+                Some(slice) => slices.push(slice),
+                None => footer.push((AnnotationType::Note, describe_location(related))),
+            }
+        }
+        for suggestion in &diagnostic.suggestions {
+            footer.push((AnnotationType::Help, suggestion.message.clone()));
+        }
+
+        let snippet = Snippet {
+            title: Some(Annotation {
+                label: Some(&diagnostic.message),
+                id: diagnostic.code.as_ref().map(DiagnosticCode::as_str),
+                annotation_type,
+            }),
+            footer: footer
+                .iter()
+                .map(|(annotation_type, label)| Annotation {
+                    label: Some(label),
+                    id: None,
+                    annotation_type: *annotation_type,
+                })
+                .collect(),
+            slices: slices.iter().map(OwnedSlice::as_slice).collect(),
+        };
+        let renderer = if self.color_choice.use_colors() {
+            Renderer::styled()
+        } else {
+            Renderer::plain()
+        };
+        renderer.render(snippet).to_string()
+    }
+
+    /// Renders multiple [`Diagnostic`]s, separated by empty lines and followed by a summary of how many errors and warnings there were.
+    pub fn render_all<'d>(&self, diagnostics: impl IntoIterator<Item = &'d Diagnostic>) -> String {
+        let mut output = String::new();
+        let mut errors = 0;
+        let mut warnings = 0;
+        for diagnostic in diagnostics {
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => errors += 1,
+                DiagnosticSeverity::Warning => warnings += 1,
+            }
+            output.push_str(&self.render(diagnostic));
+            output.push_str("\n\n");
+        }
+        let plural = |count: usize, noun: &str| {
+            let suffix = if count == 1 { "" } else { "s" };
+            format!("{count} {noun}{suffix}")
+        };
+        output.push_str(&format!(
+            "{} and {} emitted",
+            plural(errors, "error"),
+            plural(warnings, "warning")
+        ));
+        output
+    }
+
+    fn primary_slice(&self, diagnostic: &Diagnostic) -> Option<OwnedSlice> {
+        let from_source = diagnostic
+            .file_name
+            .as_deref()
+            .zip(diagnostic.range.as_ref())
+            .and_then(|(file_name, range)| self.slice_from_source(file_name, range));
+        from_source.or_else(|| {
+            diagnostic.context.as_ref().map(|context| OwnedSlice {
+                source: context.clone(),
+                first_line: diagnostic.start_line,
+                origin: diagnostic.file_name.clone(),
+                annotations: Vec::new(),
+            })
+        })
+    }
+
+    fn slice_from_source(&self, file_name: &str, range: &Range<Position>) -> Option<OwnedSlice> {
+        let source = self.sources.get(file_name)?;
+        let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+        let lines: Vec<_> = source.lines().collect();
+        if range.start.line >= lines.len() {
+            return None;
+        }
+        let first_line = range.start.line.saturating_sub(self.context_lines);
+        let last_line = (range.end.line + self.context_lines).min(lines.len() - 1);
+        Some(OwnedSlice {
+            source: lines[first_line..=last_line].join("\n"),
+            first_line,
+            origin: Some(file_name.to_owned()),
+            annotations: Vec::new(),
+        })
+    }
+}
+
+/// A [`Slice`] that owns its data, so that the rendered [`Snippet`] can borrow from it.
+#[derive(Debug)]
+struct OwnedSlice {
+    source: String,
+    /// The zero-based line number of the first line of `source`.
+    first_line: usize,
+    origin: Option<String>,
+    annotations: Vec<(usize, usize, String, AnnotationType)>,
+}
+
+impl OwnedSlice {
+    /// Adds an annotation for the given absolute range.
+    /// Returns `false` if the range does not lie within this slice.
+    fn annotate(
+        &mut self,
+        range: &Range<Position>,
+        label: &str,
+        annotation_type: AnnotationType,
+    ) -> bool {
+        let Some((start, end)) = self.relative_byte_range(range) else {
+            return false;
+        };
+        self.annotations
+            .push((start, end, label.to_owned(), annotation_type));
+        true
+    }
+
+    /// Converts a range of code points within the whole file into the byte range within this slice expected by annotate-snippets.
+    fn relative_byte_range(&self, range: &Range<Position>) -> Option<(usize, usize)> {
+        let start_line = range.start.line.checked_sub(self.first_line)?;
+        let end_line = range.end.line.checked_sub(self.first_line)?;
+        let line_offsets: Vec<_> = self
+            .source
+            .split('\n')
+            .scan(0, |offset, line| {
+                let line_start = *offset;
+                *offset += line.len() + 1;
+                Some((line_start, line))
+            })
+            .collect();
+        let byte_offset = |line: usize, character: usize| {
+            let (line_start, text) = line_offsets.get(line)?;
+            let within_line = text
+                .char_indices()
+                .map(|(index, _)| index)
+                .nth(character)
+                .unwrap_or(text.len());
+            Some(line_start + within_line)
+        };
+        let start = byte_offset(start_line, range.start.character)?;
+        // The Diagnostic range is exclusive, but the annotation range is inclusive
+        let end = byte_offset(end_line, range.end.character.saturating_sub(1))
+            .unwrap_or(start)
+            .max(start);
+        Some((start, end))
+    }
+
+    fn as_slice(&self) -> Slice<'_> {
+        Slice {
+            source: &self.source,
+            line_start: self.first_line + 1,
+            origin: self.origin.as_deref(),
+            fold: false,
+            annotations: self
+                .annotations
+                .iter()
+                .map(|(start, end, label, annotation_type)| SourceAnnotation {
+                    label,
+                    annotation_type: *annotation_type,
+                    range: (*start, *end),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn describe_location(related: &RelatedLocation) -> String {
+    let location = match (related.file_name.as_deref(), related.range.as_ref()) {
+        (Some(file_name), Some(range)) => format!(
+            " ({file_name}:{}:{})",
+            range.start.line + 1,
+            range.start.character + 1
+        ),
+        (Some(file_name), None) => format!(" ({file_name})"),
+        (None, Some(range)) => format!(" ({}:{})", range.start.line + 1, range.start.character + 1),
+        (None, None) => String::new(),
+    };
+    format!("{}{location}", related.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file() -> File {
+        File {
+            file_name: "test.yarn".to_owned(),
+            source: "title: Start\n---\n<<declare $gold = 1>>\nYou have {$gol} coins.\n===\n"
+                .to_owned(),
+        }
+    }
+
+    fn diagnostic() -> Diagnostic {
+        Diagnostic::from_message("Can't figure out the type of variable $gol")
+            .with_code(DiagnosticCode::UndeterminedVariableType)
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 3,
+                    character: 10,
+                }..Position {
+                    line: 3,
+                    character: 14,
+                },
+            )
+            .with_related_location(
+                RelatedLocation::new("$gold is declared here")
+                    .with_file_name("test.yarn")
+                    .with_range(
+                        Position {
+                            line: 2,
+                            character: 10,
+                        }..Position {
+                            line: 2,
+                            character: 15,
+                        },
+                    ),
+            )
+    }
+
+    #[test]
+    fn renders_plain_snippet_from_source() {
+        let file = file();
+        let rendered = DiagnosticRenderer::new()
+            .with_source(&file)
+            .with_color_choice(ColorChoice::Never)
+            .render(&diagnostic());
+
+        assert!(rendered.starts_with("error[YS0015]: Can't figure out the type of variable $gol"));
+        assert!(rendered.contains("test.yarn:4:"));
+        assert!(rendered.contains("You have {$gol} coins."));
+        assert!(rendered.contains("$gold is declared here"));
+        assert!(rendered.contains('^'));
+        assert!(rendered.is_ascii());
+    }
+
+    #[test]
+    fn falls_back_to_notes_without_source() {
+        let rendered = DiagnosticRenderer::new()
+            .with_color_choice(ColorChoice::Never)
+            .render(&diagnostic());
+
+        assert!(rendered.contains("note: $gold is declared here (test.yarn:3:11)"));
+    }
+
+    #[test]
+    fn colors_can_be_forced() {
+        let file = file();
+        let renderer = DiagnosticRenderer::new().with_source(&file);
+        let colored = renderer
+            .clone()
+            .with_color_choice(ColorChoice::Always)
+            .render(&diagnostic());
+        let plain = renderer
+            .with_color_choice(ColorChoice::Never)
+            .render(&diagnostic());
+
+        assert!(colored.contains('\u{1b}'));
+        assert!(!plain.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn summarizes_all_diagnostics() {
+        let warning = diagnostic().with_severity(DiagnosticSeverity::Warning);
+        let rendered = DiagnosticRenderer::new()
+            .with_color_choice(ColorChoice::Never)
+            .render_all([&diagnostic(), &warning]);
+
+        assert!(rendered.ends_with("1 error and 1 warning emitted"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Machine-readable output formats for [`Diagnostic`]s, so that CI systems can annotate code reviews.
+
+use crate::prelude::*;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use yarnspinner_core::prelude::*;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub(crate) fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    serde_json::to_string_pretty(diagnostics).unwrap_or_bug()
+}
+
+pub(crate) fn diagnostics_to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let rules: BTreeSet<_> = diagnostics.iter().filter_map(|d| d.code).collect();
+    let rules: Vec<_> = rules
+        .into_iter()
+        .map(|code| {
+            json!({
+                "id": code.as_str(),
+                "name": code.name(),
+                "shortDescription": { "text": code.description() },
+            })
+        })
+        .collect();
+    let results: Vec<_> = diagnostics.iter().map(sarif_result).collect();
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "yarnspinner",
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            // `Position::character` counts code points, not UTF-16 code units.
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    });
+    serde_json::to_string_pretty(&sarif).unwrap_or_bug()
+}
+
+fn sarif_result(diagnostic: &Diagnostic) -> Value {
+    let level = match diagnostic.severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+    };
+    let mut result = json!({
+        "level": level,
+        "message": { "text": diagnostic.message },
+        "locations": [sarif_location(diagnostic.file_name.as_deref(), diagnostic.range.as_ref())],
+    });
+    if let Some(code) = diagnostic.code {
+        result["ruleId"] = code.as_str().into();
+    }
+    if !diagnostic.related_locations.is_empty() {
+        let related_locations: Vec<_> = diagnostic
+            .related_locations
+            .iter()
+            .enumerate()
+            .map(|(id, related)| {
+                let mut location =
+                    sarif_location(related.file_name.as_deref(), related.range.as_ref());
+                location["id"] = id.into();
+                location["message"] = json!({ "text": related.message });
+                location
+            })
+            .collect();
+        result["relatedLocations"] = related_locations.into();
+    }
+    if !diagnostic.suggestions.is_empty() {
+        let fixes: Vec<_> = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                let file_name = suggestion
+                    .file_name
+                    .as_deref()
+                    .or(diagnostic.file_name.as_deref());
+                json!({
+                    "description": { "text": suggestion.message },
+                    "artifactChanges": [{
+                        "artifactLocation": sarif_artifact_location(file_name),
+                        "replacements": [{
+                            "deletedRegion": sarif_region(&suggestion.range),
+                            "insertedContent": { "text": suggestion.replacement },
+                        }],
+                    }],
+                })
+            })
+            .collect();
+        result["fixes"] = fixes.into();
+    }
+    result
+}
+
+fn sarif_location(file_name: Option<&str>, range: Option<&Range<Position>>) -> Value {
+    let mut physical_location = json!({
+        "artifactLocation": sarif_artifact_location(file_name),
+    });
+    if let Some(range) = range {
+        physical_location["region"] = sarif_region(range);
+    }
+    json!({ "physicalLocation": physical_location })
+}
+
+fn sarif_artifact_location(file_name: Option<&str>) -> Value {
+    json!({ "uri": file_name.unwrap_or("<input>").replace('\\', "/") })
+}
+
+/// SARIF lines and columns are 1-based, while [`Position`] is 0-based.
+fn sarif_region(range: &Range<Position>) -> Value {
+    json!({
+        "startLine": range.start.line + 1,
+        "startColumn": range.start.character + 1,
+        "endLine": range.end.line + 1,
+        "endColumn": range.end.character + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        let range = Position {
+            line: 2,
+            character: 5,
+        }..Position {
+            line: 2,
+            character: 9,
+        };
+        vec![
+            Diagnostic::from_message("Can't figure out the type of variable $gol")
+                .with_code(DiagnosticCode::UndeterminedVariableType)
+                .with_file_name("test.yarn")
+                .with_range(range.clone())
+                .with_suggestion(SuggestedReplacement::did_you_mean(
+                    Some("test.yarn".to_owned()),
+                    range,
+                    "$gold",
+                )),
+            Diagnostic::from_message("More than one node is named Start")
+                .with_code(DiagnosticCode::DuplicateNodeName)
+                .with_severity(DiagnosticSeverity::Warning)
+                .with_related_location(
+                    RelatedLocation::new("Start is also defined here").with_file_name("other.yarn"),
+                ),
+        ]
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let diagnostics = diagnostics();
+        let json = diagnostics.to_json();
+        assert!(json.contains("\"YS0015\""));
+        let deserialized: Vec<Diagnostic> = serde_json::from_str(&json).unwrap();
+        assert_eq!(diagnostics, deserialized);
+    }
+
+    #[test]
+    fn sarif_contains_rules_results_and_fixes() {
+        let sarif: Value = serde_json::from_str(&diagnostics().to_sarif()).unwrap();
+        assert_eq!("2.1.0", sarif["version"]);
+        let run = &sarif["runs"][0];
+        assert_eq!(2, run["tool"]["driver"]["rules"].as_array().unwrap().len());
+
+        let error = &run["results"][0];
+        assert_eq!("YS0015", error["ruleId"]);
+        assert_eq!("error", error["level"]);
+        let region = &error["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(3, region["startLine"]);
+        assert_eq!(6, region["startColumn"]);
+        let replacement = &error["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!("$gold", replacement["insertedContent"]["text"]);
+
+        let warning = &run["results"][1];
+        assert_eq!("warning", warning["level"]);
+        assert_eq!(
+            "other.yarn",
+            warning["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+        );
+    }
+}
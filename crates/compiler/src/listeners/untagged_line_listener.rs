@@ -25,13 +25,13 @@ pub(crate) struct UntaggedLineListener<'input> {
 }
 
 impl<'input> UntaggedLineListener<'input> {
-    pub fn new(existing_line_tags: Vec<LineId>, file: FileParseResult<'input>) -> Self {
-        let original_source = file
-            .tokens()
-            .get_all_text()
-            .lines()
-            .map(|s| s.to_owned())
-            .collect();
+    /// Takes the `source` that `file` was parsed from, as the tokens don't contain the format specifiers skipped by the lexer.
+    pub fn new(
+        existing_line_tags: Vec<LineId>,
+        file: FileParseResult<'input>,
+        source: &str,
+    ) -> Self {
+        let original_source = source.lines().map(|s| s.to_owned()).collect();
         Self {
             existing_line_tags,
             file,
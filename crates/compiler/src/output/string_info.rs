@@ -2,6 +2,7 @@
 
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
+use sha2::{Digest, Sha256};
 
 /// Information about a string. Stored inside a string table, which is
 /// produced from the Compiler.
@@ -44,3 +45,14 @@ pub struct StringInfo {
     /// string besides the `#line:` hashtag.
     pub metadata: Vec<String>,
 }
+
+/// Computes the lock of a line's text, which is the first 8 characters of its SHA-256 hash.
+/// Translations store the lock of the text they were translated from, so comparing it with the lock of the current text
+/// tells whether they are outdated. This is the lock used by `*.strings.csv` files and the translation formats.
+///
+/// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
+pub fn compute_lock(text: &str) -> String {
+    const MAX_CHARS: usize = 8;
+    let hash = Sha256::digest(text);
+    format!("{hash:x}").chars().take(MAX_CHARS).collect()
+}
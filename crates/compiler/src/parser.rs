@@ -1,8 +1,10 @@
 //! The parser for the compiler.
 
 mod actual_types;
+mod format_specifiers;
 pub(crate) mod generated;
 mod indent_aware_lexer;
 
 pub(crate) use actual_types::*;
+pub(crate) use format_specifiers::FormatSpecifiers;
 pub(crate) use indent_aware_lexer::IndentAwareYarnSpinnerLexer as YarnSpinnerLexer;
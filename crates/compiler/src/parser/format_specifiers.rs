@@ -0,0 +1,154 @@
+//! Format specifiers of inline expressions in lines, e.g. the `N0` in `You have {$gold:N0} coins`.
+//!
+//! The Yarn grammar does not know about format specifiers, so the [`YarnSpinnerLexer`](crate::parser::YarnSpinnerLexer)
+//! skips them and records them here. This lets us attach the specifiers to the substitution markers
+//! of the string table afterwards, e.g. `You have {0:N0} coins`.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// The format specifiers found in a file, keyed by the position of the last character of the expression they belong to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FormatSpecifiers(HashMap<usize, FormatSpecifierLocation>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FormatSpecifierLocation {
+    /// The specifier as written, without the colon.
+    text: String,
+    /// The position of the colon, counted in chars from the start of the file.
+    colon: usize,
+    /// The position of the closing brace of the expression, counted in chars from the start of the file.
+    closing_brace: usize,
+    /// The zero-based line and the character within it where the colon is.
+    start: Position,
+}
+
+impl FormatSpecifiers {
+    /// Records the specifier `text` found between the chars `colon` and `closing_brace`,
+    /// belonging to the expression whose last character is at `expression_stop`.
+    pub(crate) fn insert(
+        &mut self,
+        expression_stop: isize,
+        text: &str,
+        colon: isize,
+        closing_brace: isize,
+        start: Position,
+    ) {
+        self.0.insert(
+            expression_stop as usize,
+            FormatSpecifierLocation {
+                text: text.to_owned(),
+                colon: colon as usize,
+                closing_brace: closing_brace as usize,
+                start,
+            },
+        );
+    }
+
+    /// Returns the specifier belonging to the expression whose last character is at `expression_stop`, as reported by [`Token::get_stop`](antlr_rust::token::Token::get_stop).
+    pub(crate) fn get(&self, expression_stop: isize) -> Option<&str> {
+        let expression_stop = usize::try_from(expression_stop).ok()?;
+        self.0
+            .get(&expression_stop)
+            .map(|location| location.text.as_str())
+    }
+
+    /// Reports all format specifiers that cannot be parsed as a [`FormatSpecifier`]. `source` must be the source the specifiers were found in.
+    pub(crate) fn diagnostics(&self, file_name: &str, source: &str) -> Vec<Diagnostic> {
+        let mut locations: Vec<_> = self.0.values().collect();
+        locations.sort_by_key(|location| location.colon);
+        locations
+            .into_iter()
+            .filter_map(|location| {
+                let error = location.text.parse::<FormatSpecifier>().err()?;
+                let end = Position {
+                    line: location.start.line,
+                    character: location.start.character + location.closing_brace - location.colon,
+                };
+                let line = source.lines().nth(location.start.line).unwrap_or_default();
+                let diagnostic = Diagnostic::from_message(error.to_string())
+                    .with_code(DiagnosticCode::InvalidFormatSpecifier)
+                    .with_file_name(file_name)
+                    .with_start_line(location.start.line)
+                    .with_range(location.start..end)
+                    .with_context(line);
+                Some(diagnostic)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use antlr_rust::common_token_stream::CommonTokenStream;
+    use antlr_rust::input_stream::CodePoint32BitCharStream;
+
+    const SOURCE: &str = "title: Start
+position: {1:2}
+---
+You have {$gold:N0} coins and {$ratio : P1} of the {\"map: north\"}. #line:abc
+<<set $x to {1}>> // {$y:N0}
+-> Buy {$price:X}
+[b]Deal\\#1[/b]: {$price : N2}
+===
+";
+
+    fn find(source: &str) -> FormatSpecifiers {
+        let chars: Vec<_> = source.chars().map(|c| c as u32).collect();
+        let lexer = YarnSpinnerLexer::new(
+            CodePoint32BitCharStream::new(&chars),
+            "test.yarn".to_owned(),
+        );
+        let format_specifiers = lexer.format_specifiers.clone();
+        let mut parser =
+            generated::yarnspinnerparser::YarnSpinnerParser::new(CommonTokenStream::new(lexer));
+        parser.dialogue().unwrap();
+        format_specifiers.take()
+    }
+
+    #[test]
+    fn finds_format_specifiers_in_lines_only() {
+        let specifiers = find(SOURCE);
+        let mut texts: Vec<_> = specifiers
+            .0
+            .values()
+            .map(|location| location.text.as_str())
+            .collect();
+        texts.sort();
+        assert_eq!(texts, ["N0", "N2", "P1", "X"]);
+    }
+
+    #[test]
+    fn keys_format_specifiers_by_the_end_of_their_expression() {
+        let specifiers = find(SOURCE);
+        let gold_end = SOURCE.find("$gold").unwrap() + "$gold".len() - 1;
+        assert_eq!(specifiers.get(gold_end as isize), Some("N0"));
+        let ratio_end = SOURCE.find("$ratio").unwrap() + "$ratio".len() - 1;
+        assert_eq!(specifiers.get(ratio_end as isize), Some("P1"));
+    }
+
+    #[test]
+    fn reports_invalid_format_specifiers() {
+        let diagnostics = find(SOURCE).diagnostics("test.yarn", SOURCE);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            Some(DiagnosticCode::InvalidFormatSpecifier)
+        );
+        assert_eq!(
+            diagnostics[0].range,
+            Some(
+                Position {
+                    line: 5,
+                    character: 14
+                }..Position {
+                    line: 5,
+                    character: 16
+                }
+            )
+        );
+        assert_eq!(diagnostics[0].start_line, 5);
+        assert_eq!(diagnostics[0].context.as_deref(), Some("-> Buy {$price:X}"));
+    }
+}
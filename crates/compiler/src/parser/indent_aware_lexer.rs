@@ -5,6 +5,7 @@
 //! directly, and the `IndentAwareLexer` derives from the ANTLR Lexer base class.
 //! Instead of this, we use a proxy/wrapper around the generated lexer to handle everything correctly.
 
+use super::FormatSpecifiers;
 use super::generated::yarnspinnerlexer::{
     self, LocalTokenFactory, YarnSpinnerLexer as GeneratedYarnSpinnerLexer,
 };
@@ -34,6 +35,9 @@ antlr_rust::tid! { impl<'input, Input> TidAble<'input> for IndentAwareYarnSpinne
 /// ## Implementation notes
 ///
 /// In contrast to the original implementation, the warnings emitted by this lexer are actually respected in the diagnostics.
+///
+/// Format specifiers of inline expressions in lines, like the `N0` in `{$gold:N0}`, are not part of the grammar.
+/// This lexer skips them and collects them in [`IndentAwareYarnSpinnerLexer::format_specifiers`] instead.
 pub(crate) struct IndentAwareYarnSpinnerLexer<
     'input,
     Input: CharStream<From<'input>>,
@@ -61,8 +65,13 @@ pub(crate) struct IndentAwareYarnSpinnerLexer<
     /// holds the line number of the last seen option.
     /// Lets us work out if the blank line needs to end the option.
     last_seen_option_content: Option<isize>,
+    /// Whether we are inside an inline expression of a line, i.e. between `{` and `}`.
+    is_in_line_expression: bool,
+    /// The position of the last character of the last token of the current inline expression, excluding whitespace.
+    last_expression_stop: Option<isize>,
     file_name: String,
     pub(crate) diagnostics: Rc<RefCell<Vec<Diagnostic>>>,
+    pub(crate) format_specifiers: Rc<RefCell<FormatSpecifiers>>,
 }
 
 impl<'input, Input: CharStream<From<'input>>> Deref for IndentAwareYarnSpinnerLexer<'input, Input> {
@@ -136,12 +145,19 @@ where
             last_indent: Default::default(),
             unbalanced_indents: Default::default(),
             last_seen_option_content: None,
+            is_in_line_expression: false,
+            last_expression_stop: None,
             diagnostics: Default::default(),
+            format_specifiers: Default::default(),
         }
     }
 
     fn check_next_token(&mut self) {
+        if self.is_in_line_expression && self.last_expression_stop.is_some() {
+            self.skip_format_specifier();
+        }
         let current = self.base.next_token();
+        self.track_line_expression(&current);
 
         match current.token_type {
             // Insert indents or dedents depending on the next token's
@@ -176,6 +192,60 @@ where
         self.last_token = Some(current);
     }
 
+    fn track_line_expression(&mut self, token: &CommonToken<'input>) {
+        match token.token_type {
+            yarnspinnerlexer::EXPRESSION_START => {
+                self.is_in_line_expression = true;
+                self.last_expression_stop = None;
+            }
+            yarnspinnerlexer::EXPRESSION_END => self.is_in_line_expression = false,
+            _ if self.is_in_line_expression && token.channel == TOKEN_DEFAULT_CHANNEL => {
+                self.last_expression_stop = Some(token.stop);
+            }
+            _ => {}
+        }
+    }
+
+    /// If the input continues with a format specifier, i.e. a colon followed by anything but a newline up to the closing brace,
+    /// consumes it without emitting a token and records it for the last expression.
+    fn skip_format_specifier(&mut self) {
+        let start = Position {
+            line: self.base.get_line() as usize - 1,
+            character: self.base.get_char_position_in_line() as usize,
+        };
+        let input = self.base.input();
+        if input.la(1) != ':' as isize {
+            return;
+        }
+        let mut text = String::new();
+        let mut offset = 2;
+        loop {
+            match char::from_u32(input.la(offset) as u32) {
+                Some('}') => break,
+                Some('\n' | '\r') | None => return,
+                Some(c) => text.push(c),
+            }
+            offset += 1;
+        }
+        let colon = input.index();
+        let expression_stop = self.last_expression_stop.take().unwrap();
+        self.format_specifiers.borrow_mut().insert(
+            expression_stop,
+            text.trim(),
+            colon,
+            colon + offset - 1,
+            start,
+        );
+
+        // Going through the interpreter keeps the lines and columns of the following tokens correct
+        let lexer = self.base.deref_mut();
+        let interpreter = lexer.interpreter.as_ref().unwrap();
+        let input = lexer.input.as_mut().unwrap();
+        for _ in 1..offset {
+            interpreter.consume(input);
+        }
+    }
+
     fn handle_newline_token(
         &mut self,
         current_token: Box<antlr_rust::token::GenericToken<std::borrow::Cow<'input, str>>>,
@@ -317,6 +387,7 @@ where
         if saw_spaces && saw_tabs {
             self.diagnostics.borrow_mut().push(
                 Diagnostic::from_message("Indentation contains tabs and spaces")
+                    .with_code(DiagnosticCode::MixedIndentation)
                     .with_range(get_newline_indentation_range(current_token))
                     .with_context(get_newline_indentation_text(current_token))
                     .with_start_line(current_token.line as usize)
@@ -357,6 +428,7 @@ where
             let last_line_len = token.get_text().lines().last().unwrap().len();
             self.diagnostics.borrow_mut().push(
                 Diagnostic::from_message("Newlines are not allowed in commands")
+                    .with_code(DiagnosticCode::NewlineInCommand)
                     .with_range(
                         Position {
                             line: token.get_line_as_usize() - 1,
@@ -0,0 +1,331 @@
+//! Import and export of string tables and their translations in formats understood by translation tools,
+//! namely [XLIFF 2.0](https://docs.oasis-open.org/xliff/xliff-core/v2.0/xliff-core-v2.0.html)
+//! and [gettext PO](https://www.gnu.org/software/gettext/manual/html_node/PO-Files.html).
+//! Catalogs can also be exported as [Project Fluent](https://projectfluent.org/) `.ftl` skeletons.
+//!
+//! A [`TranslationCatalog`] is created from the [`Compilation::string_table`] and optionally filled with the
+//! translations of one language. It can then be written to and read from either format:
+//!
+//! ```no_run
+//! # use yarnspinner_compiler::prelude::*;
+//! # use std::collections::HashMap;
+//! # let compilation = Compilation::default();
+//! # let german_lines: HashMap<yarnspinner_core::prelude::LineId, String> = HashMap::new();
+//! let catalog = TranslationCatalog::from_string_table("en", compilation.string_table.clone())?
+//!     .with_translations("de", german_lines);
+//! let xliff = catalog.to_xliff();
+//!
+//! let imported = TranslationCatalog::from_xliff(&xliff)?;
+//! let translations = imported.translations();
+//! # Ok::<(), TranslationError>(())
+//! ```
+//!
+//! Every exported line carries its line ID, the node and file it was found in, its hashtags as notes for translators,
+//! and a lock that identifies the version of the original text. Use [`TranslationUnit::is_outdated`] after importing
+//! to find translations whose original text changed since the file was exported.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use yarnspinner_core::prelude::*;
+
+mod fluent;
+mod po;
+mod xliff;
+
+/// The original lines of a project together with their translations into a single language.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TranslationCatalog {
+    /// The language the lines were written in, e.g. `"en-US"`.
+    pub source_language: String,
+
+    /// The language of the [`TranslationUnit::translation`]s, if any.
+    pub target_language: Option<String>,
+
+    /// The lines, ordered by file and line number.
+    pub units: Vec<TranslationUnit>,
+}
+
+/// A single line together with everything a translator needs to know about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationUnit {
+    /// The ID of the line.
+    pub id: LineId,
+
+    /// The original text of the line.
+    pub source: String,
+
+    /// The translated text of the line, if it has been translated.
+    pub translation: Option<String>,
+
+    /// The name of the file the line was found in.
+    pub file: String,
+
+    /// The name of the node the line was found in.
+    pub node: String,
+
+    /// The 1-indexed line number at which the line was found in its [`TranslationUnit::file`].
+    pub line_number: usize,
+
+    /// The hashtags of the line except for its `#line:` tag, without the leading `#`.
+    pub metadata: Vec<String>,
+
+    /// The first 8 characters of the SHA-256 hash of [`TranslationUnit::source`] at the time of the export.
+    /// This is the same lock that is used by `*.strings.csv` files.
+    pub lock: String,
+}
+
+impl TranslationCatalog {
+    /// Creates a catalog without any translations from a [`Compilation::string_table`].
+    ///
+    /// Fails if any line has an implicit line ID, as these are not stable between compilations
+    /// and translations could not be associated with their lines anymore.
+    pub fn from_string_table(
+        source_language: impl Into<String>,
+        string_table: impl IntoIterator<Item = (LineId, StringInfo)>,
+    ) -> Result<Self, TranslationError> {
+        let mut units = Vec::new();
+        for (id, string_info) in string_table {
+            if string_info.is_implicit_tag {
+                return Err(TranslationError::ImplicitLineId {
+                    file_name: string_info.file_name,
+                    line_number: string_info.line_number,
+                });
+            }
+            units.push(TranslationUnit {
+                id,
+                lock: compute_lock(&string_info.text),
+                source: string_info.text,
+                translation: None,
+                file: string_info.file_name,
+                node: string_info.node_name,
+                line_number: string_info.line_number,
+                metadata: string_info
+                    .metadata
+                    .into_iter()
+                    .filter(|metadata| !metadata.starts_with(LINE_ID_PREFIX))
+                    .collect(),
+            });
+        }
+        units.sort_by(|lhs, rhs| {
+            lhs.file
+                .cmp(&rhs.file)
+                .then(lhs.line_number.cmp(&rhs.line_number))
+                .then_with(|| lhs.id.0.cmp(&rhs.id.0))
+        });
+        Ok(Self {
+            source_language: source_language.into(),
+            target_language: None,
+            units,
+        })
+    }
+
+    /// Sets the translations of the lines into the given language. Translations for unknown line IDs are ignored.
+    pub fn with_translations(
+        mut self,
+        target_language: impl Into<String>,
+        translations: impl IntoIterator<Item = (LineId, String)>,
+    ) -> Self {
+        let mut translations: HashMap<_, _> = translations.into_iter().collect();
+        for unit in &mut self.units {
+            unit.translation = translations.remove(&unit.id);
+        }
+        self.target_language = Some(target_language.into());
+        self
+    }
+
+    /// Returns all translated lines by their line ID.
+    pub fn translations(&self) -> HashMap<LineId, String> {
+        self.units
+            .iter()
+            .filter_map(|unit| {
+                let translation = unit.translation.clone()?;
+                Some((unit.id.clone(), translation))
+            })
+            .collect()
+    }
+
+    /// Serializes the catalog into an XLIFF 2.0 document.
+    /// Lines are grouped into one `<file>` element per Yarn file, the other information is stored in `<note>`s.
+    pub fn to_xliff(&self) -> String {
+        xliff::write(self)
+    }
+
+    /// Reads a catalog from an XLIFF 2.0 document, like one written by [`TranslationCatalog::to_xliff`].
+    pub fn from_xliff(xliff: &str) -> Result<Self, TranslationError> {
+        xliff::read(xliff)
+    }
+
+    /// Serializes the catalog into a gettext PO file. The line IDs are stored as `msgctxt`,
+    /// the files and line numbers as references and the other information as extracted comments.
+    /// Without a [`TranslationCatalog::target_language`], this produces a PO template (POT).
+    pub fn to_po(&self) -> String {
+        po::write(self)
+    }
+
+    /// Reads a catalog from a gettext PO file, like one written by [`TranslationCatalog::to_po`].
+    pub fn from_po(po: &str) -> Result<Self, TranslationError> {
+        po::read(po)
+    }
+
+    /// Serializes the catalog into a Project Fluent `.ftl` file with one message per line, named by [`LineId::to_fluent_message_id`].
+    /// The messages contain the translations or, for untranslated lines, the original text, with substitutions turned into the
+    /// arguments `$arg0`, `$arg1`, etc. that are passed by a `FluentTextProvider`. The other information is stored in comments.
+    pub fn to_fluent(&self) -> String {
+        fluent::write(self)
+    }
+}
+
+impl TranslationUnit {
+    /// Returns `true` if the original text of the line was changed since this unit was exported,
+    /// meaning its [`TranslationUnit::translation`] needs to be updated.
+    pub fn is_outdated(&self, current_source: &str) -> bool {
+        self.lock != compute_lock(current_source)
+    }
+}
+
+/// An error that occurred while importing or exporting a [`TranslationCatalog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationError {
+    /// A line in the string table has no `#line:` tag.
+    ImplicitLineId {
+        /// The file the line was found in.
+        file_name: String,
+        /// The 1-indexed line number of the line.
+        line_number: usize,
+    },
+
+    /// The XLIFF document could not be read.
+    InvalidXliff(String),
+
+    /// The PO file could not be read.
+    InvalidPo {
+        /// The 1-indexed line of the PO file that could not be read.
+        line_number: usize,
+        /// What went wrong.
+        message: String,
+    },
+}
+
+impl Display for TranslationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationError::ImplicitLineId {
+                file_name,
+                line_number,
+            } => write!(
+                f,
+                "Cannot export lines without line IDs (line {line_number} in \"{file_name}\" is not tagged)"
+            ),
+            TranslationError::InvalidXliff(message) => write!(f, "Invalid XLIFF: {message}"),
+            TranslationError::InvalidPo {
+                line_number,
+                message,
+            } => write!(f, "Invalid PO file at line {line_number}: {message}"),
+        }
+    }
+}
+
+impl Error for TranslationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn string_table() -> HashMap<LineId, StringInfo> {
+        let lines = [
+            (
+                "line:greeting",
+                "Hello, \"traveller\" <3",
+                3,
+                vec!["mood:happy"],
+            ),
+            ("line:farewell", "Bye & good luck!\tSee you", 7, vec![]),
+            (
+                "line:question",
+                "Want some {$gold} gold?",
+                5,
+                vec!["last", "line:question"],
+            ),
+        ];
+        lines
+            .into_iter()
+            .map(|(id, text, line_number, metadata)| {
+                let string_info = StringInfo {
+                    text: text.to_owned(),
+                    node_name: "Start".to_owned(),
+                    line_number,
+                    file_name: "intro.yarn".to_owned(),
+                    is_implicit_tag: false,
+                    metadata: metadata.into_iter().map(str::to_owned).collect(),
+                };
+                (id.into(), string_info)
+            })
+            .collect()
+    }
+
+    pub(super) fn translated_catalog() -> TranslationCatalog {
+        TranslationCatalog::from_string_table("en", string_table())
+            .unwrap()
+            .with_translations(
+                "de",
+                [
+                    ("line:greeting".into(), "Hallo, \"Reisender\" <3".to_owned()),
+                    ("line:farewell".into(), "Tschüss & viel Glück!".to_owned()),
+                ],
+            )
+    }
+
+    #[test]
+    fn orders_units_by_line_number() {
+        let catalog = TranslationCatalog::from_string_table("en", string_table()).unwrap();
+        let ids: Vec<_> = catalog
+            .units
+            .iter()
+            .map(|unit| unit.id.0.as_str())
+            .collect();
+        assert_eq!(ids, ["line:greeting", "line:question", "line:farewell"]);
+    }
+
+    #[test]
+    fn strips_line_id_from_metadata() {
+        let catalog = TranslationCatalog::from_string_table("en", string_table()).unwrap();
+        assert_eq!(catalog.units[1].metadata, ["last"]);
+    }
+
+    #[test]
+    fn refuses_implicit_line_ids() {
+        let mut string_table = string_table();
+        string_table
+            .get_mut(&LineId::from("line:farewell"))
+            .unwrap()
+            .is_implicit_tag = true;
+        let result = TranslationCatalog::from_string_table("en", string_table);
+        assert_eq!(
+            result,
+            Err(TranslationError::ImplicitLineId {
+                file_name: "intro.yarn".to_owned(),
+                line_number: 7
+            })
+        );
+    }
+
+    #[test]
+    fn detects_outdated_translations() {
+        let catalog = translated_catalog();
+        let greeting = &catalog.units[0];
+        assert!(!greeting.is_outdated("Hello, \"traveller\" <3"));
+        assert!(greeting.is_outdated("Hello, traveller"));
+    }
+
+    #[test]
+    fn returns_only_translated_lines() {
+        let translations = translated_catalog().translations();
+        assert_eq!(translations.len(), 2);
+        assert_eq!(
+            translations[&LineId::from("line:farewell")],
+            "Tschüss & viel Glück!"
+        );
+    }
+}
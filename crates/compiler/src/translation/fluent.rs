@@ -0,0 +1,136 @@
+use super::*;
+use std::fmt::Write;
+
+const COMMENT_FILE: &str = "file: ";
+const COMMENT_NODE: &str = "node: ";
+const COMMENT_LOCK: &str = "lock: ";
+const COMMENT_TAG: &str = "tag: ";
+
+pub(super) fn write(catalog: &TranslationCatalog) -> String {
+    let mut ftl = String::new();
+    writeln!(ftl, "### Source language: {}", catalog.source_language).unwrap();
+    if let Some(target_language) = catalog.target_language.as_deref() {
+        writeln!(ftl, "### Language: {target_language}").unwrap();
+    }
+
+    let mut current_file = None;
+    for unit in &catalog.units {
+        if current_file != Some(&unit.file) {
+            writeln!(ftl).unwrap();
+            writeln!(ftl, "## {}", unit.file).unwrap();
+            current_file = Some(&unit.file);
+        }
+        writeln!(ftl).unwrap();
+        writeln!(ftl, "# {COMMENT_FILE}{}:{}", unit.file, unit.line_number).unwrap();
+        writeln!(ftl, "# {COMMENT_NODE}{}", unit.node).unwrap();
+        for tag in &unit.metadata {
+            writeln!(ftl, "# {COMMENT_TAG}{tag}").unwrap();
+        }
+        writeln!(ftl, "# {COMMENT_LOCK}{}", unit.lock).unwrap();
+        let text = unit.translation.as_deref().unwrap_or(&unit.source);
+        writeln!(
+            ftl,
+            "{} = {}",
+            unit.id.to_fluent_message_id(),
+            to_pattern(text)
+        )
+        .unwrap();
+    }
+    ftl
+}
+
+/// Converts the text of a line into a Fluent pattern, turning the `{0}`, `{1:N2}`, etc. substitution markers
+/// into placeables referencing the arguments `$arg0`, `$arg1`, etc.
+fn to_pattern(text: &str) -> String {
+    if text.is_empty() {
+        return "{\"\"}".to_owned();
+    }
+    let mut pattern = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '{'
+            && let Some(end) = rest.find('}')
+            && let Some(placeable) = to_placeable(&rest[1..end])
+        {
+            pattern.push_str(&placeable);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        match c {
+            '{' => pattern.push_str("{\"{\"}"),
+            '}' => pattern.push_str("{\"}\"}"),
+            // Leading whitespace would be trimmed by Fluent
+            ' ' if pattern.is_empty() => pattern.push_str("{\" \"}"),
+            _ => pattern.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    pattern
+}
+
+fn to_placeable(marker: &str) -> Option<String> {
+    let (index, specifier) = match marker.split_once(':') {
+        Some((index, specifier)) => (index, Some(specifier)),
+        None => (marker, None),
+    };
+    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let argument = format!("$arg{index}");
+    let Some(specifier) = specifier.and_then(|specifier| specifier.parse::<FormatSpecifier>().ok())
+    else {
+        return Some(format!("{{ {argument} }}"));
+    };
+    let precision = specifier.precision_or_default();
+    let options = match specifier.kind {
+        FormatKind::Number => String::new(),
+        FormatKind::FixedPoint => ", useGrouping: \"false\"".to_owned(),
+        FormatKind::Percent => ", style: \"percent\"".to_owned(),
+    };
+    Some(format!(
+        "{{ NUMBER({argument}, minimumFractionDigits: {precision}, maximumFractionDigits: {precision}{options}) }}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation::tests::{string_table, translated_catalog};
+
+    #[test]
+    fn writes_messages_with_comments() {
+        let ftl = write(&translated_catalog());
+        assert!(ftl.starts_with("### Source language: en\n### Language: de\n\n## intro.yarn\n"));
+        let greeting = translated_catalog().units[0].lock.clone();
+        let expected = format!(
+            "# file: intro.yarn:3\n# node: Start\n# tag: mood:happy\n# lock: {greeting}\nline-greeting = Hallo, \"Reisender\" <3\n"
+        );
+        assert!(ftl.contains(&expected));
+    }
+
+    #[test]
+    fn uses_source_text_for_untranslated_lines() {
+        let catalog = TranslationCatalog::from_string_table("en", string_table()).unwrap();
+        let ftl = write(&catalog);
+        assert!(!ftl.contains("### Language"));
+        assert!(ftl.contains("line-farewell = Bye & good luck!\tSee you\n"));
+    }
+
+    #[test]
+    fn converts_substitutions_to_arguments() {
+        assert_eq!(
+            to_pattern("You have {0} gold and {1:N0} gems."),
+            "You have { $arg0 } gold and { NUMBER($arg1, minimumFractionDigits: 0, maximumFractionDigits: 0) } gems."
+        );
+        assert_eq!(
+            to_pattern("{0:P1} done"),
+            "{ NUMBER($arg0, minimumFractionDigits: 1, maximumFractionDigits: 1, style: \"percent\") } done"
+        );
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(to_pattern(" a {b} c}"), "{\" \"}a {\"{\"}b{\"}\"} c{\"}\"}");
+        assert_eq!(to_pattern(""), "{\"\"}");
+    }
+}
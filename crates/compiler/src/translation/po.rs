@@ -0,0 +1,336 @@
+use super::*;
+use std::fmt::Write;
+
+const COMMENT_NODE: &str = "node: ";
+const COMMENT_LOCK: &str = "lock: ";
+const COMMENT_TAG: &str = "tag: ";
+const HEADER_LANGUAGE: &str = "Language";
+const HEADER_SOURCE_LANGUAGE: &str = "X-Source-Language";
+
+pub(super) fn write(catalog: &TranslationCatalog) -> String {
+    let mut po = String::new();
+    writeln!(po, "msgid \"\"").unwrap();
+    writeln!(po, "msgstr \"\"").unwrap();
+    let headers = [
+        ("MIME-Version", "1.0"),
+        ("Content-Type", "text/plain; charset=UTF-8"),
+        ("Content-Transfer-Encoding", "8bit"),
+        (
+            HEADER_LANGUAGE,
+            catalog.target_language.as_deref().unwrap_or_default(),
+        ),
+        (HEADER_SOURCE_LANGUAGE, catalog.source_language.as_str()),
+    ];
+    for (key, value) in headers {
+        writeln!(po, "{}", quote(&format!("{key}: {value}\n"))).unwrap();
+    }
+
+    for unit in &catalog.units {
+        writeln!(po).unwrap();
+        writeln!(po, "#. {COMMENT_NODE}{}", unit.node).unwrap();
+        writeln!(po, "#. {COMMENT_LOCK}{}", unit.lock).unwrap();
+        for tag in &unit.metadata {
+            writeln!(po, "#. {COMMENT_TAG}{tag}").unwrap();
+        }
+        writeln!(po, "#: {}:{}", unit.file, unit.line_number).unwrap();
+        writeln!(po, "msgctxt {}", quote(&unit.id.0)).unwrap();
+        writeln!(po, "msgid {}", quote(&unit.source)).unwrap();
+        let translation = unit.translation.as_deref().unwrap_or_default();
+        writeln!(po, "msgstr {}", quote(translation)).unwrap();
+    }
+    po
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn unquote(text: &str) -> Option<String> {
+    let content = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(content.len());
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        let escaped = match chars.next()? {
+            '\\' => '\\',
+            '"' => '"',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            _ => return None,
+        };
+        unquoted.push(escaped);
+    }
+    Some(unquoted)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Context,
+    Id,
+    Translation,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    line_number: usize,
+    comments: Vec<String>,
+    reference: Option<String>,
+    is_fuzzy: bool,
+    context: Option<String>,
+    id: Option<String>,
+    translation: Option<String>,
+    current_field: Option<Field>,
+}
+
+impl Entry {
+    fn field_mut(&mut self, field: Field) -> &mut Option<String> {
+        match field {
+            Field::Context => &mut self.context,
+            Field::Id => &mut self.id,
+            Field::Translation => &mut self.translation,
+        }
+    }
+}
+
+pub(super) fn read(po: &str) -> Result<TranslationCatalog, TranslationError> {
+    let mut catalog = TranslationCatalog::default();
+    let mut entry = Entry::default();
+    for (index, line) in po.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message: &str| TranslationError::InvalidPo {
+            line_number,
+            message: message.to_owned(),
+        };
+        let line = line.trim();
+        let starts_new_entry = line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("msgctxt")
+            || line.starts_with("msgid");
+        if starts_new_entry && entry.translation.is_some() {
+            finish_entry(std::mem::take(&mut entry), &mut catalog)?;
+        }
+        if entry.line_number == 0 && !line.is_empty() {
+            entry.line_number = line_number;
+        }
+
+        if line.is_empty() || line.starts_with("#~") || line.starts_with("#|") {
+            // Blank lines, obsolete entries and previous strings are not needed
+        } else if let Some(comment) = line.strip_prefix("#.") {
+            entry.comments.push(comment.trim().to_owned());
+        } else if let Some(reference) = line.strip_prefix("#:") {
+            entry.reference = Some(reference.trim().to_owned());
+        } else if let Some(flags) = line.strip_prefix("#,") {
+            entry.is_fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+        } else if line.starts_with('#') {
+            // Translator comments
+        } else if line.starts_with("msgid_plural") || line.starts_with("msgstr[") {
+            return Err(error("Plural forms are not supported"));
+        } else if line.starts_with('"') {
+            let Some(field) = entry.current_field else {
+                return Err(error("Found a string that does not belong to any keyword"));
+            };
+            let text = unquote(line).ok_or_else(|| error("Invalid string"))?;
+            entry
+                .field_mut(field)
+                .get_or_insert_with(String::new)
+                .push_str(&text);
+        } else {
+            let (keyword, text) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| error("Expected a keyword followed by a string"))?;
+            let field = match keyword {
+                "msgctxt" => Field::Context,
+                "msgid" => Field::Id,
+                "msgstr" => Field::Translation,
+                _ => return Err(error(&format!("Unknown keyword \"{keyword}\""))),
+            };
+            let text = unquote(text.trim()).ok_or_else(|| error("Invalid string"))?;
+            *entry.field_mut(field) = Some(text);
+            entry.current_field = Some(field);
+        }
+    }
+    if entry.id.is_some() {
+        finish_entry(entry, &mut catalog)?;
+    }
+    Ok(catalog)
+}
+
+fn finish_entry(entry: Entry, catalog: &mut TranslationCatalog) -> Result<(), TranslationError> {
+    let error = |message: &str| TranslationError::InvalidPo {
+        line_number: entry.line_number,
+        message: message.to_owned(),
+    };
+    let Some(source) = entry.id.clone() else {
+        return Err(error("Found an entry without a msgid"));
+    };
+    let translation = entry.translation.clone().unwrap_or_default();
+    let Some(id) = entry.context.clone() else {
+        if source.is_empty() {
+            read_header(&translation, catalog);
+            return Ok(());
+        }
+        return Err(error(
+            "Found an entry without a msgctxt, which is needed to store the line ID",
+        ));
+    };
+
+    let (file, line_number) = match entry
+        .reference
+        .as_deref()
+        .and_then(|reference| reference.rsplit_once(':'))
+    {
+        Some((file, line_number)) => {
+            let line_number = line_number
+                .parse()
+                .map_err(|_| error("Invalid line number in reference"))?;
+            (file.to_owned(), line_number)
+        }
+        None => (entry.reference.clone().unwrap_or_default(), 0),
+    };
+    let mut node = String::new();
+    let mut lock = None;
+    let mut metadata = Vec::new();
+    for comment in &entry.comments {
+        if let Some(value) = comment.strip_prefix(COMMENT_NODE) {
+            node = value.to_owned();
+        } else if let Some(value) = comment.strip_prefix(COMMENT_LOCK) {
+            lock = Some(value.to_owned());
+        } else if let Some(value) = comment.strip_prefix(COMMENT_TAG) {
+            metadata.push(value.to_owned());
+        }
+    }
+
+    catalog.units.push(TranslationUnit {
+        id: id.into(),
+        // Tools may drop comments they don't know, in which case we assume the source text is up to date
+        lock: lock.unwrap_or_else(|| compute_lock(&source)),
+        source,
+        // Like gettext, we don't use fuzzy translations, as they still need to be reviewed
+        translation: (!translation.is_empty() && !entry.is_fuzzy).then_some(translation),
+        file,
+        node,
+        line_number,
+        metadata,
+    });
+    Ok(())
+}
+
+fn read_header(header: &str, catalog: &mut TranslationCatalog) {
+    for line in header.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            HEADER_LANGUAGE => {
+                catalog.target_language = (!value.is_empty()).then(|| value.to_owned())
+            }
+            HEADER_SOURCE_LANGUAGE => catalog.source_language = value.to_owned(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation::tests::{string_table, translated_catalog};
+
+    #[test]
+    fn round_trips_translated_catalog() {
+        let catalog = translated_catalog();
+        let po = write(&catalog);
+        assert_eq!(read(&po).unwrap(), catalog);
+    }
+
+    #[test]
+    fn round_trips_template() {
+        let catalog = TranslationCatalog::from_string_table("en", string_table()).unwrap();
+        let po = write(&catalog);
+        assert!(po.contains("\"Language: \\n\""));
+        assert_eq!(read(&po).unwrap(), catalog);
+    }
+
+    #[test]
+    fn writes_context_references_and_comments() {
+        let po = write(&translated_catalog());
+        let expected = r#"#. node: Start
+#. lock: "#;
+        assert!(po.contains(expected));
+        assert!(po.contains("#. tag: mood:happy\n#: intro.yarn:3\nmsgctxt \"line:greeting\"\n"));
+        assert!(po.contains(r#"msgid "Hello, \"traveller\" <3""#));
+        assert!(po.contains(r#"msgid "Bye & good luck!\tSee you""#));
+    }
+
+    #[test]
+    fn reads_files_edited_by_other_tools() {
+        let po = r#"# Translators: someone
+msgid ""
+msgstr ""
+"Language: de\n"
+"X-Source-Language: en\n"
+
+# A translator comment
+#: intro.yarn:3
+#, fuzzy
+msgctxt "line:greeting"
+msgid "Hello"
+msgstr "Hallo"
+
+#: intro.yarn:5
+msgctxt "line:question"
+msgid ""
+"Want some "
+"gold?"
+msgstr ""
+"Möchtest du "
+"Gold?"
+
+#~ msgctxt "line:old"
+#~ msgid "Old"
+#~ msgstr "Alt"
+"#;
+        let catalog = read(po).unwrap();
+        assert_eq!(catalog.source_language, "en");
+        assert_eq!(catalog.target_language.as_deref(), Some("de"));
+        assert_eq!(catalog.units.len(), 2);
+        assert_eq!(catalog.units[0].translation, None);
+        assert_eq!(catalog.units[0].lock, compute_lock("Hello"));
+        assert_eq!(catalog.units[1].source, "Want some gold?");
+        assert_eq!(
+            catalog.units[1].translation.as_deref(),
+            Some("Möchtest du Gold?")
+        );
+        assert_eq!(catalog.units[1].line_number, 5);
+    }
+
+    #[test]
+    fn rejects_entries_without_context() {
+        let po = "msgid \"Hello\"\nmsgstr \"Hallo\"\n";
+        assert_eq!(
+            read(po),
+            Err(TranslationError::InvalidPo {
+                line_number: 1,
+                message: "Found an entry without a msgctxt, which is needed to store the line ID"
+                    .to_owned()
+            })
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use super::*;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::fmt::Write;
+
+const NOTE_NODE: &str = "node";
+const NOTE_LINE_NUMBER: &str = "line";
+const NOTE_LOCK: &str = "lock";
+const NOTE_TAG: &str = "tag";
+
+pub(super) fn write(catalog: &TranslationCatalog) -> String {
+    let mut xliff = String::new();
+    writeln!(xliff, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    write!(
+        xliff,
+        r#"<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="{}""#,
+        escape(&catalog.source_language)
+    )
+    .unwrap();
+    if let Some(target_language) = &catalog.target_language {
+        write!(xliff, r#" trgLang="{}""#, escape(target_language)).unwrap();
+    }
+    writeln!(xliff, ">").unwrap();
+
+    let mut units = catalog.units.iter().peekable();
+    let mut file_index = 0;
+    while let Some(first_unit) = units.peek() {
+        let file = first_unit.file.clone();
+        file_index += 1;
+        writeln!(
+            xliff,
+            r#"  <file id="f{file_index}" original="{}">"#,
+            escape(&file)
+        )
+        .unwrap();
+        while let Some(unit) = units.next_if(|unit| unit.file == file) {
+            write_unit(&mut xliff, unit);
+        }
+        writeln!(xliff, "  </file>").unwrap();
+    }
+    writeln!(xliff, "</xliff>").unwrap();
+    xliff
+}
+
+fn write_unit(xliff: &mut String, unit: &TranslationUnit) {
+    writeln!(xliff, r#"    <unit id="{}">"#, escape(&unit.id.0)).unwrap();
+    writeln!(xliff, "      <notes>").unwrap();
+    let line_number = unit.line_number.to_string();
+    let notes = [
+        (NOTE_NODE, unit.node.as_str()),
+        (NOTE_LINE_NUMBER, line_number.as_str()),
+        (NOTE_LOCK, unit.lock.as_str()),
+    ]
+    .into_iter()
+    .chain(unit.metadata.iter().map(|tag| (NOTE_TAG, tag.as_str())));
+    for (category, content) in notes {
+        writeln!(
+            xliff,
+            r#"        <note category="{category}">{}</note>"#,
+            escape(content)
+        )
+        .unwrap();
+    }
+    writeln!(xliff, "      </notes>").unwrap();
+    let state = if unit.translation.is_some() {
+        "translated"
+    } else {
+        "initial"
+    };
+    writeln!(xliff, r#"      <segment state="{state}">"#).unwrap();
+    writeln!(
+        xliff,
+        r#"        <source xml:space="preserve">{}</source>"#,
+        escape(&unit.source)
+    )
+    .unwrap();
+    if let Some(translation) = &unit.translation {
+        writeln!(
+            xliff,
+            r#"        <target xml:space="preserve">{}</target>"#,
+            escape(translation)
+        )
+        .unwrap();
+    }
+    writeln!(xliff, "      </segment>").unwrap();
+    writeln!(xliff, "    </unit>").unwrap();
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The element whose text is currently being read.
+enum Capture {
+    Note(String),
+    Source,
+    Target,
+}
+
+#[derive(Default)]
+struct XliffReader {
+    catalog: TranslationCatalog,
+    found_root: bool,
+    file: String,
+    unit: Option<TranslationUnit>,
+    lock: Option<String>,
+    capture: Option<Capture>,
+    text: String,
+}
+
+pub(super) fn read(xliff: &str) -> Result<TranslationCatalog, TranslationError> {
+    let mut reader = Reader::from_str(xliff);
+    let mut state = XliffReader::default();
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(element) => state.start_element(&element)?,
+            Event::Empty(element) => {
+                state.start_element(&element)?;
+                state.end_element(element.local_name().as_ref())?;
+            }
+            Event::End(element) => state.end_element(element.local_name().as_ref())?,
+            Event::Text(content) if state.capture.is_some() => {
+                state.text.push_str(&content.unescape().map_err(invalid)?);
+            }
+            Event::CData(content) if state.capture.is_some() => {
+                state.text.push_str(&String::from_utf8_lossy(&content));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !state.found_root {
+        return Err(invalid("Missing <xliff> root element"));
+    }
+    Ok(state.catalog)
+}
+
+impl XliffReader {
+    fn start_element(&mut self, element: &BytesStart) -> Result<(), TranslationError> {
+        match element.local_name().as_ref() {
+            b"xliff" => {
+                let version = attribute(element, "version")?.unwrap_or_default();
+                if !version.starts_with("2.") {
+                    return Err(invalid(format!(
+                        "Only XLIFF 2.0 is supported, but the document has version \"{version}\""
+                    )));
+                }
+                self.found_root = true;
+                self.catalog.source_language = attribute(element, "srcLang")?.unwrap_or_default();
+                self.catalog.target_language = attribute(element, "trgLang")?;
+            }
+            b"file" => self.file = attribute(element, "original")?.unwrap_or_default(),
+            b"unit" => {
+                let Some(id) = attribute(element, "id")? else {
+                    return Err(invalid("Found a <unit> without an id"));
+                };
+                self.unit = Some(TranslationUnit {
+                    id: id.into(),
+                    source: String::new(),
+                    translation: None,
+                    file: self.file.clone(),
+                    node: String::new(),
+                    line_number: 0,
+                    metadata: Vec::new(),
+                    lock: String::new(),
+                });
+                self.lock = None;
+            }
+            b"note" => {
+                let category = attribute(element, "category")?.unwrap_or_default();
+                self.capture = Some(Capture::Note(category));
+            }
+            b"source" => self.capture = Some(Capture::Source),
+            b"target" => self.capture = Some(Capture::Target),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end_element(&mut self, name: &[u8]) -> Result<(), TranslationError> {
+        match name {
+            b"note" | b"source" | b"target" => {
+                let content = std::mem::take(&mut self.text);
+                let (Some(capture), Some(unit)) = (self.capture.take(), self.unit.as_mut()) else {
+                    return Ok(());
+                };
+                match capture {
+                    Capture::Note(category) => match category.as_str() {
+                        NOTE_NODE => unit.node = content,
+                        NOTE_LINE_NUMBER => {
+                            unit.line_number = content.trim().parse().map_err(|_| {
+                                invalid(format!(
+                                    "Invalid line number \"{content}\" in unit {}",
+                                    unit.id
+                                ))
+                            })?
+                        }
+                        NOTE_LOCK => self.lock = Some(content),
+                        NOTE_TAG => unit.metadata.push(content),
+                        _ => {}
+                    },
+                    Capture::Source => unit.source.push_str(&content),
+                    Capture::Target if !content.is_empty() => unit
+                        .translation
+                        .get_or_insert_with(String::new)
+                        .push_str(&content),
+                    Capture::Target => {}
+                }
+            }
+            b"unit" => {
+                if let Some(mut unit) = self.unit.take() {
+                    // Tools may drop notes they don't know, in which case we assume the source text is up to date
+                    unit.lock = self
+                        .lock
+                        .take()
+                        .unwrap_or_else(|| compute_lock(&unit.source));
+                    self.catalog.units.push(unit);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, TranslationError> {
+    let Some(attribute) = element.try_get_attribute(name).map_err(invalid)? else {
+        return Ok(None);
+    };
+    let value = attribute.unescape_value().map_err(invalid)?;
+    Ok(Some(value.into_owned()))
+}
+
+fn invalid(error: impl Display) -> TranslationError {
+    TranslationError::InvalidXliff(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translation::tests::{string_table, translated_catalog};
+
+    #[test]
+    fn round_trips_translated_catalog() {
+        let catalog = translated_catalog();
+        let xliff = write(&catalog);
+        assert_eq!(read(&xliff).unwrap(), catalog);
+    }
+
+    #[test]
+    fn round_trips_catalog_without_translations() {
+        let catalog = TranslationCatalog::from_string_table("en", string_table()).unwrap();
+        let xliff = write(&catalog);
+        assert!(!xliff.contains("trgLang"));
+        assert_eq!(read(&xliff).unwrap(), catalog);
+    }
+
+    #[test]
+    fn writes_notes_and_escapes_text() {
+        let xliff = write(&translated_catalog());
+        assert!(xliff.contains(r#"srcLang="en" trgLang="de""#));
+        assert!(xliff.contains(r#"<file id="f1" original="intro.yarn">"#));
+        assert!(xliff.contains(r#"<unit id="line:greeting">"#));
+        assert!(xliff.contains(r#"<note category="tag">mood:happy</note>"#));
+        assert!(xliff.contains(r#"<note category="line">3</note>"#));
+        assert!(xliff.contains("Hello, &quot;traveller&quot; &lt;3"));
+    }
+
+    #[test]
+    fn reads_documents_from_other_tools() {
+        let xliff = r#"<?xml version="1.0"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="en" trgLang="de">
+  <file id="intro" original="intro.yarn">
+    <unit id="line:greeting">
+      <segment><source>Hello, </source><target>Hallo, </target></segment>
+      <segment><source><![CDATA[<friend>]]></source><target>Freund</target></segment>
+    </unit>
+    <unit id="line:farewell">
+      <segment><source>Bye</source><target/></segment>
+    </unit>
+  </file>
+</xliff>"#;
+        let catalog = read(xliff).unwrap();
+        assert_eq!(catalog.units.len(), 2);
+        let greeting = &catalog.units[0];
+        assert_eq!(greeting.source, "Hello, <friend>");
+        assert_eq!(greeting.translation.as_deref(), Some("Hallo, Freund"));
+        assert_eq!(greeting.file, "intro.yarn");
+        assert_eq!(greeting.lock, compute_lock("Hello, <friend>"));
+        assert_eq!(catalog.units[1].translation, None);
+    }
+
+    #[test]
+    fn rejects_xliff_1() {
+        let xliff = r#"<xliff version="1.2"><file original="a.yarn"/></xliff>"#;
+        assert!(matches!(
+            read(xliff),
+            Err(TranslationError::InvalidXliff(_))
+        ));
+    }
+}
@@ -2,6 +2,7 @@ mod code_generation_visitor;
 mod constant_value_visitor;
 mod declaration_visitor;
 mod hashable_interval;
+mod jump_destination_visitor;
 mod last_line_before_options_visitor;
 mod node_tracking_visitor;
 mod string_table_generator_visitor;
@@ -9,6 +10,6 @@ mod type_check_visitor;
 
 pub(crate) use self::{
     code_generation_visitor::*, declaration_visitor::*, hashable_interval::*,
-    last_line_before_options_visitor::*, node_tracking_visitor::*,
+    jump_destination_visitor::*, last_line_before_options_visitor::*, node_tracking_visitor::*,
     string_table_generator_visitor::*, type_check_visitor::*,
 };
@@ -457,6 +457,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
     ) -> Self::Return {
         let end_of_group_label = self.compiler_listener.register_label("group_end");
         let mut labels = Vec::new();
+        let mut has_unconditional_option = false;
 
         // For each option, create an internal destination label that, if
         // the user selects the option, control flow jumps to. Then,
@@ -494,6 +495,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             } else {
                 false
             };
+            has_unconditional_option |= !has_line_condition;
 
             // We can now prepare and add the option.
 
@@ -519,8 +521,31 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                     .with_operand(has_line_condition),
             );
         }
-        // All of the options that we intend to show are now ready to go.
+        if !has_unconditional_option {
+            let file = &self.compiler_listener.file;
+            self.compiler_listener.diagnostics.borrow_mut().push(
+                Diagnostic::from_message(
+                    "Every option in this group has a condition. If all of them fail, no option can be chosen",
+                )
+                .with_code(DiagnosticCode::NoUnconditionalOption)
+                .with_file_name(file.name.clone())
+                .with_parser_context(ctx, file.tokens())
+                .with_severity(DiagnosticSeverity::Warning),
+            );
+        }
+
+        // ## Implementation note
+        // The original does not push the end of the group. We do so right before `ShowOptions`
+        // so that the runtime can skip the group when none of the options are available,
+        // while runtimes that don't know about this simply pop it again at the end of the group.
         let token = ctx.stop();
+        self.compiler_listener.emit(
+            Emit::from_op_code(OpCode::PushString)
+                .with_token(token.deref())
+                .with_operand(end_of_group_label.clone()),
+        );
+
+        // All of the options that we intend to show are now ready to go.
         self.compiler_listener
             .emit(Emit::from_op_code(OpCode::ShowOptions).with_token(token.deref()));
 
@@ -558,6 +583,9 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             .insert(end_of_group_label, current_node.instructions.len() as i32);
         self.compiler_listener
             .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
+        // Also pop the end of the group pushed before `ShowOptions`
+        self.compiler_listener
+            .emit(Emit::from_op_code(OpCode::Pop).with_token(token.deref()));
     }
 
     fn visit_declare_statement(&mut self, _ctx: &Declare_statementContext<'input>) -> Self::Return {
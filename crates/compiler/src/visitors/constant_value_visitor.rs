@@ -55,6 +55,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
             let message = format!("Failed to parse {text} as a float",);
             self.diagnostics.push(
                 Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::InvalidNumber)
                     .with_file_name(&self.file.name)
                     .with_parser_context(ctx, self.file.tokens()),
             );
@@ -80,6 +81,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
         );
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::NonConstantDeclaration)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -95,6 +97,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
         let message = "Null is not a permitted type in Yarn Spinner 2.0 and later";
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::NullValue)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -107,6 +110,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
             format!("Variable declarations must be constant values, but `{text}` is a function",);
         self.diagnostics.push(
             Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::NonConstantDeclaration)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -101,6 +101,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                     format!("The node '{current_node_name}' contains illegal characters.");
                 self.diagnostics.push(
                     Diagnostic::from_message(message)
+                        .with_code(DiagnosticCode::InvalidNodeName)
                         .with_file_name(self.file.name.clone())
                         .with_parser_context(header.as_ref(), self.file.tokens()),
                 );
@@ -131,10 +132,24 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 "{} has already been declared in {}{line}",
                 existing_explicit_declaration.name, existing_explicit_declaration.source_file_name,
             );
+            let mut related_location = RelatedLocation::new(format!(
+                "{} was first declared here",
+                existing_explicit_declaration.name
+            ));
+            if let DeclarationSource::File(file_name) =
+                &existing_explicit_declaration.source_file_name
+            {
+                related_location = related_location.with_file_name(file_name);
+            }
+            if let Some(range) = existing_explicit_declaration.range.clone() {
+                related_location = related_location.with_range(range);
+            }
             self.diagnostics.push(
                 Diagnostic::from_message(msg)
+                    .with_code(DiagnosticCode::DuplicateVariableDeclaration)
                     .with_file_name(&self.file.name)
-                    .with_parser_context(ctx, self.file.tokens()),
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_related_location(related_location),
             );
             return;
         }
@@ -164,6 +179,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                         let msg = format!("Unknown type {}", declaration_type.get_text());
                         self.diagnostics.push(
                             Diagnostic::from_message(msg)
+                                .with_code(DiagnosticCode::UnknownType)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                         );
@@ -186,6 +202,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 );
                 self.diagnostics.push(
                     Diagnostic::from_message(msg)
+                        .with_code(DiagnosticCode::DeclarationTypeMismatch)
                         .with_file_name(&self.file.name)
                         .with_parser_context(ctx, self.file.tokens()),
                 );
@@ -238,6 +255,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile()
         .unwrap();
@@ -328,6 +346,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile();
 
@@ -336,6 +355,7 @@ mod tests {
         assert_eq!(
             diagnostics[0],
             Diagnostic::from_message("Type string does not match value 1 (Number)".to_string())
+                .with_code(DiagnosticCode::DeclarationTypeMismatch)
                 .with_file_name("test.yarn".to_string())
                 .with_context(file.source.clone())
                 .with_range(
@@ -351,6 +371,7 @@ mod tests {
         assert_eq!(
             diagnostics[1],
             Diagnostic::from_message("Can't figure out the type of variable $foo given its context. Specify its type with a <<declare>> statement.".to_string())
+                .with_code(DiagnosticCode::UndeterminedVariableType)
                 .with_file_name("test.yarn".to_string())
                 .with_context(file.source)
                 .with_range(
@@ -0,0 +1,79 @@
+use crate::listeners::closest_match;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+use std::collections::HashSet;
+use yarnspinner_core::prelude::*;
+
+/// A visitor that reports `<<jump>>` statements whose destination is a node name
+/// that does not belong to any node in the compilation.
+///
+/// Jumps to expressions are not checked, as their destination is only known at runtime.
+pub(crate) struct JumpDestinationVisitor<'input> {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+
+    /// The titles of all nodes in the compilation, across all files.
+    node_names: HashSet<String>,
+
+    file: FileParseResult<'input>,
+
+    _dummy: (),
+}
+
+impl<'input> JumpDestinationVisitor<'input> {
+    pub(crate) fn new(node_names: HashSet<String>, file: FileParseResult<'input>) -> Self {
+        Self {
+            node_names,
+            file,
+            diagnostics: Default::default(),
+            _dummy: Default::default(),
+        }
+    }
+}
+
+impl<'input> ParseTreeVisitorCompat<'input> for JumpDestinationVisitor<'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'input> YarnSpinnerParserVisitorCompat<'input> for JumpDestinationVisitor<'input> {
+    fn visit_jumpToNodeName(&mut self, ctx: &JumpToNodeNameContext<'input>) -> Self::Return {
+        // The parser will have generated an error for us if the destination is missing.
+        let Some(destination) = ctx.destination.as_ref() else {
+            return;
+        };
+        let node_name = destination.get_text();
+        if self.node_names.contains(node_name) {
+            return;
+        }
+        let line = destination.get_line_as_usize().saturating_sub(1);
+        let character = destination.get_column_as_usize();
+        let range = Position { line, character }..Position {
+            line,
+            character: character + node_name.chars().count(),
+        };
+        let mut diagnostic = Diagnostic::from_message(format!(
+            "Jump to node {node_name}, which does not exist in this compilation"
+        ))
+        .with_code(DiagnosticCode::UnknownNode)
+        .with_severity(DiagnosticSeverity::Warning)
+        .with_file_name(&self.file.name)
+        .with_parser_context(ctx, self.file.tokens());
+        if let Some(candidate) =
+            closest_match(node_name, self.node_names.iter().map(String::as_str))
+        {
+            diagnostic = diagnostic.with_suggestion(SuggestedReplacement::did_you_mean(
+                Some(self.file.name.clone()),
+                range,
+                candidate,
+            ));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+}
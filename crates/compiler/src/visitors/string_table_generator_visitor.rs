@@ -107,18 +107,33 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
             // but this can logically not be the case in this scope.
             let diagnostic_context = line_id_tag.clone().unwrap();
             let line_id = line_id.get_text();
-            self.diagnostics.push(
-                Diagnostic::from_message(format!("Duplicate line ID {line_id}"))
-                    .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
-                    .with_file_name(&self.file.name),
-            );
+            let mut diagnostic = Diagnostic::from_message(format!("Duplicate line ID {line_id}"))
+                .with_code(DiagnosticCode::DuplicateLineId)
+                .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
+                .with_file_name(&self.file.name);
+            if let Some(existing) = self
+                .string_table_manager
+                .get(&LineId::from(line_id.to_owned()))
+            {
+                let line = existing.line_number.saturating_sub(1);
+                let range = Position { line, character: 0 }..Position { line, character: 0 };
+                diagnostic = diagnostic.with_related_location(
+                    RelatedLocation::new(format!("{line_id} is first used here"))
+                        .with_file_name(&existing.file_name)
+                        .with_range(range),
+                );
+            }
+            self.diagnostics.push(diagnostic);
             return;
         };
 
         let line_number = ctx.start().get_line_as_usize();
         let hashtag_texts = get_hashtag_texts(&hashtags);
 
-        let composed_string = generate_formatted_text(&ctx.line_formatted_text().unwrap());
+        let composed_string = generate_formatted_text(
+            &ctx.line_formatted_text().unwrap(),
+            &self.file.format_specifiers,
+        );
 
         let string_id = self.string_table_manager.insert(
             line_id.map(|t| t.get_text().into()),
@@ -142,7 +157,12 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
 /// `Hi there { some_expression }, how are you { another_expression } doing?`
 /// and turns it into
 /// `Hi there {0}, how are you {1}? doing`
-fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
+///
+/// Format specifiers are kept, so `You have {$gold:N0} coins` turns into `You have {0:N0} coins`.
+fn generate_formatted_text(
+    ctx: &Line_formatted_textContext,
+    format_specifiers: &FormatSpecifiers,
+) -> String {
     let mut expression_count = 0;
     let mut composed_string = String::new();
     // First, visit all of the nodes, which are either terminal
@@ -160,6 +180,10 @@ fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
             // captured already has them. So, we just need to write
             // the expression count.
             composed_string.push_str(&expression_count.to_string());
+            if let Some(format_specifier) = format_specifiers.get(child.stop().get_stop()) {
+                composed_string.push(':');
+                composed_string.push_str(format_specifier);
+            }
             expression_count += 1;
         }
     }
@@ -183,8 +207,8 @@ pub(crate) fn get_hashtag_texts(hashtags: &[Rc<HashtagContext>]) -> Vec<String>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use antlr_rust::InputStream;
     use antlr_rust::common_token_stream::CommonTokenStream;
+    use antlr_rust::input_stream::CodePoint32BitCharStream;
     use yarnspinner_core::prelude::Position;
 
     #[test]
@@ -223,8 +247,25 @@ A line with {$many} many {(1 -(1 * 2))}{$cool} expressions
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn keeps_format_specifiers() {
+        let input = "title: Title
+---
+You have {$gold:N0} coins and {$ratio : P1} of the map
+===
+";
+        let result = process_input(input);
+        let expected = "You have {0:N0} coins and {1:P1} of the map";
+        assert_eq!(result, expected);
+    }
+
     fn process_input(input: &str) -> String {
-        let lexer = YarnSpinnerLexer::new(InputStream::new(input), "input.yarn".to_owned());
+        let chars: Vec<_> = input.chars().map(|c| c as u32).collect();
+        let lexer = YarnSpinnerLexer::new(
+            CodePoint32BitCharStream::new(&chars),
+            "input.yarn".to_owned(),
+        );
+        let format_specifiers = lexer.format_specifiers.clone();
         let mut parser = YarnSpinnerParser::new(CommonTokenStream::new(lexer));
         let line_formatted_text = parser
             .dialogue()
@@ -239,7 +280,7 @@ A line with {$many} many {(1 -(1 * 2))}{$cool} expressions
             .unwrap()
             .line_formatted_text()
             .unwrap();
-        generate_formatted_text(&line_formatted_text)
+        generate_formatted_text(&line_formatted_text, &format_specifiers.borrow())
     }
 
     #[test]
@@ -259,6 +300,7 @@ a {1 + 3} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile()
         .unwrap();
@@ -317,6 +359,7 @@ a {very} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile();
 
@@ -333,6 +376,7 @@ a {very} cool expression
         let context = "a {very} cool expression\n       ^".to_owned();
         let first_expected =
             Diagnostic::from_message("Unexpected \"}\" while reading a function call".to_string())
+                .with_code(DiagnosticCode::SyntaxError)
                 .with_file_name("test.yarn".to_string())
                 .with_range(range.clone())
                 .with_context(context.clone())
@@ -341,6 +385,7 @@ a {very} cool expression
 
         let second_expected =
             Diagnostic::from_message("mismatched input '}' expecting '('".to_string())
+                .with_code(DiagnosticCode::SyntaxError)
                 .with_file_name("test.yarn".to_string())
                 .with_range(range)
                 .with_context(context)
@@ -251,6 +251,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
     fn visit_valueNull(&mut self, ctx: &ValueNullContext<'input>) -> Self::Return {
         self.diagnostics.push(
             Diagnostic::from_message("Null is not a permitted type in Yarn Spinner 2.0 and later")
+                .with_code(DiagnosticCode::NullValue)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -337,6 +338,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                 parameters,
                 supplied_parameters.len()
             ))
+            .with_code(DiagnosticCode::ParameterCountMismatch)
             .with_file_name(&self.file.name)
             .with_parser_context(ctx, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -372,6 +374,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                     expected_type.format(),
                     supplied_type.format()
                 ))
+                .with_code(DiagnosticCode::ParameterTypeMismatch)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -411,6 +414,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         // so we save this as a potential diagnostic for the compiler itself to resolve
         let diagnostic =
             Diagnostic::from_message(format_cannot_determine_variable_type_error(&name))
+                .with_code(DiagnosticCode::UndeterminedVariableType)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens());
         self.deferred_types
@@ -467,6 +471,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                             variable_type.format(),
                             expression_type.format(),
                         ))
+                        .with_code(DiagnosticCode::InvalidAssignment)
                         .with_file_name(&self.file.name)
                         .with_parser_context(ctx, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -497,6 +502,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                                 Diagnostic::from_message(
                                     format_cannot_determine_variable_type_error(&variable_name),
                                 )
+                                .with_code(DiagnosticCode::UndeterminedVariableType)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                             )
@@ -553,6 +559,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
             self.diagnostics.push(
                             Diagnostic::from_message(
                                 format!("Type of expression \"{}\" can't be determined without more context. Please declare one or more terms.", ctx.get_text_with_whitespace(self.file.tokens())))
+                                .with_code(DiagnosticCode::UndeterminedExpressionType)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()));
         }
@@ -645,6 +652,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile()
         .unwrap();
@@ -670,6 +678,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile();
 
@@ -680,6 +689,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("$foo (Number) cannot be assigned a String")
+                .with_code(DiagnosticCode::InvalidAssignment)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -695,6 +705,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("$bar (Bool) cannot be assigned a Number")
+                .with_code(DiagnosticCode::InvalidAssignment)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -710,6 +721,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("$baz (String) cannot be assigned a Bool")
+                .with_code(DiagnosticCode::InvalidAssignment)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -727,6 +739,7 @@ mod tests {
         assert!(
             // Does not factor in context or start line because these are subject to frequent change
             diagnostics.iter().any(|d| d.file_name == expected.file_name
+                && d.code == expected.code
                 && d.message == expected.message
                 && d.range == expected.range),
             "Expected diagnostics:\n{}\nto contain:\n- {:?}",
@@ -757,6 +770,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile()
         .unwrap();
@@ -780,6 +794,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            optimize: false,
         }
         .compile();
 
@@ -790,6 +805,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("$foo (Number) cannot be assigned a undefined")
+                .with_code(DiagnosticCode::InvalidAssignment)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -805,6 +821,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("$foo (Number) cannot be assigned a undefined")
+                .with_code(DiagnosticCode::InvalidAssignment)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -820,6 +837,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("All terms of + must be the same, not Number, String")
+                .with_code(DiagnosticCode::MixedTermTypes)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -835,6 +853,7 @@ mod tests {
         assert_contains(
             &diagnostics,
             &Diagnostic::from_message("All terms of * must be the same, not Number, String")
+                .with_code(DiagnosticCode::MixedTermTypes)
                 .with_file_name("test.yarn")
                 .with_range(
                     Position {
@@ -94,6 +94,7 @@ impl<'input> TypeCheckVisitor<'input> {
                             context.get_text_with_whitespace(self.file.tokens()),
                         );
                         let diagnostic = Diagnostic::from_message(message)
+                            .with_code(DiagnosticCode::UndeterminedExpressionType)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -106,6 +107,7 @@ impl<'input> TypeCheckVisitor<'input> {
                             context.get_text_with_whitespace(self.file.tokens()),
                         );
                         let diagnostic = Diagnostic::from_message(message)
+                            .with_code(DiagnosticCode::UndeterminedExpressionType)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -256,6 +258,7 @@ impl<'input> TypeCheckVisitor<'input> {
                 let diagnostic = Diagnostic::from_message(
                     format_cannot_determine_variable_type_error(&var_name),
                 )
+                .with_code(DiagnosticCode::UndeterminedVariableType)
                 .with_file_name(&self.file.name)
                 .with_parser_context(undefined_variable_context.as_ref(), self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -280,6 +283,7 @@ impl<'input> TypeCheckVisitor<'input> {
             let message =
                 format!("All terms of {operation_description} must be the same, not {type_list}");
             let diagnostic = Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::MixedTermTypes)
                 .with_file_name(&self.file.name)
                 .with_parser_context(context, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -315,6 +319,7 @@ impl<'input> TypeCheckVisitor<'input> {
                     expression_type.format(),
                 );
                 let diagnostic = Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::UnsupportedOperation)
                     .with_file_name(&self.file.name)
                     .with_parser_context(context, self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -349,6 +354,7 @@ impl<'input> TypeCheckVisitor<'input> {
                 "Terms of '{operation_description}' must be {permitted_types_list}, not {type_list}",
             );
             let diagnostic = Diagnostic::from_message(message)
+                .with_code(DiagnosticCode::UnsupportedOperation)
                 .with_file_name(&self.file.name)
                 .with_parser_context(context, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -375,6 +381,7 @@ impl<'input> TypeCheckVisitor<'input> {
             );
             self.diagnostics.push(
                 Diagnostic::from_message(message)
+                    .with_code(DiagnosticCode::UnsupportedOperation)
                     .with_file_name(&self.file.name)
                     .with_parser_context(context, self.file.tokens()),
             );
@@ -0,0 +1,538 @@
+//! Scripts for voice-over recording sessions, generated from the [`Compilation::string_table`].
+//!
+//! A [`VoiceOverScript`] groups all lines by the character speaking them, as given by the `Character: text` syntax,
+//! and lists each line together with its ID, the node it appears in, the line preceding it as context for the actor,
+//! and its hashtags, which commonly carry hints such as `#emotion:angry`. It can be written as CSV or as a printable HTML document:
+//!
+//! ```no_run
+//! # use yarnspinner_compiler::prelude::*;
+//! # let compilation = Compilation::default();
+//! let script = VoiceOverScript::from_string_table(compilation.string_table.clone())?;
+//! std::fs::write("script.csv", script.to_csv())?;
+//! std::fs::write("script.html", script.to_html())?;
+//!
+//! let report = script.check_recordings("assets/dialogue/en-US", &["mp3", "ogg", "wav"])?;
+//! for id in &report.missing {
+//!     println!("No recording for {id}");
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Recordings are expected to be named like the line ID without its `line:` prefix, e.g. `abc.ogg` for `#line:abc`,
+//! which is where `bevy_yarnspinner`'s `AudioAssetProvider` looks for them.
+
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The lines of a project grouped by the characters speaking them. See the [module documentation](self) for more.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VoiceOverScript {
+    /// The characters ordered by name, followed by the lines without a character, if any.
+    pub characters: Vec<CharacterScript>,
+}
+
+/// All lines spoken by a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterScript {
+    /// The name of the character, or `None` for lines that are not written as `Character: text`, such as narration.
+    pub character: Option<String>,
+
+    /// The lines, ordered by file and line number.
+    pub lines: Vec<VoiceOverLine>,
+}
+
+/// A single line to be recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceOverLine {
+    /// The ID of the line.
+    pub id: LineId,
+
+    /// The text of the line without the character name and markup.
+    pub text: String,
+
+    /// The name of the file the line was found in.
+    pub file: String,
+
+    /// The name of the node the line was found in.
+    pub node: String,
+
+    /// The 1-indexed line number at which the line was found in its [`VoiceOverLine::file`].
+    pub line_number: usize,
+
+    /// The full text of the line that precedes this one in the same node, including its character name, if any.
+    pub context: Option<String>,
+
+    /// The hashtags of the line except for its `#line:` tag, without the leading `#`.
+    pub metadata: Vec<String>,
+}
+
+/// The result of [`VoiceOverScript::check_recordings`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RecordingReport {
+    /// The lines without a recording, in the order of the script.
+    pub missing: Vec<LineId>,
+
+    /// The audio files that do not belong to any line of the script, ordered by path.
+    pub orphaned: Vec<PathBuf>,
+}
+
+impl VoiceOverScript {
+    /// Creates a script from a [`Compilation::string_table`].
+    ///
+    /// Fails if any line has an implicit line ID, as these are not stable between compilations
+    /// and recordings could not be associated with their lines anymore.
+    pub fn from_string_table(
+        string_table: impl IntoIterator<Item = (LineId, StringInfo)>,
+    ) -> Result<Self, VoiceOverError> {
+        let mut lines = Vec::new();
+        for (id, string_info) in string_table {
+            if string_info.is_implicit_tag {
+                return Err(VoiceOverError::ImplicitLineId {
+                    file_name: string_info.file_name,
+                    line_number: string_info.line_number,
+                });
+            }
+            lines.push((id, string_info));
+        }
+        lines.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+            lhs.file_name
+                .cmp(&rhs.file_name)
+                .then(lhs.line_number.cmp(&rhs.line_number))
+                .then_with(|| lhs_id.0.cmp(&rhs_id.0))
+        });
+
+        let mut characters: HashMap<Option<String>, Vec<VoiceOverLine>> = HashMap::new();
+        let mut previous: Option<&StringInfo> = None;
+        for (id, string_info) in &lines {
+            let context = previous
+                .filter(|previous| {
+                    previous.file_name == string_info.file_name
+                        && previous.node_name == string_info.node_name
+                })
+                .map(|previous| previous.text.clone());
+            previous = Some(string_info);
+
+            let (character, text) = split_character(&string_info.text);
+            characters
+                .entry(character)
+                .or_default()
+                .push(VoiceOverLine {
+                    id: id.clone(),
+                    text,
+                    file: string_info.file_name.clone(),
+                    node: string_info.node_name.clone(),
+                    line_number: string_info.line_number,
+                    context,
+                    metadata: string_info
+                        .metadata
+                        .iter()
+                        .filter(|metadata| !metadata.starts_with(LINE_ID_PREFIX))
+                        .cloned()
+                        .collect(),
+                });
+        }
+
+        let mut characters: Vec<_> = characters
+            .into_iter()
+            .map(|(character, lines)| CharacterScript { character, lines })
+            .collect();
+        // `None` sorts first, but narration is easier to find at the end of a script
+        characters.sort_by(|lhs, rhs| match (&lhs.character, &rhs.character) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(rhs),
+            (lhs, rhs) => rhs.is_some().cmp(&lhs.is_some()),
+        });
+        Ok(Self { characters })
+    }
+
+    /// Returns all lines of the script, grouped by character.
+    pub fn lines(&self) -> impl Iterator<Item = &VoiceOverLine> {
+        self.characters
+            .iter()
+            .flat_map(|character| character.lines.iter())
+    }
+
+    /// Serializes the script into CSV with one row per line, grouped by character.
+    /// The columns are `character`, `id`, `text`, `node`, `file`, `line_number`, `context` and `metadata`, the latter being separated by spaces.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        writeln!(
+            csv,
+            "character,id,text,node,file,line_number,context,metadata"
+        )
+        .unwrap();
+        for character in &self.characters {
+            for line in &character.lines {
+                let fields = [
+                    character.character.clone().unwrap_or_default(),
+                    line.id.0.clone(),
+                    line.text.clone(),
+                    line.node.clone(),
+                    line.file.clone(),
+                    line.line_number.to_string(),
+                    line.context.clone().unwrap_or_default(),
+                    line.metadata.join(" "),
+                ];
+                let row: Vec<_> = fields.iter().map(|field| escape_csv(field)).collect();
+                writeln!(csv, "{}", row.join(",")).unwrap();
+            }
+        }
+        csv
+    }
+
+    /// Serializes the script into a standalone HTML document meant for printing, with one section per character
+    /// that starts on a new page and contains a table of its lines.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>").unwrap();
+        writeln!(html, "<html>").unwrap();
+        writeln!(html, "<head>").unwrap();
+        writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>Voice-over script</title>").unwrap();
+        writeln!(html, "<style>").unwrap();
+        writeln!(html, "body {{ font-family: sans-serif; }}").unwrap();
+        writeln!(html, "section {{ break-before: page; }}").unwrap();
+        writeln!(html, "table {{ border-collapse: collapse; width: 100%; }}").unwrap();
+        writeln!(
+            html,
+            "th, td {{ border: 1px solid #999; padding: 4px; text-align: left; vertical-align: top; }}"
+        )
+        .unwrap();
+        writeln!(html, "tr {{ break-inside: avoid; }}").unwrap();
+        writeln!(html, ".context {{ color: #666; font-style: italic; }}").unwrap();
+        writeln!(html, "</style>").unwrap();
+        writeln!(html, "</head>").unwrap();
+        writeln!(html, "<body>").unwrap();
+        for character in &self.characters {
+            let name = character.character.as_deref().unwrap_or("No character");
+            writeln!(html, "<section>").unwrap();
+            writeln!(html, "<h2>{}</h2>", escape_html(name)).unwrap();
+            writeln!(html, "<table>").unwrap();
+            writeln!(
+                html,
+                "<tr><th>ID</th><th>Node</th><th>Context</th><th>Line</th><th>Notes</th></tr>"
+            )
+            .unwrap();
+            for line in &character.lines {
+                writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td class=\"context\">{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&line.id.0),
+                    escape_html(&line.node),
+                    escape_html(line.context.as_deref().unwrap_or_default()),
+                    escape_html(&line.text),
+                    escape_html(&line.metadata.join(" ")),
+                )
+                .unwrap();
+            }
+            writeln!(html, "</table>").unwrap();
+            writeln!(html, "</section>").unwrap();
+        }
+        writeln!(html, "</body>").unwrap();
+        writeln!(html, "</html>").unwrap();
+        html
+    }
+
+    /// Compares the audio files in `directory` with the lines of this script. Only files with one of the given `extensions` are considered,
+    /// and a line counts as recorded if a file named like its ID without the `line:` prefix exists with any of them, e.g. `abc.ogg` for `line:abc`.
+    /// Subdirectories are not searched.
+    pub fn check_recordings(
+        &self,
+        directory: impl AsRef<Path>,
+        extensions: &[&str],
+    ) -> io::Result<RecordingReport> {
+        let mut recordings: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            let is_audio_file = path.is_file()
+                && path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| extensions.contains(&extension));
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if is_audio_file {
+                recordings.entry(name.to_owned()).or_default().push(path);
+            }
+        }
+
+        let mut file_names = HashSet::new();
+        let mut missing = Vec::new();
+        for line in self.lines() {
+            let file_name = line.id.0.trim_start_matches(LINE_ID_PREFIX);
+            if !recordings.contains_key(file_name) {
+                missing.push(line.id.clone());
+            }
+            file_names.insert(file_name);
+        }
+        let mut orphaned: Vec<_> = recordings
+            .into_iter()
+            .filter(|(name, _)| !file_names.contains(name.as_str()))
+            .flat_map(|(_, paths)| paths)
+            .collect();
+        orphaned.sort();
+        Ok(RecordingReport { missing, orphaned })
+    }
+}
+
+impl RecordingReport {
+    /// Returns `true` if every line has a recording and every recording belongs to a line.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+/// Splits a line into the name of its character and the rest of the text, with markup removed.
+///
+/// Like the runtime, this looks for the first colon of the text without markup. Unlike the runtime, which does this after
+/// substituting inline expressions, colons in substitution markers like `{0:N2}` are ignored,
+/// and a line whose character name contains a substitution is treated as having no character, as its name is only known at runtime.
+fn split_character(text: &str) -> (Option<String>, String) {
+    let text = strip_markup(text);
+    let mut substitution_depth = 0;
+    let colon = text.char_indices().find_map(|(index, c)| {
+        match c {
+            '{' => substitution_depth += 1,
+            '}' => substitution_depth -= 1,
+            ':' if substitution_depth == 0 => return Some(index),
+            _ => {}
+        }
+        None
+    });
+    match colon.map(|colon| text.split_at(colon)) {
+        Some((character, rest)) if !character.trim().is_empty() && !character.contains('{') => (
+            Some(character.trim().to_owned()),
+            rest[1..].trim_start().to_owned(),
+        ),
+        _ => (None, text),
+    }
+}
+
+/// Removes all markup tags like `[b]` and `[/b]` from the text, turning escaped brackets like `\[` into plain ones.
+fn strip_markup(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut is_in_tag = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('[' | ']')) => {
+                let bracket = chars.next().unwrap();
+                if !is_in_tag {
+                    stripped.push(bracket);
+                }
+            }
+            '[' => is_in_tag = true,
+            ']' if is_in_tag => is_in_tag = false,
+            _ if !is_in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// An error that occurred while creating a [`VoiceOverScript`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoiceOverError {
+    /// A line in the string table has no `#line:` tag.
+    ImplicitLineId {
+        /// The file the line was found in.
+        file_name: String,
+        /// The 1-indexed line number of the line.
+        line_number: usize,
+    },
+}
+
+impl Display for VoiceOverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VoiceOverError::ImplicitLineId {
+                file_name,
+                line_number,
+            } => write!(
+                f,
+                "Cannot record lines without line IDs (line {line_number} in \"{file_name}\" is not tagged)"
+            ),
+        }
+    }
+}
+
+impl Error for VoiceOverError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_table() -> HashMap<LineId, StringInfo> {
+        let lines = [
+            (
+                "line:a1",
+                "Alice: Hi, Bob!",
+                "Start",
+                3,
+                vec!["emotion:happy"],
+            ),
+            ("line:b1", "Bob: Hello, \"Alice\".", "Start", 4, vec![]),
+            ("line:n1", "The sun sets.", "Start", 5, vec!["line:n1"]),
+            ("line:a2", "Alice: <Goodbye>", "End", 9, vec![]),
+        ];
+        lines
+            .into_iter()
+            .map(|(id, text, node, line_number, metadata)| {
+                let string_info = StringInfo {
+                    text: text.to_owned(),
+                    node_name: node.to_owned(),
+                    line_number,
+                    file_name: "intro.yarn".to_owned(),
+                    is_implicit_tag: false,
+                    metadata: metadata.into_iter().map(str::to_owned).collect(),
+                };
+                (id.into(), string_info)
+            })
+            .collect()
+    }
+
+    fn script() -> VoiceOverScript {
+        VoiceOverScript::from_string_table(string_table()).unwrap()
+    }
+
+    #[test]
+    fn groups_lines_by_character() {
+        let script = script();
+        let characters: Vec<_> = script
+            .characters
+            .iter()
+            .map(|character| character.character.as_deref())
+            .collect();
+        assert_eq!(characters, [Some("Alice"), Some("Bob"), None]);
+        let alice: Vec<_> = script.characters[0]
+            .lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect();
+        assert_eq!(alice, ["Hi, Bob!", "<Goodbye>"]);
+    }
+
+    #[test]
+    fn uses_preceding_line_of_same_node_as_context() {
+        let script = script();
+        let context = |id: &str| {
+            script
+                .lines()
+                .find(|line| line.id.0 == id)
+                .unwrap()
+                .context
+                .clone()
+        };
+        assert_eq!(context("line:a1"), None);
+        assert_eq!(context("line:b1").as_deref(), Some("Alice: Hi, Bob!"));
+        assert_eq!(context("line:a2"), None);
+    }
+
+    #[test]
+    fn writes_csv() {
+        let csv = script().to_csv();
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            rows,
+            [
+                "character,id,text,node,file,line_number,context,metadata",
+                "Alice,line:a1,\"Hi, Bob!\",Start,intro.yarn,3,,emotion:happy",
+                "Alice,line:a2,<Goodbye>,End,intro.yarn,9,,",
+                "Bob,line:b1,\"Hello, \"\"Alice\"\".\",Start,intro.yarn,4,\"Alice: Hi, Bob!\",",
+                ",line:n1,The sun sets.,Start,intro.yarn,5,\"Bob: Hello, \"\"Alice\"\".\",",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_escaped_html() {
+        let html = script().to_html();
+        assert!(html.contains("<h2>Alice</h2>"));
+        assert!(html.contains("<h2>No character</h2>"));
+        assert!(html.contains("<td>&lt;Goodbye&gt;</td>"));
+        assert!(html.contains("<td>emotion:happy</td>"));
+    }
+
+    #[test]
+    fn ignores_markup_and_substitutions_when_splitting_characters() {
+        assert_eq!(
+            split_character("[b]Alice[/b]: It costs [i]{0:N2}[/i] gold."),
+            (Some("Alice".to_owned()), "It costs {0:N2} gold.".to_owned())
+        );
+        assert_eq!(
+            split_character("It costs {0:N2} gold."),
+            (None, "It costs {0:N2} gold.".to_owned())
+        );
+        assert_eq!(split_character("{0}: Hi!"), (None, "{0}: Hi!".to_owned()));
+        assert_eq!(
+            split_character("\\[Aside\\] Hmm."),
+            (None, "[Aside] Hmm.".to_owned())
+        );
+    }
+
+    #[test]
+    fn refuses_implicit_line_ids() {
+        let mut string_table = string_table();
+        string_table
+            .get_mut(&LineId::from("line:b1"))
+            .unwrap()
+            .is_implicit_tag = true;
+        assert_eq!(
+            VoiceOverScript::from_string_table(string_table),
+            Err(VoiceOverError::ImplicitLineId {
+                file_name: "intro.yarn".to_owned(),
+                line_number: 4
+            })
+        );
+    }
+
+    #[test]
+    fn finds_missing_and_orphaned_recordings() {
+        let directory = std::env::temp_dir().join(format!(
+            "yarnspinner_voice_over_test_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        for file in ["a1.ogg", "b1.wav", "old.mp3", "n1.txt"] {
+            std::fs::write(directory.join(file), []).unwrap();
+        }
+
+        let report = script()
+            .check_recordings(&directory, &["mp3", "ogg", "wav"])
+            .unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            report.missing,
+            [LineId::from("line:a2"), LineId::from("line:n1")]
+        );
+        assert_eq!(report.orphaned, [directory.join("old.mp3")]);
+        assert!(!report.is_complete());
+    }
+}
@@ -0,0 +1,171 @@
+//! Adapted from the standard numeric format strings of .NET, see <https://learn.microsoft.com/en-us/dotnet/standard/base-types/standard-numeric-format-strings>
+
+use crate::prelude::*;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+/// A format specifier that can follow an inline expression in a line, separated by a colon, e.g. the `N0` in `You have {$gold:N0} coins`.
+/// Determines how a number is formatted when it is inserted into the line. Values that are not numbers ignore the specifier.
+///
+/// A specifier consists of a letter for the [`FormatKind`], case-insensitive, and an optional precision of up to [`FormatSpecifier::MAX_PRECISION`],
+/// which is the number of digits after the decimal separator.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_core::prelude::*;
+/// let specifier: FormatSpecifier = "N0".parse().unwrap();
+/// assert_eq!(specifier.kind, FormatKind::Number);
+/// assert_eq!(specifier.precision, Some(0));
+/// assert!("X2".parse::<FormatSpecifier>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct FormatSpecifier {
+    /// How the number is formatted.
+    pub kind: FormatKind,
+    /// The number of digits after the decimal separator. If `None`, [`FormatKind::default_precision`] is used.
+    pub precision: Option<u8>,
+}
+
+/// The kind of a [`FormatSpecifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum FormatKind {
+    /// `N`: A number with grouping separators, e.g. `1,234.50` in English or `1.234,50` in German.
+    Number,
+    /// `F`: A number without grouping separators, e.g. `1234.50` in English or `1234,50` in German.
+    FixedPoint,
+    /// `P`: A number multiplied by 100 and followed by a percent sign, e.g. `12.50%` for `0.125`.
+    Percent,
+}
+
+impl FormatKind {
+    /// The precision used when a [`FormatSpecifier`] does not specify one. This is `2` for all kinds, just like in .NET.
+    pub const fn default_precision(self) -> u8 {
+        2
+    }
+}
+
+impl FormatSpecifier {
+    /// The highest precision that can be specified. Higher values would only show imprecisions of the underlying floating point numbers.
+    pub const MAX_PRECISION: u8 = 15;
+
+    /// Returns the precision of this specifier, falling back to [`FormatKind::default_precision`].
+    pub fn precision_or_default(&self) -> u8 {
+        self.precision
+            .unwrap_or_else(|| self.kind.default_precision())
+    }
+}
+
+impl FromStr for FormatSpecifier {
+    type Err = ParseFormatSpecifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseFormatSpecifierError(s.to_owned());
+        let mut chars = s.chars();
+        let kind = match chars.next().map(|c| c.to_ascii_uppercase()) {
+            Some('N') => FormatKind::Number,
+            Some('F') => FormatKind::FixedPoint,
+            Some('P') => FormatKind::Percent,
+            _ => return Err(error()),
+        };
+        let precision = chars.as_str();
+        let precision = if precision.is_empty() {
+            None
+        } else if precision.chars().all(|c| c.is_ascii_digit()) {
+            let precision = precision.parse().map_err(|_| error())?;
+            if precision > Self::MAX_PRECISION {
+                return Err(error());
+            }
+            Some(precision)
+        } else {
+            return Err(error());
+        };
+        Ok(Self { kind, precision })
+    }
+}
+
+impl Display for FormatSpecifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let kind = match self.kind {
+            FormatKind::Number => 'N',
+            FormatKind::FixedPoint => 'F',
+            FormatKind::Percent => 'P',
+        };
+        write!(f, "{kind}")?;
+        if let Some(precision) = self.precision {
+            write!(f, "{precision}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents a failure to parse a [`FormatSpecifier`]. Contains the offending specifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFormatSpecifierError(pub String);
+
+impl Error for ParseFormatSpecifierError {}
+
+impl Display for ParseFormatSpecifierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Invalid format specifier \"{}\". Expected one of `N`, `F` or `P`, optionally followed by a precision of up to {}, e.g. `N0`.",
+            self.0,
+            FormatSpecifier::MAX_PRECISION
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_specifiers() {
+        let parse = |s: &str| s.parse::<FormatSpecifier>();
+        assert_eq!(
+            parse("N0"),
+            Ok(FormatSpecifier {
+                kind: FormatKind::Number,
+                precision: Some(0)
+            })
+        );
+        assert_eq!(
+            parse("p"),
+            Ok(FormatSpecifier {
+                kind: FormatKind::Percent,
+                precision: None
+            })
+        );
+        assert_eq!(parse("F15").unwrap().precision, Some(15));
+        assert!(parse("").is_err());
+        assert!(parse("F16").is_err());
+        assert!(parse("N-1").is_err());
+        assert!(parse("Number").is_err());
+    }
+
+    #[test]
+    fn displays_specifiers() {
+        for specifier in ["N0", "F3", "P"] {
+            assert_eq!(
+                specifier.parse::<FormatSpecifier>().unwrap().to_string(),
+                specifier
+            );
+        }
+    }
+}
@@ -14,6 +14,7 @@ extern crate alloc;
 extern crate std;
 
 mod feature_gates;
+mod format_specifier;
 mod generated;
 mod internal_value;
 mod library;
@@ -40,6 +41,7 @@ pub mod prelude {
     };
 
     pub use crate::{
+        format_specifier::*,
         generated::{
             Header, Instruction, InvalidOpCodeError, Node, Operand, Program, instruction::OpCode,
             operand::Value as OperandValue,
@@ -36,3 +36,35 @@ impl Display for LineId {
         self.0.fmt(f)
     }
 }
+
+impl LineId {
+    /// Returns the identifier of the [Fluent](https://projectfluent.org/) message that holds the text of this line.
+    /// Fluent identifiers must start with an ASCII letter and may only contain ASCII letters, digits, `_` and `-`,
+    /// so `:` is replaced by `-` and `_` escapes everything else: `-` becomes `_-`, `_` becomes `__`
+    /// and any other character becomes its hexadecimal code point between two `_`.
+    /// IDs not starting with a letter are prefixed with `line_x`. This way, different line IDs never share a message.
+    ///
+    /// ```
+    /// # use yarnspinner_core::prelude::*;
+    /// assert_eq!(LineId::from("line:123").to_fluent_message_id(), "line-123");
+    /// assert_eq!(LineId::from("line:a.b").to_fluent_message_id(), "line-a_2e_b");
+    /// assert_eq!(LineId::from("line:a-b").to_fluent_message_id(), "line-a_-b");
+    /// assert_eq!(LineId::from("42").to_fluent_message_id(), "line_x42");
+    /// ```
+    pub fn to_fluent_message_id(&self) -> String {
+        let mut id = String::with_capacity(self.0.len());
+        if !self.0.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            id.push_str("line_x");
+        }
+        for c in self.0.chars() {
+            match c {
+                c if c.is_ascii_alphanumeric() => id.push(c),
+                ':' => id.push('-'),
+                '-' => id.push_str("_-"),
+                '_' => id.push_str("__"),
+                c => id.push_str(&format!("_{:x}_", u32::from(c))),
+            }
+        }
+        id
+    }
+}
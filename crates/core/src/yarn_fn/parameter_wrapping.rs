@@ -34,16 +34,47 @@ impl From<YarnValue> for YarnValueWrapper {
 }
 
 impl YarnValueWrapper {
-    fn convert<T>(&mut self)
+    fn try_convert<T>(&mut self) -> Result<(), YarnFnParamError>
     where
         T: TryFrom<YarnValue> + 'static,
         <T as TryFrom<YarnValue>>::Error: Display,
     {
         let raw = core::mem::take(&mut self.raw).unwrap();
-        let converted: T = raw
-            .try_into()
-            .unwrap_or_else(|e| panic!("Parameter passed to Yarn has invalid type: {e}"));
+        let converted = T::try_from(raw).map_err(|e| YarnFnParamError::InvalidType {
+            error: e.to_string(),
+        })?;
         self.converted.replace(Box::new(converted));
+        Ok(())
+    }
+}
+
+/// An error returned when the values passed from Yarn don't fit the parameters of a [`YarnFn`]-like function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YarnFnParamError {
+    /// Fewer values were passed than there are required parameters.
+    TooFewArguments,
+    /// More values were passed than there are parameters.
+    TooManyArguments,
+    /// A value could not be converted to the type of its parameter.
+    InvalidType {
+        /// The description of the failed conversion.
+        error: String,
+    },
+}
+
+impl core::error::Error for YarnFnParamError {}
+
+impl Display for YarnFnParamError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            YarnFnParamError::TooFewArguments => f.write_str("Passed too few arguments to YarnFn"),
+            YarnFnParamError::TooManyArguments => {
+                f.write_str("Passed too many arguments to YarnFn")
+            }
+            YarnFnParamError::InvalidType { error } => {
+                write!(f, "Parameter passed to Yarn has invalid type: {error}")
+            }
+        }
     }
 }
 
@@ -65,7 +96,15 @@ pub trait YarnFnParam {
     type Optionality: Optionality;
 
     #[doc(hidden)]
-    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a>;
+    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
+        Self::try_retrieve(iter).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Like [`YarnFnParam::retrieve`], but returns an error instead of panicking if the passed values don't fit this parameter.
+    #[doc(hidden)]
+    fn try_retrieve<'a>(
+        iter: &mut YarnValueWrapperIter<'a>,
+    ) -> Result<Self::Item<'a>, YarnFnParamError>;
 
     #[doc(hidden)]
     fn parameter_types() -> Vec<TypeId>;
@@ -78,11 +117,13 @@ impl<T: YarnFnParam + 'static> YarnFnParam for Option<T> {
     type Item<'new> = Option<T::Item<'new>>;
     type Optionality = Optional;
 
-    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
+    fn try_retrieve<'a>(
+        iter: &mut YarnValueWrapperIter<'a>,
+    ) -> Result<Self::Item<'a>, YarnFnParamError> {
         if iter.peek().is_some() {
-            Some(T::retrieve(iter))
+            T::try_retrieve(iter).map(Some)
         } else {
-            None
+            Ok(None)
         }
     }
 
@@ -102,8 +143,8 @@ macro_rules! impl_yarn_fn_param_tuple {
             type Optionality = <($(<$param as YarnFnParam>::Optionality,)*) as AllowedOptionalityChain>::Last;
 
             #[allow(unused_variables, clippy::unused_unit)] // for n = 0 tuples
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-               ($($param::retrieve(iter),)*)
+            fn try_retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Result<Self::Item<'a>, YarnFnParamError> {
+               Ok(($($param::try_retrieve(iter)?,)*))
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -132,15 +173,17 @@ where
     type Item<'new> = ResRef<'new, T>;
     type Optionality = Required;
 
-    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-        let value = iter.next().expect("Passed too few arguments to YarnFn");
-        value.convert::<T>();
+    fn try_retrieve<'a>(
+        iter: &mut YarnValueWrapperIter<'a>,
+    ) -> Result<Self::Item<'a>, YarnFnParamError> {
+        let value = iter.next().ok_or(YarnFnParamError::TooFewArguments)?;
+        value.try_convert::<T>()?;
         let converted = value.converted.as_ref().unwrap();
         let value = converted.downcast_ref::<T>().unwrap();
-        ResRef {
+        Ok(ResRef {
             value,
             phantom_data: PhantomData,
-        }
+        })
     }
 
     fn parameter_types() -> Vec<TypeId> {
@@ -171,15 +214,17 @@ where
     type Item<'new> = ResRefBorrow<'new, T, U>;
     type Optionality = Required;
 
-    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-        let value = iter.next().expect("Passed too few arguments to YarnFn");
-        value.convert::<T>();
+    fn try_retrieve<'a>(
+        iter: &mut YarnValueWrapperIter<'a>,
+    ) -> Result<Self::Item<'a>, YarnFnParamError> {
+        let value = iter.next().ok_or(YarnFnParamError::TooFewArguments)?;
+        value.try_convert::<T>()?;
         let converted = value.converted.as_ref().unwrap();
         let value = converted.downcast_ref::<T>().unwrap();
-        ResRefBorrow {
+        Ok(ResRefBorrow {
             value: value.borrow(),
             phantom_data: PhantomData,
-        }
+        })
     }
 
     fn parameter_types() -> Vec<TypeId> {
@@ -203,12 +248,14 @@ where
     type Item<'new> = ResOwned<T>;
     type Optionality = Required;
 
-    fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-        let value = iter.next().expect("Passed too few arguments to YarnFn");
-        value.convert::<T>();
+    fn try_retrieve<'a>(
+        iter: &mut YarnValueWrapperIter<'a>,
+    ) -> Result<Self::Item<'a>, YarnFnParamError> {
+        let value = iter.next().ok_or(YarnFnParamError::TooFewArguments)?;
+        value.try_convert::<T>()?;
         let converted = value.converted.take().unwrap();
         let value = *converted.downcast::<T>().unwrap();
-        ResOwned { value }
+        Ok(ResOwned { value })
     }
 
     fn parameter_types() -> Vec<TypeId> {
@@ -232,8 +279,10 @@ macro_rules! impl_yarn_fn_param_inner {
             type Item<'new> = &'new $referenced;
             type Optionality = Required;
 
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-                ResRef::<$referenced>::retrieve(iter).value
+            fn try_retrieve<'a>(
+                iter: &mut YarnValueWrapperIter<'a>,
+            ) -> Result<Self::Item<'a>, YarnFnParamError> {
+                ResRef::<$referenced>::try_retrieve(iter).map(|r| r.value)
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -245,8 +294,10 @@ macro_rules! impl_yarn_fn_param_inner {
             type Item<'new> = $referenced;
             type Optionality = Required;
 
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-                ResOwned::<$referenced>::retrieve(iter).value
+            fn try_retrieve<'a>(
+                iter: &mut YarnValueWrapperIter<'a>,
+            ) -> Result<Self::Item<'a>, YarnFnParamError> {
+                ResOwned::<$referenced>::try_retrieve(iter).map(|r| r.value)
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -259,8 +310,10 @@ macro_rules! impl_yarn_fn_param_inner {
             type Item<'new> = &'new $referenced;
             type Optionality = Required;
 
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-                ResRefBorrow::<$owned, $referenced>::retrieve(iter).value
+            fn try_retrieve<'a>(
+                iter: &mut YarnValueWrapperIter<'a>,
+            ) -> Result<Self::Item<'a>, YarnFnParamError> {
+                ResRefBorrow::<$owned, $referenced>::try_retrieve(iter).map(|r| r.value)
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -272,8 +325,10 @@ macro_rules! impl_yarn_fn_param_inner {
             type Item<'new> = &'new $owned;
             type Optionality = Required;
 
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-                ResRef::<$owned>::retrieve(iter).value
+            fn try_retrieve<'a>(
+                iter: &mut YarnValueWrapperIter<'a>,
+            ) -> Result<Self::Item<'a>, YarnFnParamError> {
+                ResRef::<$owned>::try_retrieve(iter).map(|r| r.value)
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -285,8 +340,10 @@ macro_rules! impl_yarn_fn_param_inner {
             type Item<'new> = $owned;
             type Optionality = Required;
 
-            fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
-                ResOwned::<$owned>::retrieve(iter).value
+            fn try_retrieve<'a>(
+                iter: &mut YarnValueWrapperIter<'a>,
+            ) -> Result<Self::Item<'a>, YarnFnParamError> {
+                ResOwned::<$owned>::try_retrieve(iter).map(|r| r.value)
             }
 
             fn parameter_types() -> Vec<TypeId> {
@@ -1,4 +1,5 @@
 use crate::assets::{font_handle, image_handle};
+use bevy::color::palettes::css;
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
@@ -140,22 +141,19 @@ fn fmt_name(name: &str) -> Name {
     Name::new(format!("Yarn Spinner example dialogue view node: {name}"))
 }
 
-pub(crate) fn create_dialog_text(
-    text: impl Into<String>,
-    invisible: impl Into<String>,
-) -> [(TextSpan, TextFont, TextColor); 2] {
-    [
-        (
-            TextSpan(text.into()),
-            text_style::standard().0,
-            text_style::standard().1,
-        ),
-        (
-            TextSpan(invisible.into()),
-            text_style::standard().0,
-            TextColor(Color::NONE),
-        ),
-    ]
+/// The styles of markup in lines, e.g. `[b]` in `Hello [b]world[/b]!`.
+pub(crate) fn markup_styles() -> MarkupStyles {
+    let (font, color) = text_style::standard();
+    MarkupStyles::new(font, color)
+        .with_style("b", MarkupStyle::new().with_color(css::GOLD))
+        .with_style("i", MarkupStyle::new().with_color(css::LIGHT_SKY_BLUE))
+        .with_style("shout", MarkupStyle::new().with_font_size(24.0))
+        .with_style(
+            "whisper",
+            MarkupStyle::new()
+                .with_font_size(16.0)
+                .with_color(css::LIGHT_GRAY),
+        )
 }
 
 pub(crate) fn spawn_options<'a, T>(entity_commands: &mut EntityCommands, options: T)
@@ -1,11 +1,10 @@
 use crate::ExampleYarnSpinnerDialogueViewSystemSet;
 use crate::option_selection::OptionSelection;
-use crate::setup::{DialogueContinueNode, DialogueNode, UiRootNode, create_dialog_text};
+use crate::setup::{DialogueContinueNode, DialogueNode, UiRootNode, markup_styles};
 use crate::updating::SpeakerChangeEvent;
 use bevy::platform::time::Instant;
 use bevy::prelude::*;
 use bevy_yarnspinner::{events::*, prelude::*};
-use unicode_segmentation::UnicodeSegmentation;
 
 pub(crate) fn typewriter_plugin(app: &mut App) {
     app.add_systems(
@@ -32,8 +31,8 @@ pub(crate) struct TypewriterFinishedEvent;
 #[derive(Debug, Clone, PartialEq, Resource)]
 pub(crate) struct Typewriter {
     pub(crate) character_name: Option<String>,
-    pub(crate) current_text: String,
-    pub(crate) graphemes_left: Vec<String>,
+    pub(crate) rich_text: RichText,
+    pub(crate) graphemes_written: usize,
     pub(crate) last_before_options: bool,
     elapsed: f32,
     start: Instant,
@@ -44,8 +43,8 @@ impl Default for Typewriter {
     fn default() -> Self {
         Self {
             character_name: default(),
-            current_text: default(),
-            graphemes_left: default(),
+            rich_text: default(),
+            graphemes_written: default(),
             last_before_options: default(),
             elapsed: default(),
             start: Instant::now(),
@@ -58,19 +57,14 @@ impl Typewriter {
     pub(crate) fn set_line(&mut self, line: &LocalizedLine) {
         *self = Self {
             character_name: line.character_name().map(|s| s.to_string()),
-            current_text: String::new(),
-            graphemes_left: line
-                .text_without_character_name()
-                .graphemes(true)
-                .map(|s| s.to_string())
-                .collect(),
+            rich_text: RichText::from_line_without_character_name(line, &markup_styles()),
             last_before_options: line.is_last_line_before_options(),
             ..default()
         };
     }
 
     pub(crate) fn is_finished(&self) -> bool {
-        self.graphemes_left.is_empty() && !self.current_text.is_empty()
+        self.graphemes_written > 0 && self.graphemes_left() == 0
     }
 
     pub(crate) fn fast_forward(&mut self) {
@@ -84,11 +78,13 @@ impl Typewriter {
         self.elapsed += self.start.elapsed().as_secs_f32();
         self.start = Instant::now();
         let calculated_graphemes = (self.graphemes_per_second() * self.elapsed).floor() as usize;
-        let graphemes_left = self.graphemes_left.len();
-        let grapheme_length_to_take = (calculated_graphemes).min(graphemes_left);
+        let grapheme_length_to_take = (calculated_graphemes).min(self.graphemes_left());
         self.elapsed -= grapheme_length_to_take as f32 / self.graphemes_per_second();
-        let graphemes_to_take = self.graphemes_left.drain(..grapheme_length_to_take);
-        self.current_text.extend(graphemes_to_take);
+        self.graphemes_written += grapheme_length_to_take;
+    }
+
+    fn graphemes_left(&self) -> usize {
+        self.rich_text.grapheme_count() - self.graphemes_written
     }
 
     fn graphemes_per_second(&self) -> f32 {
@@ -126,14 +122,15 @@ fn write_text(
         });
     }
 
-    let current_text = &typewriter.current_text;
-    let rest = typewriter.graphemes_left.join("");
-    let spans = create_dialog_text(current_text, rest);
+    let spans = typewriter
+        .rich_text
+        .revealed_spans(typewriter.graphemes_written);
     text_entity
         .despawn_related::<Children>()
         .with_children(|parent| {
-            parent.spawn(spans[0].clone());
-            parent.spawn(spans[1].clone());
+            for span in spans {
+                parent.spawn(span);
+            }
         });
 }
 
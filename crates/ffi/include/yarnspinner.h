@@ -0,0 +1,574 @@
+/* Generated by cbindgen from the yarnspinner_ffi crate. Do not edit by hand. */
+
+#ifndef YARNSPINNER_H
+#define YARNSPINNER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The most parameters a function registered through [`ys_dialogue_add_function`] can have.
+ */
+#define YS_MAX_FUNCTION_PARAMETERS 8
+
+/**
+ * The result of a call into the C API. On anything but [`YsStatus::Ok`], [`ys_last_error_message`] describes what went wrong.
+ */
+typedef enum YsStatus {
+  /**
+   * The call succeeded.
+   */
+  YS_STATUS_OK = 0,
+  /**
+   * A required pointer argument was `NULL`.
+   */
+  YS_STATUS_NULL_ARGUMENT,
+  /**
+   * A string argument was not valid UTF-8.
+   */
+  YS_STATUS_INVALID_UTF8,
+  /**
+   * An argument had an invalid value, e.g. an unknown option ID or too many function parameters.
+   */
+  YS_STATUS_INVALID_ARGUMENT,
+  /**
+   * The Yarn sources could not be compiled.
+   */
+  YS_STATUS_COMPILATION_FAILED,
+  /**
+   * A program blob could not be decoded.
+   */
+  YS_STATUS_INVALID_PROGRAM,
+  /**
+   * The dialogue could not continue, e.g. because no node was set.
+   */
+  YS_STATUS_DIALOGUE_ERROR,
+  /**
+   * A variable could not be read or written.
+   */
+  YS_STATUS_VARIABLE_ERROR,
+  /**
+   * The Rust side panicked. The objects involved must still be freed, but should not be used otherwise.
+   */
+  YS_STATUS_PANIC,
+} YsStatus;
+
+/**
+ * The kind of a [`YsEvent`], which determines its meaningful fields.
+ */
+typedef enum YsEventType {
+  /**
+   * There is nothing to do until the caller acts: an option must be selected, a running command must be completed,
+   * or the dialogue was completed and a new node must be set.
+   */
+  YS_EVENT_TYPE_NONE,
+  /**
+   * A line should be presented. See [`YsEvent::line`].
+   */
+  YS_EVENT_TYPE_LINE,
+  /**
+   * Options should be presented and one of them selected through [`ys_dialogue_select_option`](crate::ys_dialogue_select_option).
+   * See [`YsEvent::options`].
+   */
+  YS_EVENT_TYPE_OPTIONS,
+  /**
+   * A command without a registered handler should be run. See [`YsEvent::command`].
+   */
+  YS_EVENT_TYPE_COMMAND,
+  /**
+   * A node was entered. See [`YsEvent::node_name`].
+   */
+  YS_EVENT_TYPE_NODE_START,
+  /**
+   * A node was completed. See [`YsEvent::node_name`].
+   */
+  YS_EVENT_TYPE_NODE_COMPLETE,
+  /**
+   * The dialogue was completed. Set a new node through [`ys_dialogue_set_node`](crate::ys_dialogue_set_node) to continue.
+   */
+  YS_EVENT_TYPE_DIALOGUE_COMPLETE,
+} YsEventType;
+
+/**
+ * The result of compiling Yarn sources, created by [`ys_compile`] and released by [`ys_compilation_free`].
+ */
+typedef struct YsCompilation YsCompilation;
+
+/**
+ * A running dialogue, created by [`ys_dialogue_new`] and released by [`ys_dialogue_free`].
+ *
+ * Uses an in-memory variable storage and the base language texts added through [`ys_dialogue_load_compilation`] or [`ys_dialogue_add_line`].
+ */
+typedef struct YsDialogue YsDialogue;
+
+/**
+ * The type of a [`YsValue`], one of the `YS_VALUE_TYPE_*` constants.
+ *
+ * This is an integer rather than an enum because C may pass any value, which would be undefined behavior for a Rust enum.
+ */
+typedef uint32_t YsValueType;
+
+/**
+ * A value passed between Yarn and C. Only the field selected by `type` is meaningful.
+ */
+typedef struct YsValue {
+  /**
+   * Which of the other fields holds the value.
+   */
+  YsValueType type;
+  /**
+   * The value if `type` is [`YS_VALUE_TYPE_NUMBER`].
+   */
+  float number;
+  /**
+   * The value if `type` is [`YS_VALUE_TYPE_BOOLEAN`].
+   */
+  bool boolean;
+  /**
+   * The NUL-terminated UTF-8 value if `type` is [`YS_VALUE_TYPE_STRING`].
+   */
+  const char *string;
+} YsValue;
+
+/**
+ * A function callable from Yarn, registered through [`ys_dialogue_add_function`].
+ *
+ * Receives the `user_data` it was registered with and the arguments passed by Yarn.
+ * Must write a value of the registered return type to `out_result`. A returned string is copied right after the callback returns.
+ * The callback must not call into the dialogue it is registered on.
+ */
+typedef void (*YsFunctionCallback)(void *user_data,
+                                   const struct YsValue *arguments,
+                                   size_t argument_count,
+                                   struct YsValue *out_result);
+
+/**
+ * What a [`YsCommandCallback`] reports back to the dialogue, one of the `YS_COMMAND_RESULT_*` constants.
+ *
+ * Like [`YsValueType`], this is an integer so that invalid values can be rejected.
+ */
+typedef uint32_t YsCommandResult;
+
+/**
+ * A command, e.g. `<<wait 2>>`.
+ */
+typedef struct YsCommand {
+  /**
+   * The name of the command, e.g. `wait`.
+   */
+  const char *name;
+  /**
+   * The parameters of the command, e.g. `2`. Parameters are split at whitespace, except inside quotes,
+   * and passed as strings without their surrounding quotes. `NULL` if there are none.
+   */
+  const struct YsValue *parameters;
+  /**
+   * The number of entries in `parameters`.
+   */
+  size_t parameter_count;
+  /**
+   * The command as written in the Yarn file, without the `<<` and `>>`.
+   */
+  const char *raw;
+} YsCommand;
+
+/**
+ * A command handler registered through [`ys_dialogue_add_command`].
+ *
+ * Receives the `user_data` it was registered with and the command, which is only valid during the call.
+ * The callback must not call into the dialogue it is registered on.
+ */
+typedef YsCommandResult (*YsCommandCallback)(void *user_data, const struct YsCommand *command);
+
+/**
+ * A Yarn source file passed to [`ys_compile`].
+ */
+typedef struct YsSource {
+  /**
+   * The name of the file, used in diagnostics and implicit line IDs.
+   */
+  const char *file_name;
+  /**
+   * The contents of the file.
+   */
+  const char *source;
+} YsSource;
+
+/**
+ * A byte buffer owned by the caller. Release it with [`ys_buffer_free`].
+ */
+typedef struct YsBuffer {
+  /**
+   * The bytes of the buffer.
+   */
+  uint8_t *data;
+  /**
+   * The number of bytes in `data`.
+   */
+  size_t length;
+} YsBuffer;
+
+/**
+ * A property of a [`YsAttribute`], e.g. `name` in `[character name="Alice"]`.
+ */
+typedef struct YsProperty {
+  /**
+   * The name of the property.
+   */
+  const char *name;
+  /**
+   * The value of the property. Integers and floats are both numbers.
+   */
+  struct YsValue value;
+} YsProperty;
+
+/**
+ * A markup attribute of a [`YsLine`], e.g. `[wave]Hello[/wave]`.
+ */
+typedef struct YsAttribute {
+  /**
+   * The name of the attribute.
+   */
+  const char *name;
+  /**
+   * The position in the line's text at which the attribute starts, counted in grapheme clusters.
+   */
+  size_t position;
+  /**
+   * The number of grapheme clusters the attribute spans.
+   */
+  size_t length;
+  /**
+   * The properties of the attribute, sorted by name. `NULL` if there are none.
+   */
+  const struct YsProperty *properties;
+  /**
+   * The number of entries in `properties`.
+   */
+  size_t property_count;
+} YsAttribute;
+
+/**
+ * A line of dialogue with its markup already parsed.
+ */
+typedef struct YsLine {
+  /**
+   * The ID of the line.
+   */
+  const char *id;
+  /**
+   * The text of the line, without markup.
+   */
+  const char *text;
+  /**
+   * The markup attributes of the line. `NULL` if there are none.
+   */
+  const struct YsAttribute *attributes;
+  /**
+   * The number of entries in `attributes`.
+   */
+  size_t attribute_count;
+} YsLine;
+
+/**
+ * An option that can be selected by the user.
+ */
+typedef struct YsOption {
+  /**
+   * The ID to pass to [`ys_dialogue_select_option`](crate::ys_dialogue_select_option) when this option is selected.
+   */
+  size_t id;
+  /**
+   * The line presented for this option.
+   */
+  struct YsLine line;
+  /**
+   * The name of the node that is run when this option is selected.
+   */
+  const char *destination_node;
+  /**
+   * Whether the option's condition passed. Unavailable options should usually be shown as disabled.
+   */
+  bool is_available;
+} YsOption;
+
+/**
+ * An event returned by [`ys_dialogue_next_event`]. Only the fields belonging to its `type` are meaningful, all other pointers are `NULL`.
+ *
+ * All pointers are borrowed from the dialogue and stay valid until the next call to [`ys_dialogue_next_event`] or until the dialogue is released.
+ */
+typedef struct YsEvent {
+  /**
+   * The kind of the event.
+   */
+  enum YsEventType type;
+  /**
+   * The line for [`YsEventType::Line`].
+   */
+  struct YsLine line;
+  /**
+   * The options for [`YsEventType::Options`].
+   */
+  const struct YsOption *options;
+  /**
+   * The number of entries in `options`.
+   */
+  size_t option_count;
+  /**
+   * The command for [`YsEventType::Command`].
+   */
+  struct YsCommand command;
+  /**
+   * The name of the node for [`YsEventType::NodeStart`] and [`YsEventType::NodeComplete`].
+   */
+  const char *node_name;
+} YsEvent;
+
+/**
+ * The command is done, the dialogue continues right away.
+ */
+#define YS_COMMAND_RESULT_FINISHED 0
+
+/**
+ * The command keeps running, e.g. to play an animation. [`ys_dialogue_next_event`](crate::ys_dialogue_next_event) returns
+ * `YS_EVENT_TYPE_NONE` until [`ys_dialogue_complete_command`] is called.
+ */
+#define YS_COMMAND_RESULT_RUNNING 1
+
+/**
+ * The value is stored in [`YsValue::number`].
+ */
+#define YS_VALUE_TYPE_NUMBER 0
+
+/**
+ * The value is stored in [`YsValue::string`].
+ */
+#define YS_VALUE_TYPE_STRING 1
+
+/**
+ * The value is stored in [`YsValue::boolean`].
+ */
+#define YS_VALUE_TYPE_BOOLEAN 2
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Registers a function that Yarn scripts can call, replacing any function of the same name.
+ *
+ * The function takes `parameter_count` parameters of any type and returns a value of `return_type`, one of the `YS_VALUE_TYPE_*` constants.
+ * Register functions before passing the dialogue to [`ys_compile`](crate::ys_compile), so that the compiler knows about them.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue, `name` a valid string and `callback` either NULL or a valid function pointer.
+ * `user_data` must stay valid for as long as the dialogue lives.
+ */
+enum YsStatus ys_dialogue_add_function(struct YsDialogue *dialogue,
+                                       const char *name,
+                                       YsValueType return_type,
+                                       size_t parameter_count,
+                                       YsFunctionCallback callback,
+                                       void *user_data);
+
+/**
+ * Registers a handler for the command with the given name, replacing any handler of the same name.
+ *
+ * Commands with a handler are not returned by [`ys_dialogue_next_event`](crate::ys_dialogue_next_event),
+ * instead the handler is called when the dialogue reaches them. All other commands are returned as `YS_EVENT_TYPE_COMMAND`.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue, `name` a valid string and `callback` either NULL or a valid function pointer.
+ * `user_data` must stay valid for as long as the dialogue lives.
+ */
+enum YsStatus ys_dialogue_add_command(struct YsDialogue *dialogue,
+                                      const char *name,
+                                      YsCommandCallback callback,
+                                      void *user_data);
+
+/**
+ * Signals that the command whose handler returned [`YS_COMMAND_RESULT_RUNNING`] finished, so that the dialogue can continue.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue.
+ */
+enum YsStatus ys_dialogue_complete_command(struct YsDialogue *dialogue);
+
+/**
+ * Compiles `source_count` Yarn sources into `*out_compilation`.
+ *
+ * If `dialogue` is not `NULL`, the functions registered on it through [`ys_dialogue_add_function`](crate::ys_dialogue_add_function)
+ * are made known to the compiler, so register them before compiling.
+ * On failure, [`ys_last_error_message`](crate::ys_last_error_message) contains the compiler's diagnostics.
+ *
+ * # Safety
+ * `sources` must point to `source_count` valid [`YsSource`]s, `dialogue` must be `NULL` or a live dialogue
+ * and `out_compilation` must be writable.
+ */
+enum YsStatus ys_compile(const struct YsSource *sources,
+                         size_t source_count,
+                         const struct YsDialogue *dialogue,
+                         struct YsCompilation **out_compilation);
+
+/**
+ * Releases a compilation created by [`ys_compile`]. Does nothing if `compilation` is `NULL`.
+ *
+ * # Safety
+ * `compilation` must be `NULL` or a compilation that was not released yet.
+ */
+void ys_compilation_free(struct YsCompilation *compilation);
+
+/**
+ * Serializes the compiled program into `*out_program`, e.g. to ship it with a game and load it later through
+ * [`ys_dialogue_load_program`](crate::ys_dialogue_load_program). Release the buffer with [`ys_buffer_free`].
+ *
+ * # Safety
+ * `compilation` must be a live compilation and `out_program` must be writable.
+ */
+enum YsStatus ys_compilation_program(const struct YsCompilation *compilation,
+                                     struct YsBuffer *out_program);
+
+/**
+ * Releases a buffer filled by the library and resets it to an empty buffer.
+ *
+ * # Safety
+ * `buffer` must be `NULL` or point to a buffer that was filled by the library and not released yet.
+ */
+void ys_buffer_free(struct YsBuffer *buffer);
+
+/**
+ * Returns the number of lines in the string table of the compilation, or 0 if `compilation` is `NULL`.
+ *
+ * # Safety
+ * `compilation` must be `NULL` or a live compilation.
+ */
+size_t ys_compilation_line_count(const struct YsCompilation *compilation);
+
+/**
+ * Gets the ID and base language text of the line at `index` in the string table of the compilation.
+ * Lines are sorted by their ID. The strings are borrowed from the compilation and stay valid until it is released.
+ *
+ * # Safety
+ * `compilation` must be a live compilation and `out_id` and `out_text` must be writable.
+ */
+enum YsStatus ys_compilation_line(const struct YsCompilation *compilation,
+                                  size_t index,
+                                  const char **out_id,
+                                  const char **out_text);
+
+/**
+ * Creates a new dialogue without a program. Release it with [`ys_dialogue_free`].
+ */
+struct YsDialogue *ys_dialogue_new(void);
+
+/**
+ * Releases a dialogue created by [`ys_dialogue_new`]. Does nothing if `dialogue` is `NULL`.
+ *
+ * # Safety
+ * `dialogue` must be `NULL` or a dialogue that was not released yet.
+ */
+void ys_dialogue_free(struct YsDialogue *dialogue);
+
+/**
+ * Replaces the program of the dialogue by the one in the compilation and adds the compiled lines to its base language texts.
+ * Stops the current node and initializes the variables declared by the program.
+ *
+ * # Safety
+ * `dialogue` and `compilation` must be live objects.
+ */
+enum YsStatus ys_dialogue_load_compilation(struct YsDialogue *dialogue,
+                                           const struct YsCompilation *compilation);
+
+/**
+ * Replaces the program of the dialogue by one serialized with [`ys_compilation_program`](crate::ys_compilation_program).
+ * Stops the current node and initializes the variables declared by the program.
+ * The texts of the lines are not part of the program, add them with [`ys_dialogue_add_line`].
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue and `data` must point to `length` readable bytes.
+ */
+enum YsStatus ys_dialogue_load_program(struct YsDialogue *dialogue,
+                                       const uint8_t *data,
+                                       size_t length);
+
+/**
+ * Adds or replaces the base language text of the line with the given ID.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue and `id` and `text` valid strings.
+ */
+enum YsStatus ys_dialogue_add_line(struct YsDialogue *dialogue, const char *id, const char *text);
+
+/**
+ * Starts the node with the given name. Any events of the previous node that were not returned yet are dropped.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue and `node_name` a valid string.
+ */
+enum YsStatus ys_dialogue_set_node(struct YsDialogue *dialogue,
+                                   const char *node_name);
+
+/**
+ * Stops the dialogue. The next calls to [`ys_dialogue_next_event`](crate::ys_dialogue_next_event) return the remaining events,
+ * ending with `YS_EVENT_TYPE_DIALOGUE_COMPLETE`.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue.
+ */
+enum YsStatus ys_dialogue_stop(struct YsDialogue *dialogue);
+
+/**
+ * Selects the option with the given ID after `YS_EVENT_TYPE_OPTIONS` was returned.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue.
+ */
+enum YsStatus ys_dialogue_select_option(struct YsDialogue *dialogue, size_t option_id);
+
+/**
+ * Sets the variable with the given name, including its leading `$`.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue, `name` a valid string and `value` must point to a valid [`YsValue`].
+ */
+enum YsStatus ys_dialogue_set_variable(struct YsDialogue *dialogue,
+                                       const char *name,
+                                       const struct YsValue *value);
+
+/**
+ * Gets the variable with the given name, including its leading `$`.
+ * A string value is borrowed from the dialogue and stays valid until the next call to this function or until the dialogue is released.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue, `name` a valid string and `out_value` must be writable.
+ */
+enum YsStatus ys_dialogue_get_variable(struct YsDialogue *dialogue,
+                                       const char *name,
+                                       struct YsValue *out_value);
+
+/**
+ * Returns a description of the last error that occurred on the calling thread, or `NULL` if no call failed yet.
+ *
+ * The string is owned by the library and stays valid until the next failing call on the same thread.
+ */
+const char *ys_last_error_message(void);
+
+/**
+ * Advances the dialogue and writes the next event to `*out_event`.
+ *
+ * Returns `YS_EVENT_TYPE_NONE` while the dialogue waits for the caller, see [`YsEventType::None`].
+ * Registered command handlers and Yarn functions are called from within this function.
+ *
+ * # Safety
+ * `dialogue` must be a live dialogue and `out_event` must be writable.
+ */
+enum YsStatus ys_dialogue_next_event(struct YsDialogue *dialogue, struct YsEvent *out_event);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* YARNSPINNER_H */
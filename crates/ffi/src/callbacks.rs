@@ -0,0 +1,283 @@
+use crate::dialogue::YsDialogue;
+use crate::error::{FfiError, Result, YsStatus, ffi_boundary};
+use crate::events::YsCommand;
+use crate::value::{ValueType, YsValue, YsValueType, read_str};
+use std::ffi::{CStr, c_char, c_void};
+use std::sync::Arc;
+use yarnspinner::core::YarnValue;
+
+/// A function callable from Yarn, registered through [`ys_dialogue_add_function`].
+///
+/// Receives the `user_data` it was registered with and the arguments passed by Yarn.
+/// Must write a value of the registered return type to `out_result`. A returned string is copied right after the callback returns.
+/// The callback must not call into the dialogue it is registered on.
+pub type YsFunctionCallback = Option<
+    extern "C" fn(
+        user_data: *mut c_void,
+        arguments: *const YsValue,
+        argument_count: usize,
+        out_result: *mut YsValue,
+    ),
+>;
+
+/// A command handler registered through [`ys_dialogue_add_command`].
+///
+/// Receives the `user_data` it was registered with and the command, which is only valid during the call.
+/// The callback must not call into the dialogue it is registered on.
+pub type YsCommandCallback =
+    Option<extern "C" fn(user_data: *mut c_void, command: *const YsCommand) -> YsCommandResult>;
+
+/// What a [`YsCommandCallback`] reports back to the dialogue, one of the `YS_COMMAND_RESULT_*` constants.
+///
+/// Like [`YsValueType`], this is an integer so that invalid values can be rejected.
+pub type YsCommandResult = u32;
+
+/// The command is done, the dialogue continues right away.
+pub const YS_COMMAND_RESULT_FINISHED: YsCommandResult = 0;
+/// The command keeps running, e.g. to play an animation. [`ys_dialogue_next_event`](crate::ys_dialogue_next_event) returns
+/// `YS_EVENT_TYPE_NONE` until [`ys_dialogue_complete_command`] is called.
+pub const YS_COMMAND_RESULT_RUNNING: YsCommandResult = 1;
+
+/// The most parameters a function registered through [`ys_dialogue_add_function`] can have.
+pub const YS_MAX_FUNCTION_PARAMETERS: usize = 8;
+
+#[derive(Debug, Clone)]
+struct FunctionCallback {
+    name: Arc<str>,
+    /// Never `None`, [`ys_dialogue_add_function`] rejects NULL.
+    callback: YsFunctionCallback,
+    user_data: *mut c_void,
+}
+
+// The caller guarantees that `user_data` may be used from the thread driving the dialogue.
+unsafe impl Send for FunctionCallback {}
+unsafe impl Sync for FunctionCallback {}
+
+impl FunctionCallback {
+    fn call<T: FromYsValue>(&self, arguments: Vec<YarnValue>) -> T {
+        let mut strings = Vec::new();
+        let arguments: Vec<_> = arguments
+            .iter()
+            .map(|argument| YsValue::from_yarn_value(argument, &mut strings))
+            .collect();
+        let mut result = YsValue {
+            r#type: T::TYPE.to_raw(),
+            number: 0.0,
+            boolean: false,
+            string: std::ptr::null(),
+        };
+        let callback = self
+            .callback
+            .expect("NULL callbacks are rejected when registering");
+        callback(
+            self.user_data,
+            arguments.as_ptr(),
+            arguments.len(),
+            &mut result,
+        );
+        T::from_ys_value(result).unwrap_or_else(|| {
+            match ValueType::from_raw(result.r#type, "type") {
+                Ok(value_type) => panic!(
+                    "The function `{}` returned a {value_type:?} instead of a {:?}",
+                    self.name,
+                    T::TYPE
+                ),
+                Err(e) => panic!(
+                    "The function `{}` returned an invalid value: {}",
+                    self.name, e.message
+                ),
+            }
+        })
+    }
+}
+
+trait FromYsValue: Sized {
+    const TYPE: ValueType;
+    fn from_ys_value(value: YsValue) -> Option<Self>;
+}
+
+impl FromYsValue for f32 {
+    const TYPE: ValueType = ValueType::Number;
+
+    fn from_ys_value(value: YsValue) -> Option<Self> {
+        (value.r#type == Self::TYPE.to_raw()).then_some(value.number)
+    }
+}
+
+impl FromYsValue for bool {
+    const TYPE: ValueType = ValueType::Boolean;
+
+    fn from_ys_value(value: YsValue) -> Option<Self> {
+        (value.r#type == Self::TYPE.to_raw()).then_some(value.boolean)
+    }
+}
+
+impl FromYsValue for String {
+    const TYPE: ValueType = ValueType::String;
+
+    fn from_ys_value(value: YsValue) -> Option<Self> {
+        if value.r#type != Self::TYPE.to_raw() || value.string.is_null() {
+            return None;
+        }
+        let string = unsafe { CStr::from_ptr(value.string) };
+        Some(string.to_string_lossy().into_owned())
+    }
+}
+
+macro_rules! add_function {
+    ($dialogue:expr, $name:expr, $function:expr, $return_type:ty, $parameter_count:expr; $($count:literal => ($($parameter:ident),*)),*) => {
+        match $parameter_count {
+            $(
+                $count => {
+                    let function = $function;
+                    $dialogue.library_mut().add_function(
+                        $name,
+                        move |$($parameter: YarnValue),*| -> $return_type {
+                            function.call(vec![$($parameter),*])
+                        },
+                    );
+                }
+            )*
+            _ => unreachable!(),
+        }
+    };
+    ($dialogue:expr, $name:expr, $function:expr, $return_type:ty, $parameter_count:expr) => {
+        add_function!($dialogue, $name, $function, $return_type, $parameter_count;
+            0 => (),
+            1 => (a),
+            2 => (a, b),
+            3 => (a, b, c),
+            4 => (a, b, c, d),
+            5 => (a, b, c, d, e),
+            6 => (a, b, c, d, e, f),
+            7 => (a, b, c, d, e, f, g),
+            8 => (a, b, c, d, e, f, g, h)
+        )
+    };
+}
+
+/// Registers a function that Yarn scripts can call, replacing any function of the same name.
+///
+/// The function takes `parameter_count` parameters of any type and returns a value of `return_type`, one of the `YS_VALUE_TYPE_*` constants.
+/// Register functions before passing the dialogue to [`ys_compile`](crate::ys_compile), so that the compiler knows about them.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue, `name` a valid string and `callback` either NULL or a valid function pointer.
+/// `user_data` must stay valid for as long as the dialogue lives.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_add_function(
+    dialogue: *mut YsDialogue,
+    name: *const c_char,
+    return_type: YsValueType,
+    parameter_count: usize,
+    callback: YsFunctionCallback,
+    user_data: *mut c_void,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let name = unsafe { read_str(name, "name")? }.to_owned();
+        if callback.is_none() {
+            return Err(FfiError::null_argument("callback"));
+        }
+        if parameter_count > YS_MAX_FUNCTION_PARAMETERS {
+            return Err(FfiError::new(
+                YsStatus::InvalidArgument,
+                format!(
+                    "The function `{name}` has {parameter_count} parameters, but at most {YS_MAX_FUNCTION_PARAMETERS} are supported"
+                ),
+            ));
+        }
+        let return_type = ValueType::from_raw(return_type, "return_type")?;
+        let function = FunctionCallback {
+            name: name.as_str().into(),
+            callback,
+            user_data,
+        };
+        let dialogue = &mut dialogue.dialogue;
+        match return_type {
+            ValueType::Number => add_function!(dialogue, name, function, f32, parameter_count),
+            ValueType::String => {
+                add_function!(dialogue, name, function, String, parameter_count)
+            }
+            ValueType::Boolean => {
+                add_function!(dialogue, name, function, bool, parameter_count)
+            }
+        }
+        Ok(())
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CommandCallback {
+    /// Never `None`, [`ys_dialogue_add_command`] rejects NULL.
+    callback: YsCommandCallback,
+    user_data: *mut c_void,
+}
+
+impl CommandCallback {
+    /// Runs the handler of the command called `name` and returns whether the command keeps running.
+    pub(crate) fn call(&self, name: &str, command: &YsCommand) -> Result<bool> {
+        let callback = self
+            .callback
+            .expect("NULL callbacks are rejected when registering");
+        match callback(self.user_data, command) {
+            YS_COMMAND_RESULT_FINISHED => Ok(false),
+            YS_COMMAND_RESULT_RUNNING => Ok(true),
+            result => Err(FfiError::new(
+                YsStatus::InvalidArgument,
+                format!("The handler of the command `{name}` returned an invalid result: {result}"),
+            )),
+        }
+    }
+}
+
+/// Registers a handler for the command with the given name, replacing any handler of the same name.
+///
+/// Commands with a handler are not returned by [`ys_dialogue_next_event`](crate::ys_dialogue_next_event),
+/// instead the handler is called when the dialogue reaches them. All other commands are returned as `YS_EVENT_TYPE_COMMAND`.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue, `name` a valid string and `callback` either NULL or a valid function pointer.
+/// `user_data` must stay valid for as long as the dialogue lives.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_add_command(
+    dialogue: *mut YsDialogue,
+    name: *const c_char,
+    callback: YsCommandCallback,
+    user_data: *mut c_void,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let name = unsafe { read_str(name, "name")? }.to_owned();
+        if callback.is_none() {
+            return Err(FfiError::null_argument("callback"));
+        }
+        dialogue.commands.insert(
+            name,
+            CommandCallback {
+                callback,
+                user_data,
+            },
+        );
+        Ok(())
+    })
+}
+
+/// Signals that the command whose handler returned [`YS_COMMAND_RESULT_RUNNING`] finished, so that the dialogue can continue.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_complete_command(dialogue: *mut YsDialogue) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        if !dialogue.is_running_command {
+            return Err(FfiError::new(
+                YsStatus::InvalidArgument,
+                "No command is running",
+            ));
+        }
+        dialogue.is_running_command = false;
+        Ok(())
+    })
+}
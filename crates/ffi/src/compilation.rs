@@ -0,0 +1,204 @@
+use crate::dialogue::YsDialogue;
+use crate::error::{FfiError, YsStatus, c_string, ffi_boundary};
+use crate::value::read_str;
+use prost::Message;
+use std::ffi::{CString, c_char};
+use std::{ptr, slice};
+use yarnspinner::compiler::{Compilation, Compiler, File};
+
+/// A Yarn source file passed to [`ys_compile`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsSource {
+    /// The name of the file, used in diagnostics and implicit line IDs.
+    pub file_name: *const c_char,
+    /// The contents of the file.
+    pub source: *const c_char,
+}
+
+/// A byte buffer owned by the caller. Release it with [`ys_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct YsBuffer {
+    /// The bytes of the buffer.
+    pub data: *mut u8,
+    /// The number of bytes in `data`.
+    pub length: usize,
+}
+
+/// The result of compiling Yarn sources, created by [`ys_compile`] and released by [`ys_compilation_free`].
+#[derive(Debug)]
+pub struct YsCompilation {
+    pub(crate) compilation: Compilation,
+    /// The line IDs and texts, sorted by ID so that they can be accessed by index.
+    lines: Vec<(CString, CString)>,
+}
+
+/// Compiles `source_count` Yarn sources into `*out_compilation`.
+///
+/// If `dialogue` is not `NULL`, the functions registered on it through [`ys_dialogue_add_function`](crate::ys_dialogue_add_function)
+/// are made known to the compiler, so register them before compiling.
+/// On failure, [`ys_last_error_message`](crate::ys_last_error_message) contains the compiler's diagnostics.
+///
+/// # Safety
+/// `sources` must point to `source_count` valid [`YsSource`]s, `dialogue` must be `NULL` or a live dialogue
+/// and `out_compilation` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_compile(
+    sources: *const YsSource,
+    source_count: usize,
+    dialogue: *const YsDialogue,
+    out_compilation: *mut *mut YsCompilation,
+) -> YsStatus {
+    ffi_boundary(|| {
+        if out_compilation.is_null() {
+            return Err(FfiError::null_argument("out_compilation"));
+        }
+        if sources.is_null() && source_count > 0 {
+            return Err(FfiError::null_argument("sources"));
+        }
+        let sources = if source_count == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(sources, source_count) }
+        };
+        let files = sources
+            .iter()
+            .map(|source| {
+                Ok(File {
+                    file_name: unsafe { read_str(source.file_name, "file_name")? }.to_owned(),
+                    source: unsafe { read_str(source.source, "source")? }.to_owned(),
+                })
+            })
+            .collect::<crate::error::Result<Vec<_>>>()?;
+
+        let mut compiler = Compiler::new();
+        compiler.add_files(files);
+        if let Some(dialogue) = unsafe { dialogue.as_ref() } {
+            compiler.extend_library(dialogue.dialogue.library().clone());
+        }
+        let compilation = compiler
+            .compile()
+            .map_err(|e| FfiError::new(YsStatus::CompilationFailed, e))?;
+
+        let mut lines: Vec<_> = compilation
+            .string_table
+            .iter()
+            .map(|(id, info)| (id.0.as_str(), info.text.as_str()))
+            .collect();
+        lines.sort_unstable();
+        let lines = lines
+            .into_iter()
+            .map(|(id, text)| (c_string(id), c_string(text)))
+            .collect();
+
+        let compilation = Box::new(YsCompilation { compilation, lines });
+        unsafe { *out_compilation = Box::into_raw(compilation) };
+        Ok(())
+    })
+}
+
+/// Releases a compilation created by [`ys_compile`]. Does nothing if `compilation` is `NULL`.
+///
+/// # Safety
+/// `compilation` must be `NULL` or a compilation that was not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_compilation_free(compilation: *mut YsCompilation) {
+    if !compilation.is_null() {
+        drop(unsafe { Box::from_raw(compilation) });
+    }
+}
+
+/// Serializes the compiled program into `*out_program`, e.g. to ship it with a game and load it later through
+/// [`ys_dialogue_load_program`](crate::ys_dialogue_load_program). Release the buffer with [`ys_buffer_free`].
+///
+/// # Safety
+/// `compilation` must be a live compilation and `out_program` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_compilation_program(
+    compilation: *const YsCompilation,
+    out_program: *mut YsBuffer,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let compilation =
+            unsafe { compilation.as_ref() }.ok_or(FfiError::null_argument("compilation"))?;
+        if out_program.is_null() {
+            return Err(FfiError::null_argument("out_program"));
+        }
+        let program = compilation.compilation.program.as_ref().ok_or_else(|| {
+            FfiError::new(
+                YsStatus::InvalidArgument,
+                "The compilation does not contain a program",
+            )
+        })?;
+        let data = program.encode_to_vec().into_boxed_slice();
+        let length = data.len();
+        let data = Box::into_raw(data).cast::<u8>();
+        unsafe { *out_program = YsBuffer { data, length } };
+        Ok(())
+    })
+}
+
+/// Releases a buffer filled by the library and resets it to an empty buffer.
+///
+/// # Safety
+/// `buffer` must be `NULL` or point to a buffer that was filled by the library and not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_buffer_free(buffer: *mut YsBuffer) {
+    let Some(buffer) = (unsafe { buffer.as_mut() }) else {
+        return;
+    };
+    if !buffer.data.is_null() {
+        let data = ptr::slice_from_raw_parts_mut(buffer.data, buffer.length);
+        drop(unsafe { Box::from_raw(data) });
+    }
+    buffer.data = ptr::null_mut();
+    buffer.length = 0;
+}
+
+/// Returns the number of lines in the string table of the compilation, or 0 if `compilation` is `NULL`.
+///
+/// # Safety
+/// `compilation` must be `NULL` or a live compilation.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_compilation_line_count(compilation: *const YsCompilation) -> usize {
+    unsafe { compilation.as_ref() }.map_or(0, |compilation| compilation.lines.len())
+}
+
+/// Gets the ID and base language text of the line at `index` in the string table of the compilation.
+/// Lines are sorted by their ID. The strings are borrowed from the compilation and stay valid until it is released.
+///
+/// # Safety
+/// `compilation` must be a live compilation and `out_id` and `out_text` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_compilation_line(
+    compilation: *const YsCompilation,
+    index: usize,
+    out_id: *mut *const c_char,
+    out_text: *mut *const c_char,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let compilation =
+            unsafe { compilation.as_ref() }.ok_or(FfiError::null_argument("compilation"))?;
+        if out_id.is_null() {
+            return Err(FfiError::null_argument("out_id"));
+        }
+        if out_text.is_null() {
+            return Err(FfiError::null_argument("out_text"));
+        }
+        let (id, text) = compilation.lines.get(index).ok_or_else(|| {
+            FfiError::new(
+                YsStatus::InvalidArgument,
+                format!(
+                    "Line index {index} is out of range, the compilation has {} lines",
+                    compilation.lines.len()
+                ),
+            )
+        })?;
+        unsafe {
+            *out_id = id.as_ptr();
+            *out_text = text.as_ptr();
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,265 @@
+use crate::callbacks::CommandCallback;
+use crate::compilation::YsCompilation;
+use crate::error::{FfiError, YsStatus, ffi_boundary};
+use crate::events::EventStorage;
+use crate::value::{YsValue, read_str};
+use prost::Message;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{CString, c_char};
+use std::slice;
+use yarnspinner::core::{LineId, Program};
+use yarnspinner::runtime::{
+    Dialogue, DialogueEvent, MemoryVariableStorage, OptionId, StringTableTextProvider,
+};
+
+/// A running dialogue, created by [`ys_dialogue_new`] and released by [`ys_dialogue_free`].
+///
+/// Uses an in-memory variable storage and the base language texts added through [`ys_dialogue_load_compilation`] or [`ys_dialogue_add_line`].
+#[derive(Debug)]
+pub struct YsDialogue {
+    pub(crate) dialogue: Dialogue,
+    pub(crate) commands: HashMap<String, CommandCallback>,
+    pub(crate) pending_events: VecDeque<DialogueEvent>,
+    pub(crate) is_running_command: bool,
+    pub(crate) is_complete: bool,
+    pub(crate) event_storage: EventStorage,
+    variable_strings: Vec<CString>,
+}
+
+impl YsDialogue {
+    fn reset(&mut self) {
+        self.pending_events.clear();
+        self.is_running_command = false;
+        self.is_complete = false;
+    }
+
+    fn string_table_mut(&mut self) -> &mut StringTableTextProvider {
+        self.dialogue
+            .text_provider_mut()
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+}
+
+/// Creates a new dialogue without a program. Release it with [`ys_dialogue_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn ys_dialogue_new() -> *mut YsDialogue {
+    let dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(StringTableTextProvider::new()),
+    );
+    Box::into_raw(Box::new(YsDialogue {
+        dialogue,
+        commands: HashMap::new(),
+        pending_events: VecDeque::new(),
+        is_running_command: false,
+        is_complete: false,
+        event_storage: EventStorage::default(),
+        variable_strings: Vec::new(),
+    }))
+}
+
+/// Releases a dialogue created by [`ys_dialogue_new`]. Does nothing if `dialogue` is `NULL`.
+///
+/// # Safety
+/// `dialogue` must be `NULL` or a dialogue that was not released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_free(dialogue: *mut YsDialogue) {
+    if !dialogue.is_null() {
+        drop(unsafe { Box::from_raw(dialogue) });
+    }
+}
+
+/// Replaces the program of the dialogue by the one in the compilation and adds the compiled lines to its base language texts.
+/// Stops the current node and initializes the variables declared by the program.
+///
+/// # Safety
+/// `dialogue` and `compilation` must be live objects.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_load_compilation(
+    dialogue: *mut YsDialogue,
+    compilation: *const YsCompilation,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let compilation = &unsafe { compilation.as_ref() }
+            .ok_or(FfiError::null_argument("compilation"))?
+            .compilation;
+        let program = compilation.program.clone().ok_or_else(|| {
+            FfiError::new(
+                YsStatus::InvalidArgument,
+                "The compilation does not contain a program",
+            )
+        })?;
+        dialogue.dialogue.replace_program(program);
+        dialogue.reset();
+        let lines = compilation
+            .string_table
+            .iter()
+            .map(|(id, info)| (id.clone(), info.text.clone()));
+        dialogue.string_table_mut().extend_base_language(lines);
+        Ok(())
+    })
+}
+
+/// Replaces the program of the dialogue by one serialized with [`ys_compilation_program`](crate::ys_compilation_program).
+/// Stops the current node and initializes the variables declared by the program.
+/// The texts of the lines are not part of the program, add them with [`ys_dialogue_add_line`].
+///
+/// # Safety
+/// `dialogue` must be a live dialogue and `data` must point to `length` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_load_program(
+    dialogue: *mut YsDialogue,
+    data: *const u8,
+    length: usize,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        if data.is_null() && length > 0 {
+            return Err(FfiError::null_argument("data"));
+        }
+        let data = if length == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(data, length) }
+        };
+        let program =
+            Program::decode(data).map_err(|e| FfiError::new(YsStatus::InvalidProgram, e))?;
+        dialogue.dialogue.replace_program(program);
+        dialogue.reset();
+        Ok(())
+    })
+}
+
+/// Adds or replaces the base language text of the line with the given ID.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue and `id` and `text` valid strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_add_line(
+    dialogue: *mut YsDialogue,
+    id: *const c_char,
+    text: *const c_char,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let id = LineId::from(unsafe { read_str(id, "id")? });
+        let text = unsafe { read_str(text, "text")? }.to_owned();
+        dialogue
+            .string_table_mut()
+            .extend_base_language([(id, text)]);
+        Ok(())
+    })
+}
+
+/// Starts the node with the given name. Any events of the previous node that were not returned yet are dropped.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue and `node_name` a valid string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_set_node(
+    dialogue: *mut YsDialogue,
+    node_name: *const c_char,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let node_name = unsafe { read_str(node_name, "node_name")? };
+        dialogue
+            .dialogue
+            .set_node(node_name)
+            .map_err(|e| FfiError::new(YsStatus::DialogueError, e))?;
+        dialogue.reset();
+        Ok(())
+    })
+}
+
+/// Stops the dialogue. The next calls to [`ys_dialogue_next_event`](crate::ys_dialogue_next_event) return the remaining events,
+/// ending with `YS_EVENT_TYPE_DIALOGUE_COMPLETE`.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_stop(dialogue: *mut YsDialogue) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let events = dialogue.dialogue.stop();
+        dialogue.is_running_command = false;
+        dialogue.is_complete = false;
+        dialogue.pending_events.extend(events);
+        Ok(())
+    })
+}
+
+/// Selects the option with the given ID after `YS_EVENT_TYPE_OPTIONS` was returned.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_select_option(
+    dialogue: *mut YsDialogue,
+    option_id: usize,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        dialogue
+            .dialogue
+            .set_selected_option(OptionId(option_id))
+            .map_err(|e| FfiError::new(YsStatus::InvalidArgument, e))?;
+        Ok(())
+    })
+}
+
+/// Sets the variable with the given name, including its leading `$`.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue, `name` a valid string and `value` must point to a valid [`YsValue`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_set_variable(
+    dialogue: *mut YsDialogue,
+    name: *const c_char,
+    value: *const YsValue,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let name = unsafe { read_str(name, "name")? }.to_owned();
+        let value = unsafe { value.as_ref() }.ok_or(FfiError::null_argument("value"))?;
+        let value = unsafe { value.to_yarn_value()? };
+        dialogue
+            .dialogue
+            .variable_storage_mut()
+            .set(name, value)
+            .map_err(|e| FfiError::new(YsStatus::VariableError, e))?;
+        Ok(())
+    })
+}
+
+/// Gets the variable with the given name, including its leading `$`.
+/// A string value is borrowed from the dialogue and stays valid until the next call to this function or until the dialogue is released.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue, `name` a valid string and `out_value` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_get_variable(
+    dialogue: *mut YsDialogue,
+    name: *const c_char,
+    out_value: *mut YsValue,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        let name = unsafe { read_str(name, "name")? };
+        if out_value.is_null() {
+            return Err(FfiError::null_argument("out_value"));
+        }
+        let value = dialogue
+            .dialogue
+            .variable_storage()
+            .get(name)
+            .map_err(|e| FfiError::new(YsStatus::VariableError, e))?;
+        dialogue.variable_strings.clear();
+        let value = YsValue::from_yarn_value(&value, &mut dialogue.variable_strings);
+        unsafe { *out_value = value };
+        Ok(())
+    })
+}
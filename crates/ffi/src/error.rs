@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::fmt::Display;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// The result of a call into the C API. On anything but [`YsStatus::Ok`], [`ys_last_error_message`] describes what went wrong.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YsStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was `NULL`.
+    NullArgument,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8,
+    /// An argument had an invalid value, e.g. an unknown option ID or too many function parameters.
+    InvalidArgument,
+    /// The Yarn sources could not be compiled.
+    CompilationFailed,
+    /// A program blob could not be decoded.
+    InvalidProgram,
+    /// The dialogue could not continue, e.g. because no node was set.
+    DialogueError,
+    /// A variable could not be read or written.
+    VariableError,
+    /// The Rust side panicked. The objects involved must still be freed, but should not be used otherwise.
+    Panic,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Returns a description of the last error that occurred on the calling thread, or `NULL` if no call failed yet.
+///
+/// The string is owned by the library and stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn ys_last_error_message() -> *const c_char {
+    LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |error| error.as_ptr()))
+}
+
+/// An error on the way through the C API, converted into a [`YsStatus`] and a message at the boundary.
+#[derive(Debug)]
+pub(crate) struct FfiError {
+    pub(crate) status: YsStatus,
+    pub(crate) message: String,
+}
+
+impl FfiError {
+    pub(crate) fn new(status: YsStatus, message: impl Display) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn null_argument(name: &str) -> Self {
+        Self::new(YsStatus::NullArgument, format!("`{name}` must not be NULL"))
+    }
+}
+
+pub(crate) type Result<T> = std::result::Result<T, FfiError>;
+
+/// Runs the body of an exported function, recording its error and catching panics so that they don't unwind into C.
+pub(crate) fn ffi_boundary(body: impl FnOnce() -> Result<()>) -> YsStatus {
+    let error = match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => return YsStatus::Ok,
+        Ok(Err(error)) => error,
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic".to_owned());
+            FfiError::new(YsStatus::Panic, message)
+        }
+    };
+    LAST_ERROR.set(Some(c_string(&error.message)));
+    error.status
+}
+
+/// Converts a Rust string for the C side. C strings can't contain NUL bytes, so they are dropped.
+pub(crate) fn c_string(string: &str) -> CString {
+    CString::new(string.replace('\0', "")).unwrap()
+}
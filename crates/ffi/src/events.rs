@@ -0,0 +1,341 @@
+use crate::dialogue::YsDialogue;
+use crate::error::{FfiError, Result, YsStatus, c_string, ffi_boundary};
+use crate::value::YsValue;
+use std::ffi::{CString, c_char};
+use std::ptr;
+use yarnspinner::runtime::{Command, DialogueEvent, DialogueOption, Line};
+
+/// The kind of a [`YsEvent`], which determines its meaningful fields.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YsEventType {
+    /// There is nothing to do until the caller acts: an option must be selected, a running command must be completed,
+    /// or the dialogue was completed and a new node must be set.
+    None,
+    /// A line should be presented. See [`YsEvent::line`].
+    Line,
+    /// Options should be presented and one of them selected through [`ys_dialogue_select_option`](crate::ys_dialogue_select_option).
+    /// See [`YsEvent::options`].
+    Options,
+    /// A command without a registered handler should be run. See [`YsEvent::command`].
+    Command,
+    /// A node was entered. See [`YsEvent::node_name`].
+    NodeStart,
+    /// A node was completed. See [`YsEvent::node_name`].
+    NodeComplete,
+    /// The dialogue was completed. Set a new node through [`ys_dialogue_set_node`](crate::ys_dialogue_set_node) to continue.
+    DialogueComplete,
+}
+
+/// A property of a [`YsAttribute`], e.g. `name` in `[character name="Alice"]`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsProperty {
+    /// The name of the property.
+    pub name: *const c_char,
+    /// The value of the property. Integers and floats are both numbers.
+    pub value: YsValue,
+}
+
+/// A markup attribute of a [`YsLine`], e.g. `[wave]Hello[/wave]`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsAttribute {
+    /// The name of the attribute.
+    pub name: *const c_char,
+    /// The position in the line's text at which the attribute starts, counted in grapheme clusters.
+    pub position: usize,
+    /// The number of grapheme clusters the attribute spans.
+    pub length: usize,
+    /// The properties of the attribute, sorted by name. `NULL` if there are none.
+    pub properties: *const YsProperty,
+    /// The number of entries in `properties`.
+    pub property_count: usize,
+}
+
+/// A line of dialogue with its markup already parsed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsLine {
+    /// The ID of the line.
+    pub id: *const c_char,
+    /// The text of the line, without markup.
+    pub text: *const c_char,
+    /// The markup attributes of the line. `NULL` if there are none.
+    pub attributes: *const YsAttribute,
+    /// The number of entries in `attributes`.
+    pub attribute_count: usize,
+}
+
+/// An option that can be selected by the user.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsOption {
+    /// The ID to pass to [`ys_dialogue_select_option`](crate::ys_dialogue_select_option) when this option is selected.
+    pub id: usize,
+    /// The line presented for this option.
+    pub line: YsLine,
+    /// The name of the node that is run when this option is selected.
+    pub destination_node: *const c_char,
+    /// Whether the option's condition passed. Unavailable options should usually be shown as disabled.
+    pub is_available: bool,
+}
+
+/// A command, e.g. `<<wait 2>>`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsCommand {
+    /// The name of the command, e.g. `wait`.
+    pub name: *const c_char,
+    /// The parameters of the command, e.g. `2`. Parameters are split at whitespace, except inside quotes,
+    /// and passed as strings without their surrounding quotes. `NULL` if there are none.
+    pub parameters: *const YsValue,
+    /// The number of entries in `parameters`.
+    pub parameter_count: usize,
+    /// The command as written in the Yarn file, without the `<<` and `>>`.
+    pub raw: *const c_char,
+}
+
+/// An event returned by [`ys_dialogue_next_event`]. Only the fields belonging to its `type` are meaningful, all other pointers are `NULL`.
+///
+/// All pointers are borrowed from the dialogue and stay valid until the next call to [`ys_dialogue_next_event`] or until the dialogue is released.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsEvent {
+    /// The kind of the event.
+    pub r#type: YsEventType,
+    /// The line for [`YsEventType::Line`].
+    pub line: YsLine,
+    /// The options for [`YsEventType::Options`].
+    pub options: *const YsOption,
+    /// The number of entries in `options`.
+    pub option_count: usize,
+    /// The command for [`YsEventType::Command`].
+    pub command: YsCommand,
+    /// The name of the node for [`YsEventType::NodeStart`] and [`YsEventType::NodeComplete`].
+    pub node_name: *const c_char,
+}
+
+impl YsEvent {
+    fn new(r#type: YsEventType) -> Self {
+        Self {
+            r#type,
+            line: YsLine {
+                id: ptr::null(),
+                text: ptr::null(),
+                attributes: ptr::null(),
+                attribute_count: 0,
+            },
+            options: ptr::null(),
+            option_count: 0,
+            command: YsCommand {
+                name: ptr::null(),
+                parameters: ptr::null(),
+                parameter_count: 0,
+                raw: ptr::null(),
+            },
+            node_name: ptr::null(),
+        }
+    }
+}
+
+/// Owns the data that the pointers of the last [`YsEvent`] refer to.
+#[derive(Debug, Default)]
+pub(crate) struct EventStorage {
+    strings: Vec<CString>,
+    properties: Vec<YsProperty>,
+    attributes: Vec<YsAttribute>,
+    options: Vec<YsOption>,
+    parameters: Vec<YsValue>,
+}
+
+impl EventStorage {
+    /// Drops the data of the last event and makes room for the markup of the given lines.
+    ///
+    /// Attributes and properties point into `self`, so their vectors must not reallocate while an event is built.
+    fn prepare<'a>(&mut self, lines: impl IntoIterator<Item = &'a Line>) {
+        self.strings.clear();
+        self.properties.clear();
+        self.attributes.clear();
+        self.options.clear();
+        self.parameters.clear();
+
+        let (attribute_count, property_count) = lines
+            .into_iter()
+            .flat_map(|line| &line.attributes)
+            .fold((0, 0), |(attributes, properties), attribute| {
+                (attributes + 1, properties + attribute.properties.len())
+            });
+        self.attributes.reserve_exact(attribute_count);
+        self.properties.reserve_exact(property_count);
+    }
+
+    fn string(&mut self, string: &str) -> *const c_char {
+        let string = c_string(string);
+        let pointer = string.as_ptr();
+        self.strings.push(string);
+        pointer
+    }
+
+    fn line(&mut self, line: &Line) -> YsLine {
+        let attribute_start = self.attributes.len();
+        for attribute in &line.attributes {
+            let property_start = self.properties.len();
+            let mut properties: Vec<_> = attribute.properties.iter().collect();
+            properties.sort_unstable_by_key(|(name, _)| *name);
+            for (name, value) in properties {
+                let property = YsProperty {
+                    name: self.string(name),
+                    value: YsValue::from_markup_value(value, &mut self.strings),
+                };
+                self.properties.push(property);
+            }
+            let attribute = YsAttribute {
+                name: self.string(&attribute.name),
+                position: attribute.position,
+                length: attribute.length,
+                properties: slice_start(&self.properties, property_start),
+                property_count: attribute.properties.len(),
+            };
+            self.attributes.push(attribute);
+        }
+        YsLine {
+            id: self.string(&line.id.0),
+            text: self.string(&line.text),
+            attributes: slice_start(&self.attributes, attribute_start),
+            attribute_count: line.attributes.len(),
+        }
+    }
+
+    fn options(&mut self, options: &[DialogueOption]) -> *const YsOption {
+        for option in options {
+            let option = YsOption {
+                id: option.id.0,
+                line: self.line(&option.line),
+                destination_node: self.string(&option.destination_node),
+                is_available: option.is_available,
+            };
+            self.options.push(option);
+        }
+        slice_start(&self.options, 0)
+    }
+
+    pub(crate) fn command(&mut self, command: &Command) -> YsCommand {
+        self.prepare([]);
+        for parameter in &command.parameters {
+            let parameter = YsValue::from_yarn_value(parameter, &mut self.strings);
+            self.parameters.push(parameter);
+        }
+        YsCommand {
+            name: self.string(&command.name),
+            parameters: slice_start(&self.parameters, 0),
+            parameter_count: command.parameters.len(),
+            raw: self.string(&command.raw),
+        }
+    }
+}
+
+/// Points to `items[start..]`, or is `NULL` if that is empty.
+fn slice_start<T>(items: &[T], start: usize) -> *const T {
+    if start < items.len() {
+        items[start..].as_ptr()
+    } else {
+        ptr::null()
+    }
+}
+
+impl YsDialogue {
+    fn next_event(&mut self) -> Result<YsEvent> {
+        loop {
+            if self.is_running_command {
+                return Ok(YsEvent::new(YsEventType::None));
+            }
+            let Some(event) = self.pending_events.pop_front() else {
+                if self.is_complete || self.dialogue.is_waiting_for_option_selection() {
+                    return Ok(YsEvent::new(YsEventType::None));
+                }
+                let events = self
+                    .dialogue
+                    .continue_()
+                    .map_err(|e| FfiError::new(YsStatus::DialogueError, e))?;
+                self.pending_events.extend(events);
+                continue;
+            };
+
+            let storage = &mut self.event_storage;
+            let ys_event = match event {
+                DialogueEvent::Line(line) => {
+                    storage.prepare([&line]);
+                    YsEvent {
+                        line: storage.line(&line),
+                        ..YsEvent::new(YsEventType::Line)
+                    }
+                }
+                DialogueEvent::Options(options) => {
+                    storage.prepare(options.iter().map(|option| &option.line));
+                    YsEvent {
+                        options: storage.options(&options),
+                        option_count: options.len(),
+                        ..YsEvent::new(YsEventType::Options)
+                    }
+                }
+                DialogueEvent::Command(command) => {
+                    let ys_command = storage.command(&command);
+                    if let Some(callback) = self.commands.get(&command.name) {
+                        self.is_running_command = callback.call(&command.name, &ys_command)?;
+                        continue;
+                    }
+                    YsEvent {
+                        command: ys_command,
+                        ..YsEvent::new(YsEventType::Command)
+                    }
+                }
+                DialogueEvent::NodeStart(node_name) => {
+                    storage.prepare([]);
+                    YsEvent {
+                        node_name: storage.string(&node_name),
+                        ..YsEvent::new(YsEventType::NodeStart)
+                    }
+                }
+                DialogueEvent::NodeComplete(node_name) => {
+                    storage.prepare([]);
+                    YsEvent {
+                        node_name: storage.string(&node_name),
+                        ..YsEvent::new(YsEventType::NodeComplete)
+                    }
+                }
+                DialogueEvent::DialogueComplete => {
+                    self.is_complete = true;
+                    YsEvent::new(YsEventType::DialogueComplete)
+                }
+                // Line hints, rollbacks, variable changes and events added in the future are not part of the C API
+                _ => continue,
+            };
+            return Ok(ys_event);
+        }
+    }
+}
+
+/// Advances the dialogue and writes the next event to `*out_event`.
+///
+/// Returns `YS_EVENT_TYPE_NONE` while the dialogue waits for the caller, see [`YsEventType::None`].
+/// Registered command handlers and Yarn functions are called from within this function.
+///
+/// # Safety
+/// `dialogue` must be a live dialogue and `out_event` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ys_dialogue_next_event(
+    dialogue: *mut YsDialogue,
+    out_event: *mut YsEvent,
+) -> YsStatus {
+    ffi_boundary(|| {
+        let dialogue = unsafe { dialogue.as_mut() }.ok_or(FfiError::null_argument("dialogue"))?;
+        if out_event.is_null() {
+            return Err(FfiError::null_argument("out_event"));
+        }
+        unsafe { *out_event = YsEvent::new(YsEventType::None) };
+        let event = dialogue.next_event()?;
+        unsafe { *out_event = event };
+        Ok(())
+    })
+}
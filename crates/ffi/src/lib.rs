@@ -0,0 +1,38 @@
+//! # C bindings for Yarn Spinner for Rust
+//! Exposes the compiler and runtime through a stable C API, for use from engines written in C or C++.
+//! The header is checked in at `include/yarnspinner.h`, regenerate it with `UPDATE_HEADER=1 cargo test -p yarnspinner_ffi --test header`.
+//!
+//! A typical session looks like this:
+//! 1. Compile the Yarn sources with `ys_compile`, or load a program blob compiled earlier through `ys_compilation_program`.
+//! 2. Create a dialogue with `ys_dialogue_new`, register functions and commands with `ys_dialogue_add_function` and `ys_dialogue_add_command`,
+//!    and pass it the compilation with `ys_dialogue_load_compilation`.
+//! 3. Select a node with `ys_dialogue_set_node`, then call `ys_dialogue_next_event` until it returns `YS_EVENT_TYPE_DIALOGUE_COMPLETE`,
+//!    answering options with `ys_dialogue_select_option` along the way.
+//!
+//! ## Ownership
+//!
+//! - Every function returning a `YsStatus` reports failures through it. `ys_last_error_message` describes the last failure on the calling thread.
+//! - Objects created by `ys_*_new` or `ys_compile` are owned by the caller and must be released by the matching `ys_*_free` function.
+//!   Passing `NULL` to a `ys_*_free` function is allowed and does nothing.
+//! - `YsBuffer`s filled by the library are owned by the caller and must be released with `ys_buffer_free`.
+//! - All other pointers handed out by the library, e.g. the strings inside a `YsEvent`, are borrowed from the object they were obtained from.
+//!   Each function documents how long they stay valid. Copy them if you need them for longer.
+//! - Strings and values passed into the library are copied, so the caller can release them as soon as the call returns.
+//! - All strings are NUL-terminated UTF-8.
+//! - Objects are not thread-safe. Use each one from a single thread at a time.
+
+mod callbacks;
+mod compilation;
+mod dialogue;
+mod error;
+mod events;
+mod value;
+
+pub use callbacks::*;
+pub use compilation::*;
+pub use dialogue::*;
+pub use error::{YsStatus, ys_last_error_message};
+pub use events::*;
+pub use value::{
+    YS_VALUE_TYPE_BOOLEAN, YS_VALUE_TYPE_NUMBER, YS_VALUE_TYPE_STRING, YsValue, YsValueType,
+};
@@ -0,0 +1,139 @@
+use crate::error::{FfiError, Result, YsStatus, c_string};
+use std::ffi::{CStr, CString, c_char};
+use yarnspinner::core::YarnValue;
+use yarnspinner::runtime::MarkupValue;
+
+/// The type of a [`YsValue`], one of the `YS_VALUE_TYPE_*` constants.
+///
+/// This is an integer rather than an enum because C may pass any value, which would be undefined behavior for a Rust enum.
+pub type YsValueType = u32;
+
+/// The value is stored in [`YsValue::number`].
+pub const YS_VALUE_TYPE_NUMBER: YsValueType = 0;
+/// The value is stored in [`YsValue::string`].
+pub const YS_VALUE_TYPE_STRING: YsValueType = 1;
+/// The value is stored in [`YsValue::boolean`].
+pub const YS_VALUE_TYPE_BOOLEAN: YsValueType = 2;
+
+/// A [`YsValueType`] that is known to be valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValueType {
+    Number,
+    String,
+    Boolean,
+}
+
+impl ValueType {
+    pub(crate) fn from_raw(value_type: YsValueType, name: &str) -> Result<Self> {
+        match value_type {
+            YS_VALUE_TYPE_NUMBER => Ok(Self::Number),
+            YS_VALUE_TYPE_STRING => Ok(Self::String),
+            YS_VALUE_TYPE_BOOLEAN => Ok(Self::Boolean),
+            _ => Err(FfiError::new(
+                YsStatus::InvalidArgument,
+                format!("`{name}` is not a valid value type: {value_type}"),
+            )),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> YsValueType {
+        match self {
+            Self::Number => YS_VALUE_TYPE_NUMBER,
+            Self::String => YS_VALUE_TYPE_STRING,
+            Self::Boolean => YS_VALUE_TYPE_BOOLEAN,
+        }
+    }
+}
+
+/// A value passed between Yarn and C. Only the field selected by `type` is meaningful.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct YsValue {
+    /// Which of the other fields holds the value.
+    pub r#type: YsValueType,
+    /// The value if `type` is [`YS_VALUE_TYPE_NUMBER`].
+    pub number: f32,
+    /// The value if `type` is [`YS_VALUE_TYPE_BOOLEAN`].
+    pub boolean: bool,
+    /// The NUL-terminated UTF-8 value if `type` is [`YS_VALUE_TYPE_STRING`].
+    pub string: *const c_char,
+}
+
+impl YsValue {
+    /// Copies the value into a [`YarnValue`]. Fails if `type` is not a valid [`YsValueType`].
+    ///
+    /// # Safety
+    /// If the value is a string, `string` must be `NULL` or point to a NUL-terminated string.
+    pub(crate) unsafe fn to_yarn_value(self) -> Result<YarnValue> {
+        Ok(match ValueType::from_raw(self.r#type, "type")? {
+            ValueType::Number => YarnValue::Number(self.number),
+            ValueType::Boolean => YarnValue::Boolean(self.boolean),
+            ValueType::String => {
+                YarnValue::String(unsafe { read_str(self.string, "string")? }.to_owned())
+            }
+        })
+    }
+
+    /// Creates a value borrowing its string from `strings`, which must outlive the returned value.
+    pub(crate) fn from_yarn_value(value: &YarnValue, strings: &mut Vec<CString>) -> Self {
+        match value {
+            YarnValue::Number(number) => Self::number(*number),
+            YarnValue::Boolean(boolean) => Self::boolean(*boolean),
+            YarnValue::String(string) => Self::string(string, strings),
+        }
+    }
+
+    /// Creates a value borrowing its string from `strings`, which must outlive the returned value.
+    pub(crate) fn from_markup_value(value: &MarkupValue, strings: &mut Vec<CString>) -> Self {
+        match value {
+            MarkupValue::Integer(integer) => Self::number(*integer as f32),
+            MarkupValue::Float(float) => Self::number(*float),
+            MarkupValue::Bool(boolean) => Self::boolean(*boolean),
+            MarkupValue::String(string) => Self::string(string, strings),
+        }
+    }
+
+    fn number(number: f32) -> Self {
+        Self {
+            r#type: YS_VALUE_TYPE_NUMBER,
+            number,
+            boolean: false,
+            string: std::ptr::null(),
+        }
+    }
+
+    fn boolean(boolean: bool) -> Self {
+        Self {
+            r#type: YS_VALUE_TYPE_BOOLEAN,
+            number: 0.0,
+            boolean,
+            string: std::ptr::null(),
+        }
+    }
+
+    fn string(string: &str, strings: &mut Vec<CString>) -> Self {
+        // Moving a `CString` doesn't move its heap allocation, so the pointer stays valid
+        let string = c_string(string);
+        let pointer = string.as_ptr();
+        strings.push(string);
+        Self {
+            r#type: YS_VALUE_TYPE_STRING,
+            number: 0.0,
+            boolean: false,
+            string: pointer,
+        }
+    }
+}
+
+/// Reads a NUL-terminated UTF-8 string passed from C.
+///
+/// # Safety
+/// `pointer` must be `NULL` or point to a NUL-terminated string that outlives the returned reference.
+pub(crate) unsafe fn read_str<'a>(pointer: *const c_char, name: &str) -> Result<&'a str> {
+    if pointer.is_null() {
+        return Err(FfiError::null_argument(name));
+    }
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .map_err(|e| FfiError::new(YsStatus::InvalidUtf8, format!("`{name}` is invalid: {e}")))
+}
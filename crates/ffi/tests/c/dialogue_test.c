@@ -0,0 +1,282 @@
+/* Drives a dialogue through the C API. Built and run by `tests/c_api.rs`. */
+
+#include "yarnspinner.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define CHECK(condition)                                                          \
+    do {                                                                          \
+        if (!(condition)) {                                                       \
+            const char *error = ys_last_error_message();                          \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,     \
+                    #condition);                                                  \
+            if (error != NULL) {                                                  \
+                fprintf(stderr, "last error: %s\n", error);                       \
+            }                                                                     \
+            exit(1);                                                              \
+        }                                                                         \
+    } while (0)
+
+#define CHECK_OK(call) CHECK((call) == YS_STATUS_OK)
+
+static const char *SOURCE =
+    "title: Start\n"
+    "---\n"
+    "<<declare $gold = 5>>\n"
+    "Narrator: Hello, [wave size=2]traveller[/wave]! #line:greeting\n"
+    "<<set $gold to double($gold)>>\n"
+    "You have {$gold} gold. #line:gold\n"
+    "-> Buy a sword <<if $gold >= 10>> #line:buy\n"
+    "    <<play_sound \"sword swing\">>\n"
+    "    <<jump Shop>>\n"
+    "-> Leave #line:leave\n"
+    "===\n"
+    "title: Shop\n"
+    "---\n"
+    "{greet(\"Alice\")} #line:shop\n"
+    "<<fade 2 true>>\n"
+    "===\n";
+
+typedef struct Recorder {
+    int double_calls;
+    int sound_calls;
+    char sound[64];
+} Recorder;
+
+static void double_number(void *user_data, const YsValue *arguments, size_t argument_count,
+                          YsValue *out_result) {
+    Recorder *recorder = user_data;
+    recorder->double_calls++;
+    CHECK(argument_count == 1);
+    CHECK(arguments[0].type == YS_VALUE_TYPE_NUMBER);
+    out_result->type = YS_VALUE_TYPE_NUMBER;
+    out_result->number = arguments[0].number * 2;
+}
+
+static void greet(void *user_data, const YsValue *arguments, size_t argument_count,
+                  YsValue *out_result) {
+    static char greeting[64];
+    (void)user_data;
+    CHECK(argument_count == 1);
+    CHECK(arguments[0].type == YS_VALUE_TYPE_STRING);
+    snprintf(greeting, sizeof greeting, "Hello, %s", arguments[0].string);
+    out_result->type = YS_VALUE_TYPE_STRING;
+    out_result->string = greeting;
+}
+
+static YsCommandResult play_sound(void *user_data, const YsCommand *command) {
+    Recorder *recorder = user_data;
+    recorder->sound_calls++;
+    CHECK(strcmp(command->name, "play_sound") == 0);
+    CHECK(command->parameter_count == 1);
+    CHECK(command->parameters[0].type == YS_VALUE_TYPE_STRING);
+    snprintf(recorder->sound, sizeof recorder->sound, "%s", command->parameters[0].string);
+    return YS_COMMAND_RESULT_RUNNING;
+}
+
+/* Returns the next event that is not a node event, checking that `expected_type` comes next. */
+static YsEvent expect_event(YsDialogue *dialogue, YsEventType expected_type) {
+    YsEvent event;
+    do {
+        CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    } while (event.type == YS_EVENT_TYPE_NODE_START || event.type == YS_EVENT_TYPE_NODE_COMPLETE);
+    CHECK(event.type == expected_type);
+    return event;
+}
+
+static const YsAttribute *find_attribute(const YsLine *line, const char *name) {
+    for (size_t i = 0; i < line->attribute_count; i++) {
+        if (strcmp(line->attributes[i].name, name) == 0) {
+            return &line->attributes[i];
+        }
+    }
+    return NULL;
+}
+
+static void test_compilation_errors_are_reported(void) {
+    YsSource source = {"broken.yarn", "title: Start\n---\n<<set $undeclared to>>\n===\n"};
+    YsCompilation *compilation = NULL;
+    CHECK(ys_compile(&source, 1, NULL, &compilation) == YS_STATUS_COMPILATION_FAILED);
+    CHECK(compilation == NULL);
+    CHECK(ys_last_error_message() != NULL);
+    CHECK(ys_last_error_message()[0] != '\0');
+}
+
+static void test_dialogue_runs_through_callbacks_and_events(void) {
+    Recorder recorder = {0};
+    YsDialogue *dialogue = ys_dialogue_new();
+    CHECK(dialogue != NULL);
+    CHECK_OK(ys_dialogue_add_function(dialogue, "double", YS_VALUE_TYPE_NUMBER, 1, double_number,
+                                      &recorder));
+    CHECK_OK(ys_dialogue_add_function(dialogue, "greet", YS_VALUE_TYPE_STRING, 1, greet, NULL));
+    CHECK_OK(ys_dialogue_add_command(dialogue, "play_sound", play_sound, &recorder));
+
+    YsSource source = {"test.yarn", SOURCE};
+    YsCompilation *compilation = NULL;
+    CHECK_OK(ys_compile(&source, 1, dialogue, &compilation));
+    CHECK(ys_compilation_line_count(compilation) == 5);
+    const char *id = NULL;
+    const char *text = NULL;
+    CHECK_OK(ys_compilation_line(compilation, 0, &id, &text));
+    CHECK(strcmp(id, "line:buy") == 0);
+    CHECK(strcmp(text, "Buy a sword") == 0);
+    CHECK(ys_compilation_line(compilation, 5, &id, &text) == YS_STATUS_INVALID_ARGUMENT);
+
+    CHECK_OK(ys_dialogue_load_compilation(dialogue, compilation));
+    ys_compilation_free(compilation);
+
+    YsEvent event;
+    CHECK(ys_dialogue_next_event(dialogue, &event) == YS_STATUS_DIALOGUE_ERROR);
+    CHECK(ys_dialogue_set_node(dialogue, "Missing") == YS_STATUS_DIALOGUE_ERROR);
+    CHECK_OK(ys_dialogue_set_node(dialogue, "Start"));
+
+    CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    CHECK(event.type == YS_EVENT_TYPE_NODE_START);
+    CHECK(strcmp(event.node_name, "Start") == 0);
+
+    event = expect_event(dialogue, YS_EVENT_TYPE_LINE);
+    CHECK(strcmp(event.line.id, "line:greeting") == 0);
+    CHECK(strcmp(event.line.text, "Narrator: Hello, traveller!") == 0);
+    const YsAttribute *character = find_attribute(&event.line, "character");
+    CHECK(character != NULL);
+    CHECK(character->property_count == 1);
+    CHECK(strcmp(character->properties[0].name, "name") == 0);
+    CHECK(strcmp(character->properties[0].value.string, "Narrator") == 0);
+    const YsAttribute *wave = find_attribute(&event.line, "wave");
+    CHECK(wave != NULL);
+    CHECK(wave->position == 17);
+    CHECK(wave->length == 9);
+    CHECK(wave->property_count == 1);
+    CHECK(strcmp(wave->properties[0].name, "size") == 0);
+    CHECK(wave->properties[0].value.type == YS_VALUE_TYPE_NUMBER);
+    CHECK(wave->properties[0].value.number == 2);
+
+    event = expect_event(dialogue, YS_EVENT_TYPE_LINE);
+    CHECK(strcmp(event.line.text, "You have 10 gold.") == 0);
+    CHECK(recorder.double_calls == 1);
+
+    event = expect_event(dialogue, YS_EVENT_TYPE_OPTIONS);
+    CHECK(event.option_count == 2);
+    CHECK(strcmp(event.options[0].line.text, "Buy a sword") == 0);
+    CHECK(event.options[0].is_available);
+    CHECK(strcmp(event.options[1].line.text, "Leave") == 0);
+    size_t buy_option = event.options[0].id;
+
+    CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    CHECK(event.type == YS_EVENT_TYPE_NONE);
+    CHECK(ys_dialogue_select_option(dialogue, 42) == YS_STATUS_INVALID_ARGUMENT);
+    CHECK_OK(ys_dialogue_select_option(dialogue, buy_option));
+
+    CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    CHECK(event.type == YS_EVENT_TYPE_NONE);
+    CHECK(recorder.sound_calls == 1);
+    CHECK(strcmp(recorder.sound, "sword swing") == 0);
+    CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    CHECK(event.type == YS_EVENT_TYPE_NONE);
+    CHECK_OK(ys_dialogue_complete_command(dialogue));
+    CHECK(ys_dialogue_complete_command(dialogue) == YS_STATUS_INVALID_ARGUMENT);
+
+    event = expect_event(dialogue, YS_EVENT_TYPE_LINE);
+    CHECK(strcmp(event.line.id, "line:shop") == 0);
+    CHECK(strcmp(event.line.text, "Hello, Alice") == 0);
+
+    event = expect_event(dialogue, YS_EVENT_TYPE_COMMAND);
+    CHECK(strcmp(event.command.name, "fade") == 0);
+    CHECK(strcmp(event.command.raw, "fade 2 true") == 0);
+    CHECK(event.command.parameter_count == 2);
+    CHECK(strcmp(event.command.parameters[0].string, "2") == 0);
+    CHECK(strcmp(event.command.parameters[1].string, "true") == 0);
+
+    expect_event(dialogue, YS_EVENT_TYPE_DIALOGUE_COMPLETE);
+    CHECK_OK(ys_dialogue_next_event(dialogue, &event));
+    CHECK(event.type == YS_EVENT_TYPE_NONE);
+
+    YsValue gold;
+    CHECK_OK(ys_dialogue_get_variable(dialogue, "$gold", &gold));
+    CHECK(gold.type == YS_VALUE_TYPE_NUMBER);
+    CHECK(gold.number == 10);
+    YsValue name = {YS_VALUE_TYPE_STRING, 0, false, "Bob"};
+    CHECK_OK(ys_dialogue_set_variable(dialogue, "$name", &name));
+    CHECK_OK(ys_dialogue_get_variable(dialogue, "$name", &name));
+    CHECK(name.type == YS_VALUE_TYPE_STRING);
+    CHECK(strcmp(name.string, "Bob") == 0);
+    CHECK(ys_dialogue_get_variable(dialogue, "$missing", &name) == YS_STATUS_VARIABLE_ERROR);
+
+    ys_dialogue_free(dialogue);
+}
+
+static void test_program_blob_can_be_loaded_into_another_dialogue(void) {
+    YsSource source = {"test.yarn",
+                       "title: Start\n---\nFirst line #line:first\n<<stop>>\nUnreachable #line:second\n===\n"};
+    YsCompilation *compilation = NULL;
+    CHECK_OK(ys_compile(&source, 1, NULL, &compilation));
+    YsBuffer program = {NULL, 0};
+    CHECK_OK(ys_compilation_program(compilation, &program));
+    CHECK(program.data != NULL);
+    CHECK(program.length > 0);
+
+    YsDialogue *dialogue = ys_dialogue_new();
+    const uint8_t garbage[] = {0xff, 0xff, 0xff};
+    CHECK(ys_dialogue_load_program(dialogue, garbage, sizeof garbage) == YS_STATUS_INVALID_PROGRAM);
+    CHECK_OK(ys_dialogue_load_program(dialogue, program.data, program.length));
+    ys_buffer_free(&program);
+    CHECK(program.data == NULL);
+    for (size_t i = 0; i < ys_compilation_line_count(compilation); i++) {
+        const char *id = NULL;
+        const char *text = NULL;
+        CHECK_OK(ys_compilation_line(compilation, i, &id, &text));
+        CHECK_OK(ys_dialogue_add_line(dialogue, id, text));
+    }
+    ys_compilation_free(compilation);
+
+    CHECK_OK(ys_dialogue_set_node(dialogue, "Start"));
+    YsEvent event = expect_event(dialogue, YS_EVENT_TYPE_LINE);
+    CHECK(strcmp(event.line.text, "First line") == 0);
+    expect_event(dialogue, YS_EVENT_TYPE_DIALOGUE_COMPLETE);
+
+    CHECK_OK(ys_dialogue_set_node(dialogue, "Start"));
+    expect_event(dialogue, YS_EVENT_TYPE_LINE);
+    CHECK_OK(ys_dialogue_stop(dialogue));
+    expect_event(dialogue, YS_EVENT_TYPE_DIALOGUE_COMPLETE);
+
+    ys_dialogue_free(dialogue);
+}
+
+static void test_null_arguments_are_rejected(void) {
+    YsEvent event;
+    CHECK(ys_dialogue_next_event(NULL, &event) == YS_STATUS_NULL_ARGUMENT);
+    CHECK(strstr(ys_last_error_message(), "dialogue") != NULL);
+
+    YsDialogue *dialogue = ys_dialogue_new();
+    CHECK(ys_dialogue_add_function(dialogue, "double", YS_VALUE_TYPE_NUMBER, 1, NULL, NULL) ==
+          YS_STATUS_NULL_ARGUMENT);
+    CHECK(strstr(ys_last_error_message(), "callback") != NULL);
+    CHECK(ys_dialogue_add_command(dialogue, "play_sound", NULL, NULL) == YS_STATUS_NULL_ARGUMENT);
+    ys_dialogue_free(dialogue);
+
+    ys_dialogue_free(NULL);
+    ys_compilation_free(NULL);
+    ys_buffer_free(NULL);
+}
+
+static void test_invalid_types_are_rejected(void) {
+    YsDialogue *dialogue = ys_dialogue_new();
+    CHECK(ys_dialogue_add_function(dialogue, "double", 42, 1, double_number, NULL) ==
+          YS_STATUS_INVALID_ARGUMENT);
+    YsValue value = {42, 0, false, NULL};
+    CHECK(ys_dialogue_set_variable(dialogue, "$gold", &value) == YS_STATUS_INVALID_ARGUMENT);
+    CHECK(strstr(ys_last_error_message(), "type") != NULL);
+    ys_dialogue_free(dialogue);
+}
+
+int main(void) {
+    test_compilation_errors_are_reported();
+    test_dialogue_runs_through_callbacks_and_events();
+    test_program_blob_can_be_loaded_into_another_dialogue();
+    test_null_arguments_are_rejected();
+    test_invalid_types_are_rejected();
+    puts("All C API tests passed");
+    return 0;
+}
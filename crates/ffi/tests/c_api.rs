@@ -0,0 +1,55 @@
+//! Builds `tests/c/dialogue_test.c` against the static library and the generated header, then runs it.
+//! Set `CC` to use a different C compiler than `cc`.
+#![cfg(target_os = "linux")]
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[test]
+fn c_program_drives_dialogue_through_c_api() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join("dialogue_test");
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+
+    let output = Command::new(&compiler)
+        .arg("-std=c11")
+        .arg("-Wall")
+        .arg("-Wextra")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests").join("c").join("dialogue_test.c"))
+        .arg(static_library())
+        .args(["-lpthread", "-ldl", "-lm"])
+        .arg("-o")
+        .arg(&executable)
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run the C compiler `{compiler}`: {e}"));
+    assert!(
+        output.status.success(),
+        "Failed to compile the C test program:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = Command::new(&executable).output().unwrap();
+    assert!(
+        output.status.success(),
+        "The C test program failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Cargo builds the static library next to the `deps` directory this test runs from.
+fn static_library() -> PathBuf {
+    let test_executable = env::current_exe().unwrap();
+    let profile_dir = test_executable.parent().unwrap().parent().unwrap();
+    let library = profile_dir.join("libyarnspinner_ffi.a");
+    assert!(
+        library.exists(),
+        "Static library not found at {}",
+        library.display()
+    );
+    library
+}
@@ -0,0 +1,29 @@
+//! Checks that the checked-in `include/yarnspinner.h` matches the exported functions and types.
+//! Run with `UPDATE_HEADER=1` to regenerate it after changing the C API.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn header_is_up_to_date() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let header_path = manifest_dir.join("include").join("yarnspinner.h");
+    let config = cbindgen::Config::from_file(manifest_dir.join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::generate_with_config(manifest_dir, config)
+        .unwrap_or_else(|e| panic!("Failed to generate the C header: {e}"));
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    if env::var_os("UPDATE_HEADER").is_some() {
+        fs::write(&header_path, generated).unwrap();
+        return;
+    }
+    let checked_in = fs::read_to_string(&header_path).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "{} is out of date, regenerate it with `UPDATE_HEADER=1 cargo test -p yarnspinner_ffi --test header`",
+        header_path.display()
+    );
+}
@@ -0,0 +1,31 @@
+/// Returns the number of single-character insertions, deletions and substitutions needed to turn `a` into `b`.
+pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous_row: Vec<_> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != *b_char);
+            let value = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+            current_row.push(value);
+        }
+        previous_row = current_row;
+    }
+    previous_row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn computes_levenshtein_distance() {
+        assert_eq!(0, levenshtein_distance("gold", "gold"));
+        assert_eq!(1, levenshtein_distance("gold", "gol"));
+        assert_eq!(1, levenshtein_distance("gold", "bold"));
+        assert_eq!(3, levenshtein_distance("kitten", "sitting"));
+        assert_eq!(4, levenshtein_distance("", "gold"));
+    }
+}
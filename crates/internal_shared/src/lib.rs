@@ -1,7 +1,9 @@
 pub mod bug;
+pub mod levenshtein;
 
 pub mod prelude {
     pub const LINE_ID_PREFIX: &str = "line:";
     pub use crate::bug::UnwrapExt;
+    pub use crate::levenshtein::levenshtein_distance;
     pub use crate::{assert_or_bug, bug};
 }
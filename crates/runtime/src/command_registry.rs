@@ -0,0 +1,537 @@
+use crate::prelude::*;
+use alloc::borrow::Cow;
+use bevy_platform::collections::HashMap;
+use bevy_platform::collections::hash_map;
+use bevy_platform::sync::{Arc, Mutex};
+use core::any::TypeId;
+use core::error::Error;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use variadics_please::all_tuples;
+use yarnspinner_core::prelude::optionality::AllowedOptionalityChain;
+use yarnspinner_core::prelude::{YarnFnParam, YarnFnParamError, YarnValueWrapper};
+
+/// A collection of commands that can be dispatched when a [`DialogueEvent::Command`] is encountered, for hosts that don't use Bevy.
+/// It is the counterpart of [`Library`] for commands.
+///
+/// If a command `add_player` with the parameters `name` and `age` has been registered, it can be called from Yarn like this:
+/// ```text
+/// <<add_player "John" 42>>
+/// ```
+///
+/// ## Example
+///
+/// ```
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::YarnValue;
+/// let mut commands = CommandRegistry::new();
+/// commands.add_command("add_player", |name: &str, age: Option<f32>| {
+///     let age = age.unwrap_or(18.0);
+///     println!("Adding player {name} with age {age}");
+/// });
+///
+/// # let command = Command { name: "add_player".to_owned(), parameters: vec![YarnValue::from("John")], raw: "add_player John".to_owned() };
+/// // For every `DialogueEvent::Command(command)`:
+/// let completion = commands.dispatch(&command)?;
+/// assert!(completion.is_complete());
+/// # Ok::<(), CommandError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandRegistry(InnerRegistry);
+
+type InnerRegistry = HashMap<Cow<'static, str>, Box<dyn UntypedCommandFn>>;
+
+impl Extend<<InnerRegistry as IntoIterator>::Item> for CommandRegistry {
+    fn extend<T: IntoIterator<Item = <InnerRegistry as IntoIterator>::Item>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for CommandRegistry {
+    type Item = (Cow<'static, str>, Box<dyn UntypedCommandFn>);
+    type IntoIter = hash_map::IntoIter<Cow<'static, str>, Box<dyn UntypedCommandFn>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl CommandRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a new command to the registry, overwriting any command with the same name.
+    /// See [`CommandFn`]'s documentation for what kinds of functions are allowed.
+    pub fn add_command<Marker, F>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        command: F,
+    ) -> &mut Self
+    where
+        Marker: 'static,
+        F: CommandFn<Marker>,
+    {
+        let wrapped = CommandFnWrapper::from(command);
+        self.0.insert(name.into(), Box::new(wrapped));
+        self
+    }
+
+    /// Loads the commands from another [`CommandRegistry`], overwriting any commands with the same name.
+    pub fn import(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    /// Removes the command with the given name and returns it, if it exists.
+    pub fn remove_command(&mut self, name: &str) -> Option<Box<dyn UntypedCommandFn>> {
+        self.0.remove(name)
+    }
+
+    /// Returns `true` if the registry contains a command with the given name.
+    pub fn contains_command(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Gets a command by name.
+    pub fn get(&self, name: &str) -> Option<&dyn UntypedCommandFn> {
+        self.0.get(name).map(|command| command.as_ref())
+    }
+
+    /// Iterates over the names and commands in the registry.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &dyn UntypedCommandFn)> {
+        self.0
+            .iter()
+            .map(|(name, command)| (name.as_ref(), command.as_ref()))
+    }
+
+    /// Iterates over the names of all commands in the registry.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(|name| name.as_ref())
+    }
+
+    /// Returns the number of registered commands.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the registry contains no commands.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Calls the command registered under the name of the given [`Command`] with its parameters.
+    ///
+    /// Returns a [`CommandCompletion`] that tells when the command finished, which is right away unless the command returned a pending one.
+    /// Fails if no command with that name is registered or if the parameters don't fit the command's signature.
+    pub fn dispatch(
+        &self,
+        command: &Command,
+    ) -> core::result::Result<CommandCompletion, CommandError> {
+        let function =
+            self.0
+                .get(command.name.as_str())
+                .ok_or_else(|| CommandError::UnknownCommand {
+                    name: command.name.clone(),
+                })?;
+        function
+            .call(command.parameters.clone())
+            .map_err(|error| CommandError::InvalidArguments {
+                name: command.name.clone(),
+                error,
+            })
+    }
+}
+
+/// An error returned by [`CommandRegistry::dispatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// No command with the given name is registered.
+    UnknownCommand {
+        /// The name of the command.
+        name: String,
+    },
+    /// The parameters passed from Yarn don't fit the signature of the registered command.
+    InvalidArguments {
+        /// The name of the command.
+        name: String,
+        /// What is wrong with the parameters.
+        error: YarnFnParamError,
+    },
+}
+
+impl Error for CommandError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CommandError::UnknownCommand { .. } => None,
+            CommandError::InvalidArguments { error, .. } => Some(error),
+        }
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CommandError::UnknownCommand { name } => {
+                write!(f, "No command named \"{name}\" is registered")
+            }
+            CommandError::InvalidArguments { name, error } => {
+                write!(f, "Invalid arguments for command \"{name}\": {error}")
+            }
+        }
+    }
+}
+
+/// Tells when a command dispatched by [`CommandRegistry::dispatch`] finished.
+///
+/// Commands that finish right away return `()`, which results in a completed [`CommandCompletion`].
+/// Long-running commands, e.g. ones that move the camera, instead create one with [`CommandCompletion::pending`],
+/// keep a clone of it and call [`CommandCompletion::complete`] on the clone when they're done. All clones share the same state.
+///
+/// Hosts can poll [`CommandCompletion::is_complete`], e.g. once per frame, or `.await` the completion, which also makes it fit a `DialogueEventStream`'s command handler.
+#[derive(Debug, Clone)]
+pub struct CommandCompletion(Option<Arc<Mutex<CompletionState>>>);
+
+#[derive(Debug, Default)]
+struct CompletionState {
+    is_complete: bool,
+    waker: Option<Waker>,
+}
+
+impl CommandCompletion {
+    /// Creates a completion of a command that already finished.
+    pub fn finished() -> Self {
+        Self(None)
+    }
+
+    /// Creates a completion of a command that is still running until [`CommandCompletion::complete`] is called on it or one of its clones.
+    pub fn pending() -> Self {
+        Self(Some(Default::default()))
+    }
+
+    /// Marks the command as finished and wakes up the task awaiting the completion, if any.
+    pub fn complete(&self) {
+        if let Some(state) = &self.0 {
+            let mut state = state.lock().unwrap();
+            state.is_complete = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Returns `true` if the command finished.
+    pub fn is_complete(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|state| state.lock().unwrap().is_complete)
+    }
+}
+
+impl Future for CommandCompletion {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(state) = &self.0 else {
+            return Poll::Ready(());
+        };
+        let mut state = state.lock().unwrap();
+        if state.is_complete {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Trait implemented by the return types of commands registered in a [`CommandRegistry`].
+pub trait IntoCommandCompletion {
+    /// Converts the returned value into a [`CommandCompletion`].
+    fn into_command_completion(self) -> CommandCompletion;
+}
+
+impl IntoCommandCompletion for () {
+    fn into_command_completion(self) -> CommandCompletion {
+        CommandCompletion::finished()
+    }
+}
+
+impl IntoCommandCompletion for CommandCompletion {
+    fn into_command_completion(self) -> CommandCompletion {
+        self
+    }
+}
+
+/// A function that can be registered as a command via [`CommandRegistry::add_command`].
+/// It must have the following properties:
+/// - Its parameters follow the same rules as the ones of a [`YarnFn`], i.e. each one must be a [`YarnFnParam`].
+///   Use `Option<T>` for parameters that may be omitted in Yarn, which must come after all required parameters,
+///   and use e.g. [`Option::unwrap_or`] to give them a default value.
+/// - It must return either `()` if it finishes right away or a [`CommandCompletion`] if it keeps running. See [`IntoCommandCompletion`].
+///
+/// ## Examples
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// fn set_sprite(character: &str, sprite: String, fade_seconds: Option<f32>) -> CommandCompletion {
+///     let completion = CommandCompletion::pending();
+///     // Hand a clone of `completion` to the code fading the sprite, which calls `complete` on it when done
+///     completion
+/// }
+/// # CommandRegistry::new().add_command("set_sprite", set_sprite);
+/// ```
+/// Which may be called from Yarn as follows:
+/// ```text
+/// <<set_sprite ship "happy">>
+/// <<set_sprite ship "sad" 0.5>>
+/// ```
+pub trait CommandFn<Marker>: Clone + Send + Sync + 'static {
+    /// The type returned by this command. See [`IntoCommandCompletion`] for what is allowed.
+    type Out: IntoCommandCompletion + 'static;
+    #[doc(hidden)]
+    fn call(&self, input: Vec<YarnValue>) -> core::result::Result<Self::Out, YarnFnParamError>;
+    /// The [`TypeId`]s of the parameters of this command.
+    fn parameter_types(&self) -> Vec<TypeId>;
+}
+
+/// A [`CommandFn`] with the `Marker` type parameter erased, as it appears in the [`CommandRegistry`].
+pub trait UntypedCommandFn: Debug + Display + Send + Sync {
+    #[doc(hidden)]
+    fn call(
+        &self,
+        input: Vec<YarnValue>,
+    ) -> core::result::Result<CommandCompletion, YarnFnParamError>;
+    #[doc(hidden)]
+    fn clone_box(&self) -> Box<dyn UntypedCommandFn>;
+    /// The [`TypeId`]s of the parameters of this command.
+    fn parameter_types(&self) -> Vec<TypeId>;
+}
+
+impl Clone for Box<dyn UntypedCommandFn> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl PartialEq for Box<dyn UntypedCommandFn> {
+    fn eq(&self, other: &Self) -> bool {
+        // Not guaranteed to be unique, but it's good enough for our purposes.
+        let debug = format!("{self:?}");
+        let other_debug = format!("{other:?}");
+        debug == other_debug
+    }
+}
+
+impl Eq for Box<dyn UntypedCommandFn> {}
+
+impl<Marker, F> UntypedCommandFn for CommandFnWrapper<Marker, F>
+where
+    Marker: 'static,
+    F: CommandFn<Marker>,
+{
+    fn call(
+        &self,
+        input: Vec<YarnValue>,
+    ) -> core::result::Result<CommandCompletion, YarnFnParamError> {
+        self.function
+            .call(input)
+            .map(IntoCommandCompletion::into_command_completion)
+    }
+
+    fn clone_box(&self) -> Box<dyn UntypedCommandFn> {
+        Box::new(self.clone())
+    }
+
+    fn parameter_types(&self) -> Vec<TypeId> {
+        self.function.parameter_types()
+    }
+}
+
+struct CommandFnWrapper<Marker, F>
+where
+    F: CommandFn<Marker>,
+{
+    function: F,
+
+    // NOTE: PhantomData<fn()-> T> gives this safe Send/Sync impls
+    _marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker, F> Clone for CommandFnWrapper<Marker, F>
+where
+    F: CommandFn<Marker>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            function: self.function.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker, F> From<F> for CommandFnWrapper<Marker, F>
+where
+    F: CommandFn<Marker>,
+{
+    fn from(function: F) -> Self {
+        Self {
+            function,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Marker, F> Debug for CommandFnWrapper<Marker, F>
+where
+    F: CommandFn<Marker>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let signature = core::any::type_name::<Marker>();
+        let function_path = core::any::type_name::<F>();
+        let debug_message = format!("{signature} {{{function_path}}}");
+        f.debug_struct(&debug_message).finish()
+    }
+}
+
+impl<Marker, F> Display for CommandFnWrapper<Marker, F>
+where
+    F: CommandFn<Marker>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let signature = core::any::type_name::<Marker>();
+        f.write_str(signature)
+    }
+}
+
+macro_rules! impl_command_fn_tuple {
+    ($($param: ident),*) => {
+        #[allow(non_snake_case)]
+        impl<F, O, $($param,)*> CommandFn<fn($($param,)*) -> O> for F
+            where
+            for<'a> F:
+                Send + Sync + Clone + 'static +
+                Fn($($param,)*) -> O +
+                Fn($(<$param as YarnFnParam>::Item<'a>,)*) -> O,
+            O: IntoCommandCompletion + 'static,
+            $($param: YarnFnParam + 'static,)*
+            ($(<$param as YarnFnParam>::Optionality,)*): AllowedOptionalityChain,
+            {
+                type Out = O;
+                fn call(&self, input: Vec<YarnValue>) -> core::result::Result<Self::Out, YarnFnParamError> {
+                    let mut params: Vec<_> = input.into_iter().map(YarnValueWrapper::from).collect();
+                    let mut iter = params.iter_mut().peekable();
+
+                    // $param is the type implementing YarnFnParam
+                    let input = (
+                        $($param::try_retrieve(&mut iter)?,)*
+                    );
+                    if iter.next().is_some() {
+                        return Err(YarnFnParamError::TooManyArguments);
+                    }
+
+                    let ($($param,)*) = input;
+                    Ok(self($($param,)*))
+                }
+
+                fn parameter_types(&self) -> Vec<TypeId> {
+                    vec![$(TypeId::of::<$param>()),*]
+                }
+            }
+    };
+}
+
+all_tuples!(impl_command_fn_tuple, 0, 16, P);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    fn command(name: &str, parameters: impl IntoIterator<Item = YarnValue>) -> Command {
+        Command {
+            name: name.to_owned(),
+            parameters: parameters.into_iter().collect(),
+            raw: name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn dispatches_commands_with_typed_and_optional_parameters() {
+        let total = Arc::new(AtomicUsize::new(0));
+        let mut commands = CommandRegistry::new();
+        let counter = total.clone();
+        commands.add_command("add", move |amount: usize, times: Option<usize>| {
+            counter.fetch_add(amount * times.unwrap_or(1), Ordering::SeqCst);
+        });
+
+        commands.dispatch(&command("add", [2.into()])).unwrap();
+        commands
+            .dispatch(&command("add", [3.into(), 10.into()]))
+            .unwrap();
+        assert_eq!(total.load(Ordering::SeqCst), 32);
+    }
+
+    #[test]
+    fn reports_unknown_commands_and_invalid_arguments() {
+        let mut commands = CommandRegistry::new();
+        commands.add_command("greet", |_name: &str, _times: f32| {});
+
+        assert_eq!(
+            commands.dispatch(&command("wave", [])).unwrap_err(),
+            CommandError::UnknownCommand {
+                name: "wave".to_owned()
+            }
+        );
+        assert_eq!(
+            commands
+                .dispatch(&command("greet", ["Sally".into()]))
+                .unwrap_err(),
+            CommandError::InvalidArguments {
+                name: "greet".to_owned(),
+                error: YarnFnParamError::TooFewArguments,
+            }
+        );
+        assert!(matches!(
+            commands
+                .dispatch(&command("greet", ["Sally".into(), "twice".into()]))
+                .unwrap_err(),
+            CommandError::InvalidArguments {
+                error: YarnFnParamError::InvalidType { .. },
+                ..
+            }
+        ));
+        assert_eq!(
+            commands
+                .dispatch(&command("greet", ["Sally".into(), 2.into(), true.into()]))
+                .unwrap_err(),
+            CommandError::InvalidArguments {
+                name: "greet".to_owned(),
+                error: YarnFnParamError::TooManyArguments,
+            }
+        );
+    }
+
+    #[test]
+    fn long_running_commands_complete_through_any_clone() {
+        let running = Arc::new(Mutex::new(Vec::new()));
+        let mut commands = CommandRegistry::new();
+        let started = running.clone();
+        commands.add_command("fade", move || {
+            let completion = CommandCompletion::pending();
+            started.lock().unwrap().push(completion.clone());
+            completion
+        });
+
+        let mut completion = commands.dispatch(&command("fade", [])).unwrap();
+        assert!(!completion.is_complete());
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(Pin::new(&mut completion).poll(&mut context).is_pending());
+
+        running.lock().unwrap().pop().unwrap().complete();
+        assert!(completion.is_complete());
+        assert!(Pin::new(&mut completion).poll(&mut context).is_ready());
+    }
+}
@@ -40,6 +40,13 @@ pub enum DialogueError {
     },
     UnexpectedOptionSelectionError,
     ContinueOnOptionSelectionError,
+    InvalidRollbackError {
+        steps: usize,
+        available: usize,
+    },
+    NoAvailableOptionsError {
+        line_ids: Vec<LineId>,
+    },
     NoNodeSelectedOnContinue,
     NoProgramLoaded,
     InvalidNode {
@@ -76,6 +83,11 @@ impl Display for DialogueError {
             },
             UnexpectedOptionSelectionError => f.write_str("An option was selected, but the dialogue wasn't waiting for a selection. This method should only be called after the Dialogue is waiting for the user to select an option."),
             ContinueOnOptionSelectionError => f.write_str("Dialogue was asked to continue running, but it is waiting for the user to select an option first."),
+            InvalidRollbackError { steps, available } => write!(f, "Cannot roll back {steps} checkpoints (expected a number between 1 and {available})."),
+            NoAvailableOptionsError { line_ids } => {
+                let line_ids = line_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+                write!(f, "None of the options {line_ids} are available because all of their conditions failed.")
+            }
             NoNodeSelectedOnContinue => f.write_str("Cannot continue running dialogue. No node has been selected."),
             NoProgramLoaded => f.write_str("No program has been loaded. Cannot continue running dialogue."),
             InvalidNode { node_name } => write!(f, "No node named \"{node_name}\" has been loaded."),
@@ -153,7 +165,9 @@ impl Dialogue {
     /// The [`Dialogue`]'s locale, as an IETF BCP 47 code.
     ///
     /// This code is used to determine how the `plural` and `ordinal`
-    /// markers determine the plural class of numbers.
+    /// markers determine the plural class of numbers and how numbers in
+    /// inline expressions like `{$gold}` are formatted, e.g. `1,234.5` in English
+    /// and `1.234,5` in German.
     ///
     /// For example, the code "en-US" represents the English language as
     /// used in the United States.
@@ -192,6 +206,22 @@ impl Dialogue {
         &mut self.vm.library
     }
 
+    /// Registers an [`AttributeMarkerProcessor`] that produces the replacement text for all markers named `attribute_name`,
+    /// e.g. `item` for `[item id=sword/]`. The processor is immediately passed the current [`Dialogue::language_code`].
+    ///
+    /// Replaces any processor that was previously registered for the same name, including the built-in ones for `select`, `plural`, `ordinal` and `nomarkup`.
+    pub fn add_marker_processor(
+        &mut self,
+        attribute_name: impl Into<String>,
+        mut processor: Box<dyn AttributeMarkerProcessor>,
+    ) -> &mut Self {
+        processor.set_language_code(self.language_code.clone());
+        self.vm
+            .line_parser_mut()
+            .replace_marker_processor(attribute_name, processor);
+        self
+    }
+
     /// Gets whether [`Dialogue::continue_`] is able able to return [`DialogueEvent::LineHints`] events.
     /// The default is `false`.
     #[must_use]
@@ -206,6 +236,127 @@ impl Dialogue {
         self
     }
 
+    /// Gets the [`DialogueHistory`] of lines shown and options chosen so far, if recording it was enabled with [`Dialogue::set_history`].
+    #[must_use]
+    pub fn history(&self) -> Option<&DialogueHistory> {
+        self.vm.history.as_ref()
+    }
+
+    /// Mutable gets the [`DialogueHistory`], e.g. to clear it or change its capacity.
+    pub fn history_mut(&mut self) -> Option<&mut DialogueHistory> {
+        self.vm.history.as_mut()
+    }
+
+    /// Starts recording every line shown and option chosen into the given [`DialogueHistory`], which may also be one restored from a save game.
+    /// Passing `None` stops recording and discards the current history. Recording is disabled by default.
+    ///
+    /// ```
+    /// # use yarnspinner_runtime::prelude::*;
+    /// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+    /// dialogue.set_history(DialogueHistory::new(100));
+    /// assert!(dialogue.history().unwrap().is_empty());
+    /// ```
+    pub fn set_history(&mut self, history: impl Into<Option<DialogueHistory>>) -> &mut Self {
+        self.vm.history = history.into();
+        self
+    }
+
+    /// Produces the lines of all entries in the [`Dialogue::history`] again through the current [`TextProvider`] and markup parser,
+    /// so that a backlog can be shown in the current language even if the lines were originally shown in another one.
+    /// Returns an empty list if no history is recorded.
+    pub fn resolve_history(&mut self) -> Result<Vec<ResolvedHistoryEntry>> {
+        let entries: Vec<_> = self
+            .vm
+            .history
+            .iter()
+            .flat_map(|history| history.entries())
+            .cloned()
+            .collect();
+        entries
+            .into_iter()
+            .map(|entry| {
+                let line = self.vm.prepare_line(entry.line_id, &entry.substitutions)?;
+                Ok(ResolvedHistoryEntry {
+                    line,
+                    selected_option: entry.selected_option,
+                })
+            })
+            .collect()
+    }
+
+    /// Gets the [`SeenLines`], i.e. the IDs of all lines and options delivered so far, e.g. to save them apart from the variables.
+    #[must_use]
+    pub fn seen_lines(&self) -> &SeenLines {
+        &self.vm.seen_lines
+    }
+
+    /// Mutable gets the [`SeenLines`], e.g. to mark lines as seen or unseen by hand.
+    pub fn seen_lines_mut(&mut self) -> &mut SeenLines {
+        &mut self.vm.seen_lines
+    }
+
+    /// Replaces the [`SeenLines`], e.g. with ones restored from disk. Returns the previous ones.
+    ///
+    /// ```
+    /// # use yarnspinner_core::prelude::*;
+    /// # use yarnspinner_runtime::prelude::*;
+    /// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+    /// let seen_lines: SeenLines = [LineId::from("line:intro")].into_iter().collect();
+    /// dialogue.set_seen_lines(seen_lines);
+    /// assert!(dialogue.seen_lines().contains(&LineId::from("line:intro")));
+    /// ```
+    pub fn set_seen_lines(&mut self, seen_lines: SeenLines) -> SeenLines {
+        core::mem::replace(&mut self.vm.seen_lines, seen_lines)
+    }
+
+    /// Gets how many checkpoints [`Dialogue::rollback`] can go back at most. The default is `0`, which disables rollback.
+    #[must_use]
+    pub fn rollback_depth(&self) -> usize {
+        self.vm.rollback_journal.depth()
+    }
+
+    /// Sets how many checkpoints [`Dialogue::rollback`] can go back at most, discarding the oldest checkpoints that no longer fit.
+    /// A checkpoint is taken whenever [`DialogueEvent::Options`] is delivered, and also on every [`DialogueEvent::Line`] if [`Dialogue::set_checkpoints_on_lines`] is enabled.
+    /// Passing `0` disables rollback and discards all checkpoints.
+    pub fn set_rollback_depth(&mut self, depth: usize) -> &mut Self {
+        self.vm.rollback_journal.set_depth(depth);
+        self
+    }
+
+    /// Gets whether a checkpoint for [`Dialogue::rollback`] is also taken on every [`DialogueEvent::Line`]. The default is `false`.
+    #[must_use]
+    pub fn checkpoints_on_lines(&self) -> bool {
+        self.vm.rollback_journal.checkpoints_on_lines()
+    }
+
+    /// Mutable gets whether a checkpoint for [`Dialogue::rollback`] is also taken on every [`DialogueEvent::Line`]. The default is `false`.
+    pub fn set_checkpoints_on_lines(&mut self, enabled: bool) -> &mut Self {
+        self.vm.rollback_journal.set_checkpoints_on_lines(enabled);
+        self
+    }
+
+    /// Gets what happens when every option of an option group has a failing condition. The default is [`UnavailableOptionsPolicy::Deliver`].
+    #[must_use]
+    pub fn unavailable_options_policy(&self) -> UnavailableOptionsPolicy {
+        self.vm.unavailable_options_policy
+    }
+
+    /// Sets what happens when every option of an option group has a failing condition. See [`UnavailableOptionsPolicy`].
+    pub fn set_unavailable_options_policy(
+        &mut self,
+        policy: UnavailableOptionsPolicy,
+    ) -> &mut Self {
+        self.vm.unavailable_options_policy = policy;
+        self
+    }
+
+    /// Gets the number of steps [`Dialogue::rollback`] can currently go back.
+    /// While the dialogue is still paused at the latest checkpoint, e.g. waiting for an option to be selected, that checkpoint is not counted.
+    #[must_use]
+    pub fn available_rollbacks(&self) -> usize {
+        self.vm.rollback_journal.available()
+    }
+
     /// Gets the currently registered [`TextProvider`].
     pub fn text_provider(&self) -> &dyn TextProvider {
         self.vm.text_provider()
@@ -292,9 +443,12 @@ impl Dialogue {
     }
 
     fn extend_variable_storage_from(&mut self, program: &Program) {
+        let variable_storage = self.variable_storage();
+        let keeps_existing_values = variable_storage.is_persistent();
         let initial: HashMap<String, YarnValue> = program
             .initial_values
             .iter()
+            .filter(|(k, _)| !keeps_existing_values || !variable_storage.contains(k))
             .map(|(k, v)| (k.clone(), v.clone().into()))
             .collect();
 
@@ -305,14 +459,17 @@ impl Dialogue {
     }
 
     /// Sets or replaces the [`Dialogue`]'s current [`Program`]. The program is replaced, all current state is reset.
+    /// The declared variables are set to their initial values, except for those already in a [`VariableStorage::is_persistent`] storage.
     pub fn replace_program(&mut self, program: Program) -> &mut Self {
         self.vm.program.replace(program.clone());
         self.vm.reset_state();
+        self.vm.rollback_journal.clear();
         self.extend_variable_storage_from(&program);
         self
     }
 
     /// Merges the currently set [`Program`] with the given one. If there is no program set, the given one is set.
+    /// The declared variables are set to their initial values, except for those already in a [`VariableStorage::is_persistent`] storage.
     pub fn add_program(&mut self, program: Program) -> &mut Self {
         if let Some(existing_program) = self.vm.program.as_mut() {
             *existing_program =
@@ -361,7 +518,8 @@ impl Dialogue {
 
     /// Unloads all nodes from the Dialogue.
     pub fn unload_all(&mut self) {
-        self.vm.unload_programs()
+        self.vm.unload_programs();
+        self.vm.rollback_journal.clear();
     }
 
     /// Gets the names of the nodes in the currently loaded Program, if there is one.
@@ -500,6 +658,23 @@ impl Dialogue {
         self.vm.set_selected_option_by_line_id(selected_line_id)
     }
 
+    /// Rewinds the dialogue `steps` checkpoints back, e.g. to let the player go back to the previous choice.
+    /// Requires a [`Dialogue::rollback_depth`] greater than `0`.
+    ///
+    /// The variables and visit counts are restored to the values they had at that checkpoint and the dialogue continues from there,
+    /// even if it was completed in the meantime. Returns a [`DialogueEvent::Rollback`] followed by the [`DialogueEvent::Options`] or [`DialogueEvent::Line`]
+    /// that was delivered at the checkpoint, which should be presented again. For options, this is the exact set of options shown back then.
+    ///
+    /// Only variable changes made by the Yarn program are undone, not ones made directly through the [`VariableStorage`].
+    /// Lines and options recorded in the [`Dialogue::history`] are kept.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `steps` is `0` or greater than [`Dialogue::available_rollbacks`].
+    pub fn rollback(&mut self, steps: usize) -> Result<Vec<DialogueEvent>> {
+        self.vm.rollback(steps)
+    }
+
     /// Gets a value indicating whether the Dialogue is currently executing Yarn instructions.
     #[must_use]
     pub fn is_active(&self) -> bool {
@@ -526,4 +701,36 @@ mod tests {
     }
 
     fn accept_send_sync(_: impl Send + Sync) {}
+
+    #[test]
+    fn loading_a_program_resets_variables_of_non_persistent_storage() {
+        let mut variable_storage = MemoryVariableStorage::new();
+        variable_storage
+            .set("$gold".to_owned(), 10.0.into())
+            .unwrap();
+        let mut dialogue = Dialogue::new(
+            Box::new(variable_storage),
+            Box::new(StringTableTextProvider::new()),
+        );
+        let program = Program {
+            initial_values: [("$gold".to_owned(), 0.0.into())].into_iter().collect(),
+            ..Default::default()
+        };
+
+        dialogue.replace_program(program.clone());
+        assert_eq!(
+            dialogue.variable_storage().get("$gold").unwrap(),
+            YarnValue::Number(0.0)
+        );
+
+        dialogue
+            .variable_storage_mut()
+            .set("$gold".to_owned(), 10.0.into())
+            .unwrap();
+        dialogue.add_program(program);
+        assert_eq!(
+            dialogue.variable_storage().get("$gold").unwrap(),
+            YarnValue::Number(0.0)
+        );
+    }
 }
@@ -37,6 +37,13 @@ pub struct DialogueOption {
     pub is_available: bool,
 }
 
+impl DialogueOption {
+    /// Whether this option was presented before. Shorthand for [`Line::is_seen`] of [`DialogueOption::line`].
+    pub fn is_seen(&self) -> bool {
+        self.line.is_seen
+    }
+}
+
 /// The identifying number for an option. You should not need to create these yourself, since you get them from [`DialogueOption`]s.
 ///
 /// Since the IDs are just zero-based indices, you can also derive them yourself. Note that the index numeration includes options which
@@ -56,3 +63,28 @@ impl Display for OptionId {
         write!(f, "{}", self.0)
     }
 }
+
+/// What a [`Dialogue`] does when it reaches an option group in which every option has a failing condition,
+/// i.e. all [`DialogueOption::is_available`] are `false`. Set it with [`Dialogue::set_unavailable_options_policy`].
+///
+/// The compiler warns about option groups in which every option has a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum UnavailableOptionsPolicy {
+    /// Deliver the options anyway in a [`DialogueEvent::Options`] and let the game decide what to do.
+    #[default]
+    Deliver,
+    /// Skip the option group and continue with the content after it, like Yarn Spinner 3 does.
+    ///
+    /// Programs compiled by older versions of the compiler don't know where the option group ends,
+    /// so their options are delivered as with [`UnavailableOptionsPolicy::Deliver`].
+    Fallthrough,
+    /// Return a [`DialogueError::NoAvailableOptionsError`].
+    Error,
+}
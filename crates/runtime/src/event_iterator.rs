@@ -0,0 +1,84 @@
+use crate::prelude::*;
+use alloc::collections::VecDeque;
+
+/// An [`Iterator`] over the [`DialogueEvent`]s of a [`Dialogue`], returned by [`Dialogue::events`].
+///
+/// Instead of handing out batches like [`Dialogue::continue_`], the events are yielded one by one, continuing the dialogue whenever a batch is exhausted.
+/// When a [`DialogueEvent::Options`] was yielded, select an option through [`DialogueEvents::select_option`] before asking for the next event:
+///
+/// ```ignore
+/// let mut events = dialogue.events();
+/// while let Some(event) = events.next() {
+///     match event? {
+///         DialogueEvent::Line(line) => println!("{}", line.text),
+///         DialogueEvent::Options(options) => events.select_option(options[0].id)?,
+///         _ => {}
+///     }
+/// }
+/// ```
+///
+/// The iterator ends after [`DialogueEvent::DialogueComplete`] or after the first error, e.g. when no option was selected.
+/// Call [`Dialogue::events`] again to resume afterwards.
+#[derive(Debug)]
+pub struct DialogueEvents<'a> {
+    dialogue: &'a mut Dialogue,
+    pending_events: VecDeque<DialogueEvent>,
+    is_finished: bool,
+}
+
+impl Dialogue {
+    /// Returns an [`Iterator`] that yields the [`DialogueEvent`]s of this dialogue one by one, calling [`Dialogue::continue_`] as needed.
+    /// See [`DialogueEvents`] for details.
+    pub fn events(&mut self) -> DialogueEvents<'_> {
+        DialogueEvents {
+            dialogue: self,
+            pending_events: VecDeque::new(),
+            is_finished: false,
+        }
+    }
+}
+
+impl DialogueEvents<'_> {
+    /// Passes the user's selection to [`Dialogue::set_selected_option`]. Must be called after a [`DialogueEvent::Options`] was yielded.
+    pub fn select_option(&mut self, selected_option_id: OptionId) -> crate::Result<()> {
+        self.dialogue.set_selected_option(selected_option_id)?;
+        Ok(())
+    }
+
+    /// Gets the [`Dialogue`] whose events are yielded.
+    pub fn dialogue(&self) -> &Dialogue {
+        self.dialogue
+    }
+
+    /// Gets the [`Dialogue`] whose events are yielded, e.g. to access its [`VariableStorage`] between events.
+    pub fn dialogue_mut(&mut self) -> &mut Dialogue {
+        self.dialogue
+    }
+}
+
+impl Iterator for DialogueEvents<'_> {
+    type Item = crate::Result<DialogueEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                if event == DialogueEvent::DialogueComplete {
+                    self.is_finished = true;
+                }
+                return Some(Ok(event));
+            }
+            if self.is_finished {
+                return None;
+            }
+            match self.dialogue.continue_() {
+                Ok(events) => self.pending_events.extend(events),
+                Err(e) => {
+                    self.is_finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for DialogueEvents<'_> {}
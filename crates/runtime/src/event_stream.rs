@@ -0,0 +1,197 @@
+use crate::prelude::*;
+use alloc::collections::VecDeque;
+use bevy_platform::sync::{Arc, Mutex};
+use core::borrow::BorrowMut;
+use core::fmt::{self, Debug};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use futures_core::{FusedStream, Stream};
+
+/// A future returned by the command handler of a [`DialogueEventStream`]. The dialogue only continues once it completed.
+pub type CommandFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+type CommandHandler = Box<dyn FnMut(&Command) -> Option<CommandFuture> + Send>;
+
+/// A [`Stream`] over the [`DialogueEvent`]s of a [`Dialogue`], for driving dialogue from async code without Bevy.
+///
+/// Like [`DialogueEvents`], the events are yielded one by one, continuing the dialogue whenever a batch is exhausted.
+/// The stream doesn't depend on a specific async runtime. Two kinds of events make it wait before continuing:
+/// - After a [`DialogueEvent::Options`], the stream is pending until an option is selected through an [`OptionSelector`] obtained from
+///   [`DialogueEventStream::option_selector`], which can be sent to whichever task handles the user's input.
+///   Selections made before the options were yielded are ignored. If the selection is invalid, the error is yielded and the stream keeps waiting for another one.
+/// - After a [`DialogueEvent::Command`], the stream is pending until the future returned for it by the handler set through
+///   [`DialogueEventStream::with_command_handler`] completed, if any.
+///
+/// The stream ends after [`DialogueEvent::DialogueComplete`] or after the first other error.
+/// The dialogue can be owned by the stream or borrowed mutably, and is given back by [`DialogueEventStream::into_inner`].
+pub struct DialogueEventStream<D = Dialogue> {
+    dialogue: D,
+    pending_events: VecDeque<DialogueEvent>,
+    option_selector: OptionSelector,
+    is_waiting_for_option_selection: bool,
+    command_handler: Option<CommandHandler>,
+    running_command: Option<CommandFuture>,
+    is_finished: bool,
+}
+
+/// A handle for selecting an option of a [`DialogueEventStream`] that is waiting after a [`DialogueEvent::Options`].
+/// Cloning it is cheap, all clones refer to the same stream.
+#[derive(Debug, Clone, Default)]
+pub struct OptionSelector(Arc<Mutex<OptionSelectorState>>);
+
+#[derive(Debug, Default)]
+struct OptionSelectorState {
+    selected_option_id: Option<OptionId>,
+    waker: Option<Waker>,
+}
+
+impl OptionSelector {
+    /// Selects the option with the given ID and wakes up the stream. Selecting again before the stream was polled replaces the selection.
+    pub fn select(&self, selected_option_id: OptionId) {
+        let mut state = self.0.lock().unwrap();
+        state.selected_option_id = Some(selected_option_id);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn take_selection(&self, waker: &Waker) -> Option<OptionId> {
+        let mut state = self.0.lock().unwrap();
+        let selection = state.selected_option_id.take();
+        if selection.is_none() {
+            state.waker = Some(waker.clone());
+        }
+        selection
+    }
+
+    fn discard_selection(&self) {
+        self.0.lock().unwrap().selected_option_id = None;
+    }
+}
+
+impl<D: BorrowMut<Dialogue>> DialogueEventStream<D> {
+    /// Creates a stream over the events of the given dialogue, which is either a [`Dialogue`] or a `&mut Dialogue`.
+    pub fn new(dialogue: D) -> Self {
+        Self {
+            dialogue,
+            pending_events: VecDeque::new(),
+            option_selector: OptionSelector::default(),
+            is_waiting_for_option_selection: false,
+            command_handler: None,
+            running_command: None,
+            is_finished: false,
+        }
+    }
+
+    /// Calls the given handler for every [`DialogueEvent::Command`] right before it is yielded.
+    /// If the handler returns a future, the dialogue is only continued once it completed.
+    #[must_use]
+    pub fn with_command_handler(
+        mut self,
+        handler: impl FnMut(&Command) -> Option<CommandFuture> + Send + 'static,
+    ) -> Self {
+        self.command_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Gets a handle for selecting an option after a [`DialogueEvent::Options`] was yielded.
+    pub fn option_selector(&self) -> OptionSelector {
+        self.option_selector.clone()
+    }
+
+    /// Gets the [`Dialogue`] whose events are yielded.
+    pub fn dialogue(&self) -> &Dialogue {
+        self.dialogue.borrow()
+    }
+
+    /// Gets the [`Dialogue`] whose events are yielded, e.g. to access its [`VariableStorage`] between events.
+    pub fn dialogue_mut(&mut self) -> &mut Dialogue {
+        self.dialogue.borrow_mut()
+    }
+
+    /// Returns the dialogue, dropping any events that were not yielded yet.
+    pub fn into_inner(self) -> D {
+        self.dialogue
+    }
+
+    fn start_handling(&mut self, event: &DialogueEvent) {
+        match event {
+            DialogueEvent::Options(_) => {
+                self.option_selector.discard_selection();
+                self.is_waiting_for_option_selection = true;
+            }
+            DialogueEvent::Command(command) => {
+                self.running_command = self
+                    .command_handler
+                    .as_mut()
+                    .and_then(|handler| handler(command));
+            }
+            DialogueEvent::DialogueComplete => self.is_finished = true,
+            _ => {}
+        }
+    }
+}
+
+impl<D: BorrowMut<Dialogue> + Unpin> Stream for DialogueEventStream<D> {
+    type Item = crate::Result<DialogueEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(running_command) = this.running_command.as_mut() {
+                if running_command.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                this.running_command = None;
+            }
+            if let Some(event) = this.pending_events.pop_front() {
+                this.start_handling(&event);
+                return Poll::Ready(Some(Ok(event)));
+            }
+            if this.is_finished {
+                return Poll::Ready(None);
+            }
+            if this.is_waiting_for_option_selection {
+                let Some(selected_option_id) = this.option_selector.take_selection(cx.waker())
+                else {
+                    return Poll::Pending;
+                };
+                if let Err(e) = this.dialogue_mut().set_selected_option(selected_option_id) {
+                    return Poll::Ready(Some(Err(e)));
+                }
+                this.is_waiting_for_option_selection = false;
+            }
+            match this.dialogue_mut().continue_() {
+                Ok(events) => this.pending_events.extend(events),
+                Err(e) => {
+                    this.is_finished = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
+
+impl<D: BorrowMut<Dialogue> + Unpin> FusedStream for DialogueEventStream<D> {
+    fn is_terminated(&self) -> bool {
+        self.is_finished && self.pending_events.is_empty()
+    }
+}
+
+impl<D: Debug> Debug for DialogueEventStream<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DialogueEventStream")
+            .field("dialogue", &self.dialogue)
+            .field("pending_events", &self.pending_events)
+            .field("option_selector", &self.option_selector)
+            .field(
+                "is_waiting_for_option_selection",
+                &self.is_waiting_for_option_selection,
+            )
+            .field("has_command_handler", &self.command_handler.is_some())
+            .field("is_running_command", &self.running_command.is_some())
+            .field("is_finished", &self.is_finished)
+            .finish()
+    }
+}
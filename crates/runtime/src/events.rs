@@ -20,6 +20,7 @@ use crate::prelude::*;
 /// ## Implementation note
 ///
 /// Corresponds to Yarn Spinner's `<EventName>Handler`s.
+#[non_exhaustive]
 pub enum DialogueEvent {
     /// A [`Line`] should be presented to the user.
     Line(Line),
@@ -44,6 +45,17 @@ pub enum DialogueEvent {
     ///
     /// Corresponds to Yarn Spinner's `PrepareForLinesHandler`
     LineHints(Vec<LineId>),
+    /// Only emitted by [`Dialogue::rollback`].
+    ///
+    /// The dialogue was rewound to an earlier checkpoint, restoring the variables and visit counts it had back then.
+    /// It is always followed by the [`DialogueEvent::Line`] or [`DialogueEvent::Options`] that was delivered at that checkpoint,
+    /// with the [`DialogueEvent::VariableChanged`]s of the restored variables in between.
+    /// The side effects of the commands that ran since are not undone, but listed in [`Rollback::undone_commands`] so that a caller can revert them.
+    Rollback(Rollback),
+    /// Only emitted if the [`VariableStorage`] is an [`ObservedVariableStorage`].
+    ///
+    /// A variable was changed, either by the dialogue itself, e.g. through `<<set>>` or [`Dialogue::rollback`], or by the host since the last call to [`Dialogue::continue_`].
+    VariableChanged(VariableChange),
     /// The dialogue was completed. Set it to a new node via [`Dialogue::set_node`] before calling [`Dialogue::continue_`] again.
     DialogueComplete,
 }
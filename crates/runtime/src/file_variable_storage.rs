@@ -0,0 +1,345 @@
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+use bevy_platform::collections::HashMap;
+use bevy_platform::sync::{Arc, Mutex};
+use core::any::Any;
+use core::time::Duration;
+use log::error;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// The format in which a [`FileVariableStorage`] reads and writes its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FileFormat {
+    /// [JSON](https://www.json.org/), e.g. `{ "$gold": { "Number": 10.0 } }`.
+    #[default]
+    Json,
+    /// [Rusty Object Notation](https://github.com/ron-rs/ron), e.g. `{ "$gold": Number(10.0) }`.
+    Ron,
+}
+
+/// A [`VariableStorage`] that keeps all variables in memory and persists them to a file, so that they survive crashes of the game.
+///
+/// Every write replaces the whole file atomically: the variables are written to a temporary file next to it,
+/// which is then renamed to the actual file, so that a crash in the middle of writing never leaves a truncated file behind.
+///
+/// By default, the file is written on every change. Since a single batch of dialogue can contain many `<<set>>`s,
+/// [`FileVariableStorage::with_debounce`] can be used to only write once no changes happened for a while.
+/// The host is then responsible for calling [`FileVariableStorage::flush_if_due`] regularly, e.g. once per frame.
+/// Pending changes are also written when the last shallow clone of the storage is dropped.
+#[derive(Debug, Clone)]
+pub struct FileVariableStorage(Arc<Mutex<FileState>>);
+
+#[derive(Debug)]
+struct FileState {
+    path: PathBuf,
+    format: FileFormat,
+    variables: HashMap<String, YarnValue>,
+    debounce: Option<Duration>,
+    /// When the variables were last changed if they were not written to the file yet.
+    pending_since: Option<Instant>,
+}
+
+impl FileVariableStorage {
+    /// Opens the file at the given path, reading the variables stored in it.
+    /// If the file doesn't exist yet, the storage starts out empty and the file is created on the first change.
+    pub fn open(path: impl Into<PathBuf>, format: FileFormat) -> Result<Self> {
+        let path = path.into();
+        let variables = match fs::read_to_string(&path) {
+            Ok(contents) => format.deserialize(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::default(),
+            Err(e) => return Err(internal_error(e)),
+        };
+        for name in variables.keys() {
+            MemoryVariableStorage::validate_name(name)?;
+        }
+        Ok(Self(Arc::new(Mutex::new(FileState {
+            path,
+            format,
+            variables,
+            debounce: None,
+            pending_since: None,
+        }))))
+    }
+
+    /// Only writes the file once no changes happened for the given duration, as checked by [`FileVariableStorage::flush_if_due`].
+    #[must_use]
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        self.0.lock().unwrap().debounce = Some(debounce);
+        self
+    }
+
+    /// The path of the file the variables are written to.
+    pub fn path(&self) -> PathBuf {
+        self.0.lock().unwrap().path.clone()
+    }
+
+    /// The format of the file the variables are written to.
+    pub fn format(&self) -> FileFormat {
+        self.0.lock().unwrap().format
+    }
+
+    /// Returns `true` if there are changes that were not written to the file yet.
+    pub fn has_pending_changes(&self) -> bool {
+        self.0.lock().unwrap().pending_since.is_some()
+    }
+
+    /// Writes pending changes to the file, regardless of the debounce duration.
+    pub fn flush(&self) -> Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+
+    /// Writes pending changes to the file if no changes happened for the debounce duration set by [`FileVariableStorage::with_debounce`].
+    pub fn flush_if_due(&self) -> Result<()> {
+        let mut state = self.0.lock().unwrap();
+        let debounce = state.debounce.unwrap_or_default();
+        match state.pending_since {
+            Some(pending_since) if pending_since.elapsed() >= debounce => state.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FileState {
+    fn changed(&mut self) -> Result<()> {
+        self.pending_since = Some(Instant::now());
+        if self.debounce.is_none() {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending_since.is_none() {
+            return Ok(());
+        }
+        let contents = self.format.serialize(&self.variables)?;
+        write_atomically(&self.path, &contents).map_err(internal_error)?;
+        self.pending_since = None;
+        Ok(())
+    }
+}
+
+impl Drop for FileState {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!(
+                "Failed to write pending variables to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl VariableStorage for FileVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+        MemoryVariableStorage::validate_name(&name)?;
+        let mut state = self.0.lock().unwrap();
+        state.variables.insert(name, value);
+        state.changed()
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue> {
+        MemoryVariableStorage::validate_name(name)?;
+        self.0
+            .lock()
+            .unwrap()
+            .variables
+            .get(name)
+            .cloned()
+            .ok_or_else(|| VariableStorageError::VariableNotFound {
+                name: name.to_owned(),
+            })
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+        for name in values.keys() {
+            MemoryVariableStorage::validate_name(name)?;
+        }
+        let mut state = self.0.lock().unwrap();
+        state.variables.extend(values);
+        state.changed()
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.0.lock().unwrap().variables.clone()
+    }
+
+    fn clear(&mut self) {
+        let mut state = self.0.lock().unwrap();
+        state.variables.clear();
+        if let Err(e) = state.changed() {
+            error!(
+                "Failed to write cleared variables to {}: {e}",
+                state.path.display()
+            );
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl FileFormat {
+    fn serialize(self, variables: &HashMap<String, YarnValue>) -> Result<String> {
+        // Sorted so that the file doesn't change needlessly between writes
+        let variables: BTreeMap<_, _> = variables.iter().collect();
+        match self {
+            FileFormat::Json => serde_json::to_string_pretty(&variables).map_err(internal_error),
+            FileFormat::Ron => {
+                ron::ser::to_string_pretty(&variables, ron::ser::PrettyConfig::default())
+                    .map_err(internal_error)
+            }
+        }
+    }
+
+    fn deserialize(self, contents: &str) -> Result<HashMap<String, YarnValue>> {
+        match self {
+            FileFormat::Json => serde_json::from_str(contents).map_err(internal_error),
+            FileFormat::Ron => ron::from_str(contents).map_err(internal_error),
+        }
+    }
+}
+
+/// Writes to a temporary file first and then renames it, which replaces the original file atomically on all major platforms.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut temporary_path = OsString::from(path.as_os_str());
+    temporary_path.push(".tmp");
+    let temporary_path = PathBuf::from(temporary_path);
+
+    let mut file = fs::File::create(&temporary_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temporary_path, path)
+}
+
+fn internal_error(error: impl core::error::Error + Send + Sync + 'static) -> VariableStorageError {
+    VariableStorageError::InternalError {
+        error: Box::new(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable_storage_conformance::assert_conforms;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A fresh directory for the files of a single test, removed by the test when it's done.
+    fn temporary_directory(test_name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("yarnspinner_{test_name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn conforms() {
+        let directory = temporary_directory("file_variable_storage_conforms");
+        let counter = AtomicUsize::new(0);
+        for format in [FileFormat::Json, FileFormat::Ron] {
+            assert_conforms(|| {
+                let index = counter.fetch_add(1, Ordering::Relaxed);
+                let path = directory.join(index.to_string());
+                Box::new(FileVariableStorage::open(path, format).unwrap())
+            });
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn variables_survive_reopening() {
+        let directory = temporary_directory("file_variable_storage_reopening");
+        for (format, file_name) in [
+            (FileFormat::Json, "variables.json"),
+            (FileFormat::Ron, "variables.ron"),
+        ] {
+            let path = directory.join(file_name);
+            let mut storage = FileVariableStorage::open(&path, format).unwrap();
+            storage.set("$gold".to_owned(), 10.0.into()).unwrap();
+            storage.set("$name".to_owned(), "Sally".into()).unwrap();
+            storage.set("$met".to_owned(), true.into()).unwrap();
+            let expected = storage.variables();
+
+            let reopened = FileVariableStorage::open(&path, format).unwrap();
+            assert_eq!(reopened.variables(), expected);
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn debounced_changes_are_written_when_due_or_dropped() {
+        let directory = temporary_directory("file_variable_storage_debounce");
+        let path = directory.join("variables.json");
+        let mut storage = FileVariableStorage::open(&path, FileFormat::Json)
+            .unwrap()
+            .with_debounce(Duration::from_secs(60));
+        storage.set("$gold".to_owned(), 10.0.into()).unwrap();
+        storage.flush_if_due().unwrap();
+        assert!(storage.has_pending_changes());
+        assert!(!path.exists());
+
+        storage.flush().unwrap();
+        assert!(!storage.has_pending_changes());
+        assert!(path.exists());
+
+        storage.set("$gold".to_owned(), 20.0.into()).unwrap();
+        drop(storage);
+        let reopened = FileVariableStorage::open(&path, FileFormat::Json).unwrap();
+        assert_eq!(reopened.get("$gold").unwrap(), YarnValue::Number(20.0));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn loading_a_program_keeps_saved_values() {
+        let directory = temporary_directory("file_variable_storage_program");
+        let path = directory.join("variables.json");
+        let mut storage = FileVariableStorage::open(&path, FileFormat::Json).unwrap();
+        storage.set("$gold".to_owned(), 10.0.into()).unwrap();
+        drop(storage);
+
+        let program = Program {
+            initial_values: [
+                ("$gold".to_owned(), 0.0.into()),
+                ("$met".to_owned(), false.into()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let reopened = FileVariableStorage::open(&path, FileFormat::Json).unwrap();
+        let mut dialogue =
+            Dialogue::new(Box::new(reopened), Box::new(StringTableTextProvider::new()));
+        dialogue.add_program(program.clone());
+        dialogue.replace_program(program);
+
+        let variable_storage = dialogue.variable_storage();
+        assert_eq!(
+            variable_storage.get("$gold").unwrap(),
+            YarnValue::Number(10.0)
+        );
+        assert_eq!(
+            variable_storage.get("$met").unwrap(),
+            YarnValue::Boolean(false)
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
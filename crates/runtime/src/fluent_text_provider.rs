@@ -0,0 +1,483 @@
+use crate::prelude::*;
+use alloc::sync::Arc;
+use core::any::Any;
+use core::convert::Infallible;
+use core::error::Error;
+use core::fmt::{self, Debug, Display};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::memoizer::MemoizerKind;
+use fluent_bundle::types::FluentNumberStyle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use intl_memoizer::Memoizable;
+use log::{debug, error, warn};
+use unic_langid::LanguageIdentifier;
+
+type Bundle = FluentBundle<Arc<FluentResource>>;
+
+/// A [`TextProvider`] that resolves lines to the messages of [Project Fluent](https://projectfluent.org/) resources (`.ftl` files),
+/// so that dialogue can use the same localization pipeline as the rest of a game, including Fluent's selectors and terms.
+///
+/// The text of a line is the value of the message named by [`LineId::to_fluent_message_id`], e.g. `line-abc` for `#line:abc`.
+/// The values of a line's inline expressions are passed to the message as the arguments `$arg0`, `$arg1`, etc.,
+/// with numbers being passed as Fluent numbers so that they can be used in selectors.
+/// Numbers are written with the separators of the language, following the `minimumFractionDigits`, `maximumFractionDigits`,
+/// `useGrouping` and `style: "percent"` options of Fluent's `NUMBER` function.
+///
+/// When a language is selected, messages are looked up in the resources of that language first, then in those of its [`Language::parents`],
+/// and finally in the base language strings added with [`FluentTextProvider::extend_base_language`].
+/// When no language is selected, only the base language strings are used.
+///
+/// ```
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::YarnValue;
+/// let mut text_provider = FluentTextProvider::new();
+/// text_provider
+///     .add_resource(
+///         "de",
+///         r#"
+/// line-apples = { $arg0 ->
+///     [one] Ich habe einen Apfel.
+///    *[other] Ich habe { $arg0 } Äpfel.
+/// }
+/// "#,
+///     )
+///     .unwrap();
+/// text_provider.set_language(Some("de-CH".into()));
+///
+/// let text = text_provider.get_text_with_substitutions(&"line:apples".into(), &[YarnValue::Number(3.0)]);
+/// assert_eq!(text.as_deref(), Some("Ich habe 3 Äpfel."));
+/// ```
+#[derive(Clone, Default)]
+pub struct FluentTextProvider {
+    base_language_table: StringTable,
+    resources: Vec<(Language, Arc<FluentResource>)>,
+    language: Option<Language>,
+    /// The bundles for [`FluentTextProvider::language`] and its parents, ordered from the most to the least specific.
+    bundles: Arc<Vec<(Language, Bundle)>>,
+}
+
+impl Debug for FluentTextProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FluentTextProvider")
+            .field("base_language_table", &self.base_language_table)
+            .field(
+                "resources",
+                &self
+                    .resources
+                    .iter()
+                    .map(|(language, _)| language)
+                    .collect::<Vec<_>>(),
+            )
+            .field("language", &self.language)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FluentTextProvider {
+    /// Creates a new [`FluentTextProvider`] without any resources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds strings for the base language, i.e. the language that the Yarn files are written in.
+    /// These are used for lines that have no message in the resources of the current language.
+    pub fn extend_base_language<T>(&mut self, string_table: impl IntoIterator<Item = T>)
+    where
+        StringTable: Extend<T>,
+    {
+        self.base_language_table.extend(string_table);
+    }
+
+    /// Parses `source` as the contents of an `.ftl` file and adds its messages and terms for `language`.
+    /// Messages of resources added later take precedence over messages with the same identifier added earlier.
+    ///
+    /// Fluent recovers from syntax errors by skipping the offending entries, so all valid entries are added even if an error is returned.
+    pub fn add_resource(
+        &mut self,
+        language: impl Into<Language>,
+        source: impl Into<String>,
+    ) -> core::result::Result<&mut Self, FluentResourceError> {
+        let language = language.into();
+        let (resource, errors) = match FluentResource::try_new(source.into()) {
+            Ok(resource) => (resource, Vec::new()),
+            Err((resource, errors)) => (resource, errors),
+        };
+        self.resources.push((language.clone(), Arc::new(resource)));
+        self.rebuild_bundles();
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(FluentResourceError {
+                language,
+                errors: errors.iter().map(ToString::to_string).collect(),
+            })
+        }
+    }
+
+    /// Removes all resources added with [`FluentTextProvider::add_resource`], e.g. to replace them with updated ones.
+    pub fn clear_resources(&mut self) {
+        self.resources.clear();
+        self.rebuild_bundles();
+    }
+
+    /// Returns the language whose resources contain the message for the given [`LineId`],
+    /// or `None` if the text is taken from the base language.
+    pub fn get_text_language(&self, id: &LineId) -> Option<Language> {
+        let message_id = id.to_fluent_message_id();
+        self.bundles
+            .iter()
+            .find(|(_, bundle)| has_message_value(bundle, &message_id))
+            .map(|(language, _)| language.clone())
+    }
+
+    fn format(&self, id: &LineId, args: Option<&FluentArgs>) -> Option<String> {
+        let message_id = id.to_fluent_message_id();
+        let (bundle, pattern) = self.bundles.iter().find_map(|(_, bundle)| {
+            let pattern = bundle.get_message(&message_id)?.value()?;
+            Some((bundle, pattern))
+        })?;
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        for error in errors {
+            warn!("Error while formatting Fluent message {message_id} for line {id}: {error}");
+        }
+        Some(text.into_owned())
+    }
+
+    fn rebuild_bundles(&mut self) {
+        let Some(language) = self.language.clone() else {
+            self.bundles = Default::default();
+            return;
+        };
+        let bundles = core::iter::once(language.clone())
+            .chain(language.parents())
+            .filter_map(|language| {
+                let resources: Vec<_> = self
+                    .resources
+                    .iter()
+                    .filter(|(resource_language, _)| *resource_language == language)
+                    .map(|(_, resource)| resource.clone())
+                    .collect();
+                if resources.is_empty() {
+                    return None;
+                }
+                let language_identifier = language
+                    .to_string()
+                    .parse()
+                    .inspect_err(|e| {
+                        error!("Failed to convert language {language} for Fluent: {e}")
+                    })
+                    .ok()?;
+                let mut bundle = Bundle::new_concurrent(vec![language_identifier]);
+                // Isolation marks would end up in the line text and its markup positions.
+                bundle.set_use_isolating(false);
+                bundle.set_formatter(Some(format_number));
+                bundle
+                    .add_builtins()
+                    .expect("A new bundle has no functions that could conflict with the builtins");
+                for resource in resources {
+                    bundle.add_resource_overriding(resource);
+                }
+                Some((language, bundle))
+            })
+            .collect();
+        self.bundles = Arc::new(bundles);
+    }
+}
+
+fn has_message_value(bundle: &Bundle, message_id: &str) -> bool {
+    bundle
+        .get_message(message_id)
+        .is_some_and(|message| message.value().is_some())
+}
+
+/// Formats numbers with the separators of the bundle's language, which fluent-rs itself ignores.
+/// Returns `None` for values and options that are left to fluent-rs, e.g. currencies.
+fn format_number<M: MemoizerKind>(value: &FluentValue, intls: &M) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+    let options = &number.options;
+    if options.style == FluentNumberStyle::Currency
+        || options.minimum_integer_digits.is_some()
+        || options.minimum_significant_digits.is_some()
+        || options.maximum_significant_digits.is_some()
+    {
+        return None;
+    }
+    let number_options = NumberOptions {
+        minimum_fraction_digits: options
+            .minimum_fraction_digits
+            .map_or(Ok(0), u8::try_from)
+            .ok()?,
+        maximum_fraction_digits: options
+            .maximum_fraction_digits
+            .map(u8::try_from)
+            .transpose()
+            .ok()?,
+        use_grouping: options.use_grouping,
+        percent: options.style == FluentNumberStyle::Percent,
+    };
+    intls
+        .with_try_get_threadsafe::<FluentNumberFormatter, _, _>((), |formatter| {
+            formatter
+                .0
+                .format_with_options(number.value, number_options)
+        })
+        .ok()
+}
+
+struct FluentNumberFormatter(NumberFormatter);
+
+impl Memoizable for FluentNumberFormatter {
+    type Args = ();
+    type Error = Infallible;
+
+    fn construct(
+        language: LanguageIdentifier,
+        _args: (),
+    ) -> core::result::Result<Self, Infallible> {
+        Ok(Self(NumberFormatter::new(Some(Language::new(
+            language.to_string(),
+        )))))
+    }
+}
+
+fn to_fluent_value(value: &YarnValue) -> FluentValue<'static> {
+    match value {
+        // Going through the string representation keeps e.g. `0.1` from turning into `0.10000000149011612`
+        YarnValue::Number(number) => FluentValue::from(
+            number
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(f64::from(*number)),
+        ),
+        YarnValue::String(string) => FluentValue::from(string.clone()),
+        YarnValue::Boolean(boolean) => FluentValue::from(boolean.to_string()),
+    }
+}
+
+impl TextProvider for FluentTextProvider {
+    fn clone_shallow(&self) -> Box<dyn TextProvider> {
+        Box::new(self.clone())
+    }
+
+    fn accept_line_hints(&mut self, _line_ids: &[LineId]) {
+        // no-op
+    }
+
+    fn get_text(&self, id: &LineId) -> Option<String> {
+        if let Some(text) = self.format(id, None) {
+            return Some(text);
+        }
+        if let Some(language) = self.language.as_ref() {
+            // Falling back is expected for lines that are not translated yet, so this is no error
+            debug!(
+                "No Fluent message {message_id} found for line {id} in language {language}, falling back to base language.",
+                message_id = id.to_fluent_message_id(),
+            );
+        }
+        self.base_language_table.get(id).cloned()
+    }
+
+    fn get_text_with_substitutions(
+        &self,
+        id: &LineId,
+        substitutions: &[YarnValue],
+    ) -> Option<String> {
+        let args: FluentArgs = substitutions
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (format!("arg{index}"), to_fluent_value(value)))
+            .collect();
+        self.format(id, Some(&args))
+    }
+
+    fn set_language(&mut self, language: Option<Language>) {
+        self.language = language;
+        self.rebuild_bundles();
+    }
+
+    fn get_language(&self) -> Option<Language> {
+        self.language.clone()
+    }
+
+    fn are_lines_available(&self) -> bool {
+        if self.language.is_none() {
+            !self.base_language_table.is_empty()
+        } else {
+            !self.bundles.is_empty()
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The errors Fluent reported while parsing a resource passed to [`FluentTextProvider::add_resource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FluentResourceError {
+    /// The language the resource was added for.
+    pub language: Language,
+    /// The descriptions of the syntax errors in the resource.
+    pub errors: Vec<String>,
+}
+
+impl Error for FluentResourceError {}
+
+impl Display for FluentResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse Fluent resource for language {}: {}",
+            self.language,
+            self.errors.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_provider() -> FluentTextProvider {
+        let mut text_provider = FluentTextProvider::new();
+        text_provider.extend_base_language([
+            (LineId::from("line:greeting"), "Hello!".to_owned()),
+            (LineId::from("line:gold"), "You have {0} gold.".to_owned()),
+            (LineId::from("line:only_base"), "Only in base.".to_owned()),
+        ]);
+        text_provider
+            .add_resource(
+                "de",
+                r#"
+-shop = Laden
+line-greeting = Willkommen im { -shop }!
+line-price = Das kostet { NUMBER($arg0, minimumFractionDigits: 2) } Gold.
+line-gold = { $arg0 ->
+    [one] Du hast ein Goldstück.
+   *[other] Du hast { $arg0 } Goldstücke.
+}
+"#,
+            )
+            .unwrap();
+        text_provider
+            .add_resource("de-CH", "line-greeting = Grüezi!")
+            .unwrap();
+        text_provider
+    }
+
+    #[test]
+    fn uses_base_language_without_language() {
+        let text_provider = text_provider();
+        assert_eq!(
+            text_provider.get_text(&"line:greeting".into()).as_deref(),
+            Some("Hello!")
+        );
+        assert_eq!(
+            text_provider.get_text_with_substitutions(&"line:gold".into(), &[1.0.into()]),
+            None
+        );
+    }
+
+    #[test]
+    fn resolves_terms_and_selectors() {
+        let mut text_provider = text_provider();
+        text_provider.set_language(Some("de".into()));
+        assert_eq!(
+            text_provider.get_text(&"line:greeting".into()).as_deref(),
+            Some("Willkommen im Laden!")
+        );
+        for (gold, expected) in [
+            (1.0, "Du hast ein Goldstück."),
+            (12.0, "Du hast 12 Goldstücke."),
+        ] {
+            assert_eq!(
+                text_provider
+                    .get_text_with_substitutions(&"line:gold".into(), &[gold.into()])
+                    .as_deref(),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn supports_builtin_functions() {
+        let mut text_provider = text_provider();
+        text_provider.set_language(Some("de".into()));
+        assert_eq!(
+            text_provider
+                .get_text_with_substitutions(&"line:price".into(), &[1.5.into()])
+                .as_deref(),
+            Some("Das kostet 1,50 Gold.")
+        );
+    }
+
+    #[test]
+    fn formats_numbers_for_the_language() {
+        let mut text_provider = FluentTextProvider::new();
+        text_provider
+            .add_resource(
+                "de",
+                r#"
+line-plain = { $arg0 }
+line-fixed = { NUMBER($arg0, minimumFractionDigits: 1, maximumFractionDigits: 1, useGrouping: "false") }
+line-percent = { NUMBER($arg0, maximumFractionDigits: 0, style: "percent") }
+"#,
+            )
+            .unwrap();
+        text_provider.set_language(Some("de".into()));
+        let format = |id: &str, value: f32| {
+            text_provider
+                .get_text_with_substitutions(&id.into(), &[value.into()])
+                .unwrap()
+        };
+        assert_eq!(format("line:plain", 1234.5), "1.234,5");
+        assert_eq!(format("line:plain", 0.1), "0,1");
+        assert_eq!(format("line:fixed", 1234.56), "1234,6");
+        assert_eq!(format("line:percent", 0.125), "13%");
+    }
+
+    #[test]
+    fn falls_back_to_parent_languages_and_base_language() {
+        let mut text_provider = text_provider();
+        text_provider.set_language(Some("de-CH".into()));
+        assert_eq!(
+            text_provider.get_text(&"line:greeting".into()).as_deref(),
+            Some("Grüezi!")
+        );
+        assert_eq!(
+            text_provider.get_text_language(&"line:gold".into()),
+            Some("de".into())
+        );
+        assert_eq!(
+            text_provider.get_text(&"line:only_base".into()).as_deref(),
+            Some("Only in base.")
+        );
+        assert_eq!(
+            text_provider.get_text_language(&"line:only_base".into()),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_but_keeps_valid_messages() {
+        let mut text_provider = FluentTextProvider::new();
+        let error = text_provider
+            .add_resource("fr", "line-a = Bonjour\nthis is not fluent\n")
+            .unwrap_err();
+        assert_eq!(error.language, Language::new("fr"));
+        assert_eq!(error.errors.len(), 1);
+
+        text_provider.set_language(Some("fr".into()));
+        assert_eq!(
+            text_provider.get_text(&"line:a".into()).as_deref(),
+            Some("Bonjour")
+        );
+    }
+}
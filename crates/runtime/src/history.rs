@@ -0,0 +1,155 @@
+use crate::prelude::*;
+use alloc::collections::VecDeque;
+
+/// A record of the lines shown and the options chosen in a [`Dialogue`], oldest first, e.g. for a backlog that the player can scroll through.
+/// Recording is opt-in and enabled by passing a history to [`Dialogue::set_history`].
+///
+/// Entries only store what is needed to produce a line again, not its text, so that they can be shown in whatever language is current
+/// when the backlog is opened. Use [`Dialogue::resolve_history`] to turn them into [`Line`]s.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DialogueHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+}
+
+/// A line that was shown or an option that was chosen, as recorded in a [`DialogueHistory`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct HistoryEntry {
+    /// The ID of the line, or of the option's line.
+    pub line_id: LineId,
+    /// The values of the inline expressions of the line at the time it was shown.
+    pub substitutions: Vec<YarnValue>,
+    /// The name of the character speaking the line in the language it was shown in, see [`Line::character_name`].
+    pub character: Option<String>,
+    /// The ID of the option if this entry is an option the player chose, or `None` if it is a line.
+    pub selected_option: Option<OptionId>,
+}
+
+/// A [`HistoryEntry`] whose line was produced again by [`Dialogue::resolve_history`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedHistoryEntry {
+    /// The line in the current language.
+    pub line: Line,
+    /// The ID of the option if this entry is an option the player chose, or `None` if it is a line.
+    pub selected_option: Option<OptionId>,
+}
+
+impl DialogueHistory {
+    /// Creates an empty history that keeps at most `capacity` entries, discarding the oldest ones when full.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The maximum number of entries this history keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of entries, discarding the oldest entries that no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity;
+        self.truncate();
+        self
+    }
+
+    /// The recorded entries, oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    /// The number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        line: &Line,
+        substitutions: Vec<YarnValue>,
+        selected_option: Option<OptionId>,
+    ) {
+        self.entries.push_back(HistoryEntry {
+            line_id: line.id.clone(),
+            substitutions,
+            character: line.character_name().map(ToOwned::to_owned),
+            selected_option,
+        });
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        self.entries.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(id: &str) -> Line {
+        Line {
+            id: id.into(),
+            text: String::new(),
+            attributes: Vec::new(),
+            is_seen: false,
+        }
+    }
+
+    fn ids(history: &DialogueHistory) -> Vec<&str> {
+        history
+            .entries()
+            .map(|entry| entry.line_id.0.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn discards_oldest_entries_when_full() {
+        let mut history = DialogueHistory::new(2);
+        for id in ["line:a", "line:b", "line:c"] {
+            history.record(&line(id), Vec::new(), None);
+        }
+        assert_eq!(ids(&history), ["line:b", "line:c"]);
+    }
+
+    #[test]
+    fn shrinking_capacity_keeps_newest_entries() {
+        let mut history = DialogueHistory::new(3);
+        for id in ["line:a", "line:b", "line:c"] {
+            history.record(&line(id), Vec::new(), None);
+        }
+        history.set_capacity(1);
+        assert_eq!(ids(&history), ["line:c"]);
+
+        history.set_capacity(0);
+        history.record(&line("line:d"), Vec::new(), None);
+        assert!(history.is_empty());
+    }
+}
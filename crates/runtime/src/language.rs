@@ -1,6 +1,7 @@
 use crate::prelude::*;
 use core::fmt::Display;
 use icu_locid::LanguageIdentifier;
+use icu_locid_transform::fallback::LocaleFallbacker;
 
 /// IETF BCP 47 code.
 /// The default is "en-US".
@@ -14,6 +15,28 @@ impl Language {
         let language = language.into();
         Self(language.parse().unwrap())
     }
+
+    /// Returns the parent locales of this language according to the [CLDR](https://cldr.unicode.org/),
+    /// ordered from the most to the least specific and excluding this language itself.
+    /// For example, the parents of `de-CH` are `[de]` and the parents of `es-AR` are `[es-419, es]`.
+    pub fn parents(&self) -> Vec<Language> {
+        let fallbacker = LocaleFallbacker::new();
+        let mut iterator = fallbacker
+            .for_config(Default::default())
+            .fallback_for((&self.0).into());
+        let mut parents = Vec::new();
+        loop {
+            iterator.step();
+            let locale = iterator.get();
+            if locale.is_und() {
+                break parents;
+            }
+            let language = Self(locale.get_langid());
+            if language != *self && !parents.contains(&language) {
+                parents.push(language);
+            }
+        }
+    }
 }
 
 impl Display for Language {
@@ -37,3 +60,38 @@ where
         Self::new(language)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_region_for_parent() {
+        assert_eq!(Language::new("de-CH").parents(), vec![Language::new("de")]);
+    }
+
+    #[test]
+    fn strips_variants_before_region() {
+        assert_eq!(
+            Language::new("de-CH-1996").parents(),
+            vec![
+                Language::new("de-CH"),
+                Language::new("de-1996"),
+                Language::new("de")
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_cldr_parent_locales() {
+        assert_eq!(
+            Language::new("es-AR").parents(),
+            vec![Language::new("es-419"), Language::new("es")]
+        );
+    }
+
+    #[test]
+    fn has_no_parents_without_subtags() {
+        assert!(Language::new("de").parents().is_empty());
+    }
+}
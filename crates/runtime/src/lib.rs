@@ -15,15 +15,33 @@ extern crate std;
 
 mod analyser;
 mod command;
+mod command_registry;
 mod dialogue;
 mod dialogue_option;
+mod event_iterator;
+#[cfg(feature = "async")]
+mod event_stream;
 mod events;
+#[cfg(feature = "file_storage")]
+mod file_variable_storage;
+#[cfg(feature = "fluent")]
+mod fluent_text_provider;
+mod history;
 mod language;
 mod line;
 pub mod markup;
+mod number_formatting;
+mod observed_variable_storage;
 mod pluralization;
+mod rollback;
+mod seen_lines;
+#[cfg(feature = "sqlite")]
+mod sqlite_variable_storage;
 mod text_provider;
+mod validating_variable_storage;
 mod variable_storage;
+#[cfg(test)]
+mod variable_storage_conformance;
 mod virtual_machine;
 
 pub use dialogue::Result;
@@ -40,19 +58,36 @@ pub mod prelude {
         vec::Vec,
     };
 
+    #[cfg(feature = "async")]
+    pub use crate::event_stream::*;
+    #[cfg(feature = "file_storage")]
+    pub use crate::file_variable_storage::*;
+    #[cfg(feature = "fluent")]
+    pub use crate::fluent_text_provider::*;
+    #[cfg(feature = "sqlite")]
+    pub use crate::sqlite_variable_storage::*;
     pub use crate::{
         analyser::*,
         command::*,
+        command_registry::*,
         dialogue::{Dialogue, DialogueError},
         dialogue_option::*,
+        event_iterator::*,
         events::*,
+        history::*,
         language::*,
         line::*,
-        markup::MarkupParseError,
+        markup::{AttributeMarkerProcessor, MarkupParseError},
+        observed_variable_storage::*,
+        rollback::Rollback,
+        seen_lines::*,
         text_provider::*,
+        validating_variable_storage::*,
         variable_storage::*,
     };
-    pub(crate) use crate::{pluralization::*, virtual_machine::*};
+    pub(crate) use crate::{
+        number_formatting::*, pluralization::*, rollback::RollbackJournal, virtual_machine::*,
+    };
     pub(crate) use yarnspinner_core::prelude::*;
     pub(crate) use yarnspinner_internal_shared::prelude::*;
 }
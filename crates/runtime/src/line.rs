@@ -4,9 +4,10 @@
 //! Introduced `LineId` newtype for better type safety
 
 use crate::markup::{
-    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, MarkupAttribute, MarkupValue,
+    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, MarkupAttribute, MarkupValue, TextUnit,
 };
 use crate::prelude::*;
+use core::ops::Range;
 
 /// A line of dialogue, sent from the [`Dialogue`] to the game.
 ///
@@ -16,6 +17,7 @@ use crate::prelude::*;
 /// - The text is parsed for markup
 ///
 /// You do not create instances of this struct yourself. They are created by the [`Dialogue`] during program execution.
+/// For tests, use [`Line::new`].
 ///
 /// ## See also
 /// [`DialogueEvent::Line`]
@@ -31,6 +33,7 @@ use crate::prelude::*;
     all(feature = "bevy", feature = "serde"),
     reflect(Serialize, Deserialize)
 )]
+#[non_exhaustive]
 pub struct Line {
     /// The ID of the line in the string table.
     pub id: LineId,
@@ -38,9 +41,22 @@ pub struct Line {
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
     pub attributes: Vec<MarkupAttribute>,
+    /// Whether the [`Dialogue`] delivered this line before, as recorded in its [`SeenLines`].
+    /// For the line of a [`DialogueOption`], this means that the option was presented before while [`DialogueOption::is_available`] was `true`.
+    pub is_seen: bool,
 }
 
 impl Line {
+    /// Creates a line that was not seen before, e.g. to test a dialogue view.
+    pub fn new(id: LineId, text: impl Into<String>, attributes: Vec<MarkupAttribute>) -> Self {
+        Self {
+            id,
+            text: text.into(),
+            attributes,
+            is_seen: false,
+        }
+    }
+
     /// Gets the first attribute with the specified name, if present.
     ///
     /// ## Implementation note
@@ -58,17 +74,17 @@ impl Line {
     /// # use yarnspinner_core::prelude::*;
     /// # use yarnspinner_runtime::markup::*;
     /// # use yarnspinner_runtime::prelude::*;
-    /// # let line = Line {
-    /// #    id: "line".into(),
-    /// #    text: "Alice: Hello! How are you today?".to_owned(),
-    /// #    attributes: vec![MarkupAttribute {
-    /// #        name: "character".to_owned(),
-    /// #        position: 0,
-    /// #        length: 7,
-    /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
-    /// #        source_position: 0,
-    /// #    }],
-    /// # };
+    /// # let line = Line::new(
+    /// #     "line".into(),
+    /// #     "Alice: Hello! How are you today?",
+    /// #     vec![MarkupAttribute {
+    /// #         name: "character".to_owned(),
+    /// #         position: 0,
+    /// #         length: 7,
+    /// #         properties: HashMap::from([("name".to_owned(), "Alice".into())]),
+    /// #         source_position: 0,
+    /// #     }],
+    /// # );
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
     /// ```
@@ -79,11 +95,11 @@ impl Line {
     /// # use yarnspinner_core::prelude::*;
     /// # use yarnspinner_runtime::markup::*;
     /// # use yarnspinner_runtime::prelude::*;
-    /// # let line = Line {
-    /// #    id: "line".into(),
-    /// #    text: "Great, thanks".to_owned(),
-    /// #    attributes: vec![],
-    /// # };
+    /// # let line = Line::new(
+    /// #     "line".into(),
+    /// #     "Great, thanks",
+    /// #     vec![],
+    /// # );
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
     pub fn character_name(&self) -> Option<&str> {
@@ -107,17 +123,17 @@ impl Line {
     /// # use yarnspinner_core::prelude::*;
     /// # use yarnspinner_runtime::markup::*;
     /// # use yarnspinner_runtime::prelude::*;
-    /// # let line = Line {
-    /// #    id: "line".into(),
-    /// #    text: "Alice: Hello! How are you today?".to_owned(),
-    /// #    attributes: vec![MarkupAttribute {
-    /// #        name: "character".to_owned(),
-    /// #        position: 0,
-    /// #        length: 7,
-    /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
-    /// #        source_position: 0,
-    /// #    }],
-    /// # };
+    /// # let line = Line::new(
+    /// #     "line".into(),
+    /// #     "Alice: Hello! How are you today?",
+    /// #     vec![MarkupAttribute {
+    /// #         name: "character".to_owned(),
+    /// #         position: 0,
+    /// #         length: 7,
+    /// #         properties: HashMap::from([("name".to_owned(), "Alice".into())]),
+    /// #         source_position: 0,
+    /// #     }],
+    /// # );
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
     /// ```
@@ -128,11 +144,11 @@ impl Line {
     /// # use yarnspinner_core::prelude::*;
     /// # use yarnspinner_runtime::markup::*;
     /// # use yarnspinner_runtime::prelude::*;
-    /// # let line = Line {
-    /// #    id: "line".into(),
-    /// #    text: "Great, thanks".to_owned(),
-    /// #    attributes: vec![],
-    /// # };
+    /// # let line = Line::new(
+    /// #     "line".into(),
+    /// #     "Great, thanks",
+    /// #     vec![],
+    /// # );
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
     pub fn text_without_character_name(&self) -> String {
@@ -144,14 +160,25 @@ impl Line {
     }
 
     /// Returns the substring of [`Line::text`] covered by the passed `attribute`s [`MarkupAttribute::position`] and [`MarkupAttribute::length`] fields.
+    ///
+    /// ## Panics
+    /// Panics if `attribute` does not fit into [`Line::text`].
     pub fn text_for_attribute(&self, attribute: &MarkupAttribute) -> &str {
-        assert!(
-            self.text.len() >= attribute.position + attribute.length,
-            "Attribute \"{attribute}\" represents a range not representable by this text: \"{}\". \
-        Does this MarkupAttribute belong to this MarkupParseResult?",
-            self.text
-        );
-        &self.text[attribute.position..attribute.position + attribute.length]
+        &self.text[self.attribute_range(attribute, TextUnit::Byte)]
+    }
+
+    /// Returns the range of [`Line::text`] covered by `attribute`, measured in `unit`. See [`MarkupAttribute::range_in`].
+    ///
+    /// ## Panics
+    /// Panics if `attribute` does not fit into [`Line::text`].
+    pub fn attribute_range(&self, attribute: &MarkupAttribute, unit: TextUnit) -> Range<usize> {
+        attribute.range_in(&self.text, unit).unwrap_or_else(|| {
+            panic!(
+                "Attribute \"{attribute}\" represents a range not representable by this text: \"{}\". \
+            Does this MarkupAttribute belong to this MarkupParseResult?",
+                self.text
+            )
+        })
     }
 
     /// Deletes an attribute from this markup.
@@ -198,13 +225,17 @@ impl Line {
                 id: self.id.clone(),
                 text: self.text.to_string(),
                 attributes,
+                is_seen: self.is_seen,
             };
         }
         let deletion_start = attribute_to_delete.position;
         let deletion_end = attribute_to_delete.position + attribute_to_delete.length;
         let edited_substring = {
             let mut text = self.text.to_string();
-            text.replace_range(deletion_start..deletion_end, "");
+            text.replace_range(
+                self.attribute_range(attribute_to_delete, TextUnit::Byte),
+                "",
+            );
             text
         };
         let attributes = self
@@ -273,6 +304,7 @@ impl Line {
             id: self.id.clone(),
             text: edited_substring,
             attributes,
+            is_seen: self.is_seen,
         }
     }
 }
@@ -10,8 +10,10 @@ mod line_parser;
 mod markup_parse_error;
 mod parsed_markup;
 
+pub use self::attribute_marker_processor::AttributeMarkerProcessor;
 pub use self::line_parser::{
-    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, Result, TRIM_WHITESPACE_PROPERTY,
+    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, REPLACEMENT_MARKER_CONTENTS, Result,
+    TRIM_WHITESPACE_PROPERTY,
 };
 pub(crate) use self::{attribute_marker_processor::*, line_parser::*};
 pub use self::{markup_parse_error::*, parsed_markup::*};
@@ -21,6 +23,7 @@ mod tests {
     //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Tests/MarkupTests.cs>
     use super::*;
     use crate::prelude::*;
+    use unicode_segmentation::UnicodeSegmentation;
 
     #[test]
     fn test_markup_parsing() {
@@ -359,6 +362,117 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_marker_processors() {
+        #[derive(Debug, Clone)]
+        struct ShoutProcessor(Option<Language>);
+
+        impl AttributeMarkerProcessor for ShoutProcessor {
+            fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String {
+                let text = match marker.property(REPLACEMENT_MARKER_CONTENTS) {
+                    Some(contents) => contents.to_string(),
+                    None => marker.property("text").unwrap().to_string(),
+                };
+                match &self.0 {
+                    Some(language) => format!("{}! ({language:?})", text.to_uppercase()),
+                    None => format!("{}!", text.to_uppercase()),
+                }
+            }
+
+            fn set_language_code(&mut self, language_code: Option<Language>) {
+                self.0 = language_code;
+            }
+
+            fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+                Box::new(self.clone())
+            }
+        }
+
+        let mut line_parser = line_parser();
+        line_parser.replace_marker_processor("shout", Box::new(ShoutProcessor(None)));
+
+        let markup = line_parser
+            .parse_markup("A [shout]b[/shout] [shout text=c/] [b]d[/b]")
+            .unwrap();
+        assert_eq!("A B! C! d", markup.text);
+        let b = markup.attributes.iter().find(|a| a.name == "b").unwrap();
+        assert_eq!(8, b.position);
+
+        line_parser.set_language_code(Language::new("en"));
+        let markup = line_parser.parse_markup("[shout]hi[/shout]").unwrap();
+        assert_eq!(format!("HI! ({:?})", Language::new("en")), markup.text);
+    }
+
+    #[test]
+    fn test_attribute_ranges_in_different_units() {
+        for (input, expected) in [
+            // Combining characters
+            ("[b]g̈ö[/b]!", "g̈ö"),
+            ("Hoa: Tiếng [b]Việt[/b] hay", "Việt"),
+            ("[b]नमस्ते[/b] दुनिया", "नमस्ते"),
+            // Right-to-left
+            ("[b]שָׁלוֹם[/b] עולם", "שָׁלוֹם"),
+            ("مرحبا [b]بالعالم[/b]!", "بالعالم"),
+            // Emoji sequences
+            ("👋🏽 [b]👨‍👩‍👧 ok[/b]", "👨‍👩‍👧 ok"),
+        ] {
+            let markup = line_parser().parse_markup(input).unwrap();
+            let line = markup.as_line();
+            let attribute = line.attribute("b").unwrap();
+            let expected = normalize(expected);
+            for unit in [TextUnit::Byte, TextUnit::Char, TextUnit::Grapheme] {
+                assert_eq!(
+                    line.attribute_range(attribute, unit),
+                    markup.attribute_range(attribute, unit),
+                    "{input}"
+                );
+            }
+
+            assert_eq!(expected, line.text_for_attribute(attribute), "{input}");
+            let bytes = line.attribute_range(attribute, TextUnit::Byte);
+            assert_eq!(expected, line.text[bytes], "{input}");
+            let chars = line.attribute_range(attribute, TextUnit::Char);
+            let text_by_chars: String = line
+                .text
+                .chars()
+                .skip(chars.start)
+                .take(chars.len())
+                .collect();
+            assert_eq!(expected, text_by_chars, "{input}");
+            let graphemes = line.attribute_range(attribute, TextUnit::Grapheme);
+            let text_by_graphemes: String = line
+                .text
+                .graphemes(true)
+                .skip(graphemes.start)
+                .take(graphemes.len())
+                .collect();
+            assert_eq!(expected, text_by_graphemes, "{input}");
+        }
+
+        let line = line_parser().parse_markup("[b]g̈ö[/b]!").unwrap().as_line();
+        let attribute = line.attribute("b").unwrap();
+        assert_eq!(0..2, line.attribute_range(attribute, TextUnit::Grapheme));
+        assert_eq!(0..3, line.attribute_range(attribute, TextUnit::Char));
+        assert_eq!(0..5, line.attribute_range(attribute, TextUnit::Byte));
+    }
+
+    #[test]
+    fn test_deleting_character_attribute_with_multibyte_name() {
+        let line = line_parser()
+            .parse_markup("Ngọc: xin [b]chào[/b]")
+            .unwrap()
+            .as_line();
+        assert_eq!(Some("Ngọc"), line.character_name());
+        assert_eq!(6, line.attribute(CHARACTER_ATTRIBUTE).unwrap().length);
+
+        let line = line.delete_range(line.attribute(CHARACTER_ATTRIBUTE).unwrap());
+        assert_eq!("xin chào", line.text);
+        assert_eq!(
+            "chào",
+            line.text_for_attribute(line.attribute("b").unwrap())
+        );
+    }
+
     fn line_parser() -> LineParser {
         let dialogue_text_processor = Box::new(DialogueTextProcessor::new());
 
@@ -394,6 +508,7 @@ mod tests {
                 id: "test".into(),
                 text: self.text.clone(),
                 attributes: self.attributes.clone(),
+                is_seen: false,
             }
         }
     }
@@ -9,15 +9,52 @@ mod dialogue_text_processor;
 mod no_markup_text_processor;
 
 /// Provides a mechanism for producing replacement text for a marker.
-pub(crate) trait AttributeMarkerProcessor: Debug + Send + Sync {
+///
+/// Register an implementation with [`Dialogue::add_marker_processor`] to have all markers with a given name
+/// replaced by text of your choosing. This is how the built-in `select`, `plural`, `ordinal` and `nomarkup` markers are implemented.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_runtime::markup::*;
+/// /// Replaces `[item id=sword/]` with the name of the item in the current language.
+/// #[derive(Debug, Clone, Default)]
+/// struct ItemNameProcessor {
+///     language: Option<Language>,
+/// }
+///
+/// impl AttributeMarkerProcessor for ItemNameProcessor {
+///     fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String {
+///         let id = marker.property("id").map(|id| id.to_string()).unwrap_or_default();
+///         if id == "sword" && self.language == Some(Language::new("de-CH")) {
+///             "Schwert".to_owned()
+///         } else {
+///             id
+///         }
+///     }
+///
+///     fn set_language_code(&mut self, language_code: Option<Language>) {
+///         self.language = language_code;
+///     }
+///
+///     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+///         Box::new(self.clone())
+///     }
+/// }
+/// ```
+pub trait AttributeMarkerProcessor: Debug + Send + Sync {
     /// Produces the replacement text that should be inserted into a parse
     /// result for a given attribute.
     ///
     /// If the marker is an `open` marker, the text from the marker's
     /// position to its corresponding closing marker is provided as a string
-    /// property called `contents`.
+    /// property called [`REPLACEMENT_MARKER_CONTENTS`](crate::markup::REPLACEMENT_MARKER_CONTENTS), i.e. `contents`.
     fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String;
-    fn set_language_code(&mut self, language_code: Option<Language>);
+    /// Called with the language of the [`Dialogue`] when the processor is registered and whenever [`Dialogue::set_language_code`] is called.
+    /// A value of `None` means that the base language is used. Does nothing by default.
+    fn set_language_code(&mut self, _language_code: Option<Language>) {}
+    /// Clones the processor into a new trait object. Usually implemented as `Box::new(self.clone())`.
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor>;
 }
 
@@ -27,10 +27,6 @@ impl AttributeMarkerProcessor for NoMarkupTextProcessor {
         }
     }
 
-    fn set_language_code(&mut self, _language_code: Option<Language>) {
-        // no-op
-    }
-
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
         Box::new(self.clone())
     }
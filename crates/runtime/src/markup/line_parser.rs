@@ -85,6 +85,17 @@ impl LineParser {
         self
     }
 
+    /// Like [`LineParser::register_marker_processor`], but replaces any processor that was previously registered for `attribute_name`
+    /// instead of treating this as a bug. Returns the replaced processor.
+    pub(crate) fn replace_marker_processor(
+        &mut self,
+        attribute_name: impl Into<String>,
+        processor: Box<dyn AttributeMarkerProcessor>,
+    ) -> Option<Box<dyn AttributeMarkerProcessor>> {
+        self.marker_processors
+            .insert(attribute_name.into(), processor)
+    }
+
     /// Parses a line of text, and produces a [`ParsedMarkup`] containing the processed text
     ///
     /// ## Implementation notes
@@ -202,7 +213,8 @@ impl LineParser {
         let character_attribute = MarkupAttribute {
             name: CHARACTER_ATTRIBUTE.to_string(),
             position: 0,
-            length: match_.end(),
+            // Attributes are measured in graphemes, but regex matches in bytes
+            length: self.input[..match_.end()].graphemes(true).count(),
             properties: HashMap::from([(
                 CHARACTER_ATTRIBUTE_NAME_PROPERTY.to_string(),
                 character_name.into(),
@@ -730,7 +742,8 @@ pub(crate) fn normalize(string: &str) -> String {
 }
 
 /// The name of the property in replacement attributes that contains the text of the attribute.
-pub(crate) const REPLACEMENT_MARKER_CONTENTS: &str = "contents";
+/// It is passed to [`AttributeMarkerProcessor::replacement_text_for_marker`] for open markers like `[shout]hello[/shout]`.
+pub const REPLACEMENT_MARKER_CONTENTS: &str = "contents";
 
 /// The name of the implicitly-generated `character` attribute.
 pub const CHARACTER_ATTRIBUTE: &str = "character";
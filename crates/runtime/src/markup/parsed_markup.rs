@@ -1,14 +1,17 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/MarkupParseResult.cs>
 
-pub use self::{markup_attribute::*, markup_value::*};
-pub(crate) use self::{markup_attribute_marker::*, tag_type::*};
+pub use self::{
+    markup_attribute::*, markup_attribute_marker::*, markup_value::*, tag_type::*, text_unit::*,
+};
 use crate::prelude::*;
 use core::fmt::Debug;
+use core::ops::Range;
 
 mod markup_attribute;
 mod markup_attribute_marker;
 mod markup_value;
 mod tag_type;
+mod text_unit;
 
 /// The result of parsing a line of marked-up text.
 ///
@@ -17,11 +20,11 @@ mod tag_type;
 ///
 /// ## Implementation Notes
 /// - This is called `MarkupParseResult` in the original C# code, but was renamed because [`Result`] already carries meaning in Rust.
-/// - The API has been merged with [`Line`], so this is now only an internal type.
+/// - The API has been merged with [`Line`], which is what the [`Dialogue`] hands out. This type only keeps the accessors for positions.
 
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub(crate) struct ParsedMarkup {
+pub struct ParsedMarkup {
     /// The original text, with all parsed markers removed.
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
@@ -32,4 +35,20 @@ impl ParsedMarkup {
     pub(crate) fn new() -> Self {
         Self::default()
     }
+
+    /// Returns the range of [`ParsedMarkup::text`] covered by `attribute`, measured in `unit`.
+    /// [`MarkupAttribute::position`] and [`MarkupAttribute::length`] count graphemes, so use this to get byte or char ranges.
+    /// See [`MarkupAttribute::range_in`].
+    ///
+    /// ## Panics
+    /// Panics if `attribute` does not fit into [`ParsedMarkup::text`].
+    pub fn attribute_range(&self, attribute: &MarkupAttribute, unit: TextUnit) -> Range<usize> {
+        attribute.range_in(&self.text, unit).unwrap_or_else(|| {
+            panic!(
+                "Attribute \"{attribute}\" represents a range not representable by this text: \"{}\". \
+            Does this MarkupAttribute belong to this ParsedMarkup?",
+                self.text
+            )
+        })
+    }
 }
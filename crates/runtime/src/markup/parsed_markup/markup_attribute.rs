@@ -1,10 +1,11 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/MarkupParseResult.cs>
 //! which was split into multiple files.
 
-use crate::markup::{MarkupAttributeMarker, MarkupValue};
+use crate::markup::{MarkupAttributeMarker, MarkupValue, TextUnit};
 use crate::prelude::*;
 use bevy_platform::collections::HashMap;
 use core::fmt::Display;
+use core::ops::Range;
 
 /// Represents a range of text in a marked-up string.
 ///
@@ -24,9 +25,10 @@ use core::fmt::Display;
 pub struct MarkupAttribute {
     /// The name of the attribute.
     pub name: String,
-    /// The position in the plain text where this attribute begins.
+    /// The position in the plain text where this attribute begins, measured in [`TextUnit::Grapheme`]s.
+    /// See [`MarkupAttribute::range_in`] for other units.
     pub position: usize,
-    /// The number of text elements in the plain text that this attribute covers.
+    /// The number of text elements, i.e. [`TextUnit::Grapheme`]s, in the plain text that this attribute covers.
     pub length: usize,
     /// The properties associated with this attribute.
     pub properties: HashMap<String, MarkupValue>,
@@ -50,6 +52,32 @@ impl MarkupAttribute {
     pub fn property(&self, name: &str) -> Option<&MarkupValue> {
         self.properties.get(name)
     }
+
+    /// Returns the range of `text` covered by this attribute, measured in `unit`.
+    /// `text` must be the plain text this attribute belongs to, e.g. [`Line::text`].
+    /// Returns `None` if the attribute does not fit into `text`, which means it belongs to another text.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy_platform::collections::HashMap;
+    /// # use yarnspinner_runtime::markup::*;
+    /// // "g̈" is a "g" followed by a combining diaeresis, so it is one grapheme, but two chars and three bytes
+    /// let text = "g̈ox";
+    /// let attribute = MarkupAttribute {
+    ///     name: "b".to_owned(),
+    ///     position: 1,
+    ///     length: 1,
+    ///     properties: HashMap::default(),
+    ///     source_position: 0,
+    /// };
+    /// assert_eq!(Some(1..2), attribute.range_in(text, TextUnit::Grapheme));
+    /// assert_eq!(Some(2..3), attribute.range_in(text, TextUnit::Char));
+    /// assert_eq!(Some(3..4), attribute.range_in(text, TextUnit::Byte));
+    /// ```
+    pub fn range_in(&self, text: &str, unit: TextUnit) -> Option<Range<usize>> {
+        unit.convert_grapheme_range(text, self.position..self.position + self.length)
+    }
 }
 
 impl Display for MarkupAttribute {
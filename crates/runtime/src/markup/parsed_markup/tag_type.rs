@@ -3,7 +3,7 @@
 
 /// A type of [`MarkupAttributeMarker`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub(crate) enum TagType {
+pub enum TagType {
     /// An open marker. For example, `[a]`.
     Open,
     /// A closing marker. For example, `[/a]`.
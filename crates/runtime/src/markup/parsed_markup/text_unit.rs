@@ -0,0 +1,54 @@
+use core::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A unit in which positions in a text can be measured.
+///
+/// [`MarkupAttribute::position`](crate::markup::MarkupAttribute::position) and [`MarkupAttribute::length`](crate::markup::MarkupAttribute::length)
+/// are always measured in [`TextUnit::Grapheme`]s. Use [`MarkupAttribute::range_in`](crate::markup::MarkupAttribute::range_in) to convert them
+/// into another unit, e.g. to slice a [`String`] or to pass them to a text renderer that counts [`char`]s.
+///
+/// All units count in logical order, i.e. the order in which the text is stored, which is not necessarily the order in which it is displayed.
+/// For right-to-left scripts such as Arabic or Hebrew, position `0` is the rightmost visible character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextUnit {
+    /// Extended grapheme clusters, i.e. what a reader perceives as a single character.
+    /// For example, `é` written as `e` followed by a combining accent, or the emoji `👋🏽`, are a single grapheme each.
+    Grapheme,
+    /// Unicode scalar values, i.e. Rust [`char`]s.
+    Char,
+    /// Bytes of the UTF-8 encoding, as used for indexing a Rust [`str`].
+    Byte,
+}
+
+impl TextUnit {
+    /// Converts `graphemes`, a range of graphemes in `text`, into a range measured in this unit.
+    /// Returns `None` if the range does not fit into `text`.
+    pub(crate) fn convert_grapheme_range(
+        self,
+        text: &str,
+        graphemes: Range<usize>,
+    ) -> Option<Range<usize>> {
+        if graphemes.start > graphemes.end {
+            return None;
+        }
+        match self {
+            TextUnit::Grapheme => {
+                (graphemes.end <= text.graphemes(true).count()).then_some(graphemes)
+            }
+            TextUnit::Byte => {
+                let byte_index = |grapheme_index: usize| {
+                    text.grapheme_indices(true)
+                        .map(|(byte_index, _)| byte_index)
+                        .chain([text.len()])
+                        .nth(grapheme_index)
+                };
+                Some(byte_index(graphemes.start)?..byte_index(graphemes.end)?)
+            }
+            TextUnit::Char => {
+                let bytes = TextUnit::Byte.convert_grapheme_range(text, graphemes)?;
+                let start = text[..bytes.start].chars().count();
+                Some(start..start + text[bytes].chars().count())
+            }
+        }
+    }
+}
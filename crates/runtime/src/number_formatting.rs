@@ -0,0 +1,135 @@
+use crate::prelude::*;
+use core::str::FromStr;
+use fixed_decimal::FixedDecimal;
+use icu_decimal::FixedDecimalFormatter;
+use icu_decimal::options::{FixedDecimalFormatterOptions, GroupingStrategy};
+
+/// Formats numbers that are inserted into lines according to the grouping and decimal separators of a language.
+#[derive(Debug)]
+pub(crate) struct NumberFormatter {
+    grouping_formatter: FixedDecimalFormatter,
+    non_grouping_formatter: FixedDecimalFormatter,
+}
+
+impl NumberFormatter {
+    /// Uses the root locale for [`FormatSpecifier`]s if no language is given.
+    pub(crate) fn new(language: Option<Language>) -> Self {
+        let locale = language
+            .as_ref()
+            .map(|language| (&language.0).into())
+            .unwrap_or_default();
+        let formatter = |grouping_strategy| {
+            let mut options = FixedDecimalFormatterOptions::default();
+            options.grouping_strategy = grouping_strategy;
+            FixedDecimalFormatter::try_new(&locale, options).unwrap()
+        };
+        Self {
+            grouping_formatter: formatter(GroupingStrategy::Auto),
+            non_grouping_formatter: formatter(GroupingStrategy::Never),
+        }
+    }
+
+    /// Formats `value` as specified by `specifier`. Without a specifier, the number is written just like [`YarnValue::to_string`] does,
+    /// so that only lines opting into it with a specifier get the separators of the language.
+    pub(crate) fn format(&self, value: f32, specifier: Option<FormatSpecifier>) -> String {
+        let Some(specifier) = specifier else {
+            return value.to_string();
+        };
+        // Going through the string representation of the `f32` gives us the shortest decimal that round-trips,
+        // e.g. `0.1` instead of `0.100000001490116`.
+        let Ok(decimal) = FixedDecimal::from_str(&value.to_string()) else {
+            // NaN or infinity
+            return value.to_string();
+        };
+        let precision = specifier.precision_or_default();
+        self.format_decimal(
+            decimal,
+            NumberOptions {
+                minimum_fraction_digits: precision,
+                maximum_fraction_digits: Some(precision),
+                use_grouping: specifier.kind != FormatKind::FixedPoint,
+                percent: specifier.kind == FormatKind::Percent,
+            },
+        )
+    }
+
+    /// Formats `value` as described by `options`, for callers whose options do not come from a [`FormatSpecifier`].
+    #[cfg(feature = "fluent")]
+    pub(crate) fn format_with_options(&self, value: f64, options: NumberOptions) -> String {
+        let Ok(decimal) = FixedDecimal::from_str(&value.to_string()) else {
+            // NaN or infinity
+            return value.to_string();
+        };
+        self.format_decimal(decimal, options)
+    }
+
+    fn format_decimal(&self, mut decimal: FixedDecimal, options: NumberOptions) -> String {
+        if options.percent {
+            decimal.multiply_pow10(2);
+            decimal.trim_start();
+        }
+        if let Some(maximum_fraction_digits) = options.maximum_fraction_digits {
+            decimal.half_expand(-i16::from(maximum_fraction_digits));
+            decimal.trim_end();
+        }
+        decimal.pad_end(-i16::from(options.minimum_fraction_digits));
+        let formatter = if options.use_grouping {
+            &self.grouping_formatter
+        } else {
+            &self.non_grouping_formatter
+        };
+        let formatted = formatter.format(&decimal).to_string();
+        if options.percent {
+            format!("{formatted}%")
+        } else {
+            formatted
+        }
+    }
+}
+
+/// How [`NumberFormatter`] writes a number, mirroring the options of a [`FormatSpecifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NumberOptions {
+    /// Pads the decimals with zeros up to this many.
+    pub(crate) minimum_fraction_digits: u8,
+    /// Rounds half away from zero to this many decimals, if set.
+    pub(crate) maximum_fraction_digits: Option<u8>,
+    /// Separates groups of digits, e.g. thousands.
+    pub(crate) use_grouping: bool,
+    /// Multiplies the number by 100 and appends `%`.
+    pub(crate) percent: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(language: Option<&str>, value: f32, specifier: Option<&str>) -> String {
+        let formatter = NumberFormatter::new(language.map(Language::new));
+        let specifier = specifier.map(|specifier| specifier.parse().unwrap());
+        formatter.format(value, specifier)
+    }
+
+    #[test]
+    fn formats_without_specifier_like_yarn_values() {
+        assert_eq!(format(None, 1234.5, None), "1234.5");
+        assert_eq!(format(Some("en-US"), 1234.5, None), "1234.5");
+        assert_eq!(format(Some("de-DE"), 0.1, None), "0.1");
+        assert_eq!(format(Some("en-US"), -42.0, None), "-42");
+    }
+
+    #[test]
+    fn formats_with_specifier() {
+        assert_eq!(format(Some("en-US"), 1234.5, Some("N0")), "1,235");
+        assert_eq!(format(Some("en-US"), 1234.5, Some("N")), "1,234.50");
+        assert_eq!(format(Some("de-DE"), 1234.5, Some("F1")), "1234,5");
+        assert_eq!(format(Some("en-US"), 0.125, Some("P1")), "12.5%");
+        assert_eq!(format(None, 1234.5, Some("N1")), "1,234.5");
+    }
+
+    #[test]
+    fn formats_special_values_without_panicking() {
+        assert_eq!(format(Some("en-US"), f32::NAN, Some("N0")), "NaN");
+        assert_eq!(format(Some("en-US"), f32::INFINITY, None), "inf");
+    }
+}
@@ -0,0 +1,176 @@
+use crate::prelude::*;
+use bevy_platform::collections::HashMap;
+use bevy_platform::sync::{Arc, RwLock};
+use core::any::Any;
+
+/// A change of a variable, recorded by an [`ObservedVariableStorage`] and delivered as [`DialogueEvent::VariableChanged`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct VariableChange {
+    /// The name of the variable, including the leading `$`.
+    pub name: String,
+    /// The value before the change, or `None` if the variable was not set.
+    pub old_value: Option<YarnValue>,
+    /// The value after the change, or `None` if the variable was removed by [`VariableStorage::clear`].
+    pub new_value: Option<YarnValue>,
+}
+
+/// A [`VariableStorage`] that wraps another one and records every change made to it,
+/// e.g. for a HUD that shows variables modified by `<<set>>` without polling [`VariableStorage::variables`] every frame.
+///
+/// Pass it to [`Dialogue::new`] to receive the changes as [`DialogueEvent::VariableChanged`]s from [`Dialogue::continue_`].
+/// Since all shallow clones share the same record, this includes changes made by the host through [`Dialogue::variable_storage_mut`]
+/// or through a clone of the storage kept around. Changes made since the last [`Dialogue::continue_`] are delivered first in the next batch of events.
+///
+/// Writes that don't change a variable's value are not recorded. Note that the variables used to track visits of nodes,
+/// whose names start with `$Yarn.Internal.`, are recorded as well.
+#[derive(Debug, Clone)]
+pub struct ObservedVariableStorage {
+    inner: Box<dyn VariableStorage>,
+    changes: Arc<RwLock<Vec<VariableChange>>>,
+}
+
+impl ObservedVariableStorage {
+    /// Wraps the given [`VariableStorage`]. Changes made to it directly, bypassing this wrapper, are not recorded.
+    pub fn new(inner: Box<dyn VariableStorage>) -> Self {
+        Self {
+            inner,
+            changes: Default::default(),
+        }
+    }
+
+    /// Gets the wrapped [`VariableStorage`].
+    pub fn inner(&self) -> &dyn VariableStorage {
+        self.inner.as_ref()
+    }
+
+    fn record(&self, name: String, old_value: Option<YarnValue>, new_value: Option<YarnValue>) {
+        if old_value != new_value {
+            self.changes.write().unwrap().push(VariableChange {
+                name,
+                old_value,
+                new_value,
+            });
+        }
+    }
+}
+
+impl VariableStorage for ObservedVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+        let old_value = self.inner.get(&name).ok();
+        self.inner.set(name.clone(), value.clone())?;
+        self.record(name, old_value, Some(value));
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue> {
+        self.inner.get(name)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.inner.contains(name)
+    }
+
+    fn is_persistent(&self) -> bool {
+        self.inner.is_persistent()
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+        let old_values: Vec<_> = values
+            .iter()
+            .map(|(name, value)| (name.clone(), self.inner.get(name).ok(), value.clone()))
+            .collect();
+        VariableStorage::extend(self.inner.as_mut(), values)?;
+        for (name, old_value, new_value) in old_values {
+            self.record(name, old_value, Some(new_value));
+        }
+        Ok(())
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.inner.variables()
+    }
+
+    fn clear(&mut self) {
+        let old_values = self.inner.variables();
+        self.inner.clear();
+        for (name, old_value) in old_values {
+            self.record(name, Some(old_value), None);
+        }
+    }
+
+    /// Removes and returns the changes recorded so far, oldest first.
+    /// This is done automatically by [`Dialogue::continue_`] when the storage is used by a [`Dialogue`].
+    fn take_changes(&self) -> Vec<VariableChange> {
+        core::mem::take(&mut *self.changes.write().unwrap())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable_storage_conformance::assert_conforms;
+
+    #[test]
+    fn conforms() {
+        assert_conforms(|| {
+            Box::new(ObservedVariableStorage::new(Box::new(
+                MemoryVariableStorage::new(),
+            )))
+        });
+    }
+
+    #[test]
+    fn records_changes_made_through_shallow_clones() {
+        let mut storage = ObservedVariableStorage::new(Box::new(MemoryVariableStorage::new()));
+        let mut clone = storage.clone_shallow();
+        storage.set("$gold".to_owned(), 10.0.into()).unwrap();
+        clone.set("$gold".to_owned(), 10.0.into()).unwrap();
+        clone.set("$gold".to_owned(), 15.0.into()).unwrap();
+
+        assert_eq!(
+            storage.take_changes(),
+            [
+                VariableChange {
+                    name: "$gold".to_owned(),
+                    old_value: None,
+                    new_value: Some(10.0.into()),
+                },
+                VariableChange {
+                    name: "$gold".to_owned(),
+                    old_value: Some(10.0.into()),
+                    new_value: Some(15.0.into()),
+                },
+            ]
+        );
+        assert!(storage.take_changes().is_empty());
+
+        clone.clear();
+        assert_eq!(
+            storage.take_changes(),
+            [VariableChange {
+                name: "$gold".to_owned(),
+                old_value: Some(15.0.into()),
+                new_value: None,
+            }]
+        );
+    }
+}
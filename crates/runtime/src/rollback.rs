@@ -0,0 +1,232 @@
+use crate::prelude::*;
+use alloc::collections::VecDeque;
+
+/// Information about a rollback performed by [`Dialogue::rollback`], delivered as [`DialogueEvent::Rollback`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct Rollback {
+    /// The number of checkpoints that were rewound.
+    pub steps: usize,
+    /// The name of the node the dialogue is in after the rollback.
+    pub node_name: String,
+    /// The commands that ran after the restored checkpoint, in the order they ran.
+    /// Their side effects are not undone by the [`Dialogue`], so a caller may want to revert them itself.
+    pub undone_commands: Vec<Command>,
+}
+
+/// Records checkpoints of the virtual machine together with the variable writes and commands that happened since each of them,
+/// so that they can be undone by [`Dialogue::rollback`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollbackJournal {
+    depth: usize,
+    checkpoints_on_lines: bool,
+    checkpoints: VecDeque<Checkpoint>,
+    /// Whether the dialogue is still paused at the latest checkpoint, in which case it doesn't count as a step to roll back to.
+    is_at_latest_checkpoint: bool,
+}
+
+/// A variable name and the value it had before it was written, or `None` if it wasn't set yet.
+pub(crate) type VariableWrite = (String, Option<YarnValue>);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpoint {
+    pub(crate) node_name: String,
+    pub(crate) state: State,
+    /// The [`DialogueEvent::Line`] or [`DialogueEvent::Options`] that was delivered when the checkpoint was taken.
+    pub(crate) event: DialogueEvent,
+    /// The variables written since this checkpoint, with the value they had before their first write.
+    /// Since every variable appears at most once, the order in which they are undone doesn't matter.
+    variable_writes: Vec<VariableWrite>,
+    commands: Vec<Command>,
+}
+
+impl RollbackJournal {
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub(crate) fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.truncate();
+    }
+
+    pub(crate) fn checkpoints_on_lines(&self) -> bool {
+        self.checkpoints_on_lines
+    }
+
+    pub(crate) fn set_checkpoints_on_lines(&mut self, enabled: bool) {
+        self.checkpoints_on_lines = enabled;
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.depth > 0
+    }
+
+    /// The number of steps [`RollbackJournal::rewind`] can go back.
+    pub(crate) fn available(&self) -> usize {
+        let available = if self.is_at_latest_checkpoint {
+            self.checkpoints.len().saturating_sub(1)
+        } else {
+            self.checkpoints.len()
+        };
+        available.min(self.depth)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.checkpoints.clear();
+        self.is_at_latest_checkpoint = false;
+    }
+
+    pub(crate) fn checkpoint(&mut self, node_name: String, state: State, event: DialogueEvent) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.checkpoints.push_back(Checkpoint {
+            node_name,
+            state,
+            event,
+            variable_writes: Vec::new(),
+            commands: Vec::new(),
+        });
+        self.is_at_latest_checkpoint = true;
+        self.truncate();
+    }
+
+    /// Called when the dialogue moves on from the latest checkpoint.
+    pub(crate) fn leave_checkpoint(&mut self) {
+        self.is_at_latest_checkpoint = false;
+    }
+
+    pub(crate) fn record_variable_write(&mut self, name: &str, previous_value: Option<YarnValue>) {
+        let Some(checkpoint) = self.checkpoints.back_mut() else {
+            return;
+        };
+        if checkpoint
+            .variable_writes
+            .iter()
+            .all(|(written_name, _)| written_name != name)
+        {
+            checkpoint
+                .variable_writes
+                .push((name.to_owned(), previous_value));
+        }
+    }
+
+    pub(crate) fn record_command(&mut self, command: &Command) {
+        if let Some(checkpoint) = self.checkpoints.back_mut() {
+            checkpoint.commands.push(command.clone());
+        }
+    }
+
+    /// Removes everything recorded after the checkpoint `steps` steps back, which must be at most [`RollbackJournal::available`].
+    /// Returns that checkpoint, the variable writes to undo in the order they must be undone, and the commands that ran since.
+    pub(crate) fn rewind(
+        &mut self,
+        steps: usize,
+    ) -> (Checkpoint, Vec<VariableWrite>, Vec<Command>) {
+        debug_assert!(steps > 0 && steps <= self.available());
+        let target = self.checkpoints.len() - steps - usize::from(self.is_at_latest_checkpoint);
+        let mut rewound: Vec<_> = self.checkpoints.drain(target + 1..).collect();
+        let checkpoint = self.checkpoints.back_mut().unwrap();
+        let variable_writes = core::iter::once(&mut *checkpoint)
+            .chain(&mut rewound)
+            .rev()
+            .flat_map(|checkpoint| core::mem::take(&mut checkpoint.variable_writes))
+            .collect();
+        let commands = core::iter::once(&mut *checkpoint)
+            .chain(&mut rewound)
+            .flat_map(|checkpoint| core::mem::take(&mut checkpoint.commands))
+            .collect();
+        self.is_at_latest_checkpoint = true;
+        (checkpoint.clone(), variable_writes, commands)
+    }
+
+    fn truncate(&mut self) {
+        // One more checkpoint than the depth is kept since the dialogue is usually paused at the latest one
+        let capacity = if self.is_enabled() { self.depth + 1 } else { 0 };
+        let excess = self.checkpoints.len().saturating_sub(capacity);
+        self.checkpoints.drain(..excess);
+        if self.checkpoints.is_empty() {
+            self.is_at_latest_checkpoint = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(depth: usize) -> RollbackJournal {
+        let mut journal = RollbackJournal::default();
+        journal.set_depth(depth);
+        journal
+    }
+
+    fn checkpoint(journal: &mut RollbackJournal, node_name: &str) {
+        journal.checkpoint(
+            node_name.to_owned(),
+            State::default(),
+            DialogueEvent::Options(Vec::new()),
+        );
+    }
+
+    #[test]
+    fn latest_checkpoint_only_counts_after_leaving_it() {
+        let mut journal = journal(5);
+        checkpoint(&mut journal, "A");
+        assert_eq!(journal.available(), 0);
+        journal.leave_checkpoint();
+        assert_eq!(journal.available(), 1);
+        checkpoint(&mut journal, "B");
+        assert_eq!(journal.available(), 1);
+    }
+
+    #[test]
+    fn undoes_first_writes_of_rewound_checkpoints_newest_checkpoint_first() {
+        let mut journal = journal(5);
+        checkpoint(&mut journal, "A");
+        journal.leave_checkpoint();
+        journal.record_variable_write("$x", Some(1.0.into()));
+        journal.record_variable_write("$x", Some(2.0.into()));
+        journal.record_command(&Command::parse("shake".to_owned()));
+        checkpoint(&mut journal, "B");
+        journal.leave_checkpoint();
+        journal.record_variable_write("$x", Some(3.0.into()));
+        journal.record_variable_write("$y", None);
+        checkpoint(&mut journal, "C");
+
+        let (checkpoint, variable_writes, commands) = journal.rewind(2);
+        assert_eq!(checkpoint.node_name, "A");
+        assert_eq!(
+            variable_writes,
+            [
+                ("$x".to_owned(), Some(3.0.into())),
+                ("$y".to_owned(), None),
+                ("$x".to_owned(), Some(1.0.into())),
+            ]
+        );
+        assert_eq!(commands, [Command::parse("shake".to_owned())]);
+        assert_eq!(journal.available(), 0);
+    }
+
+    #[test]
+    fn keeps_checkpoints_up_to_depth() {
+        let mut journal = journal(2);
+        for node_name in ["A", "B", "C", "D"] {
+            checkpoint(&mut journal, node_name);
+            journal.leave_checkpoint();
+        }
+        assert_eq!(journal.available(), 2);
+        assert_eq!(journal.rewind(2).0.node_name, "C");
+
+        journal.set_depth(0);
+        checkpoint(&mut journal, "E");
+        assert_eq!(journal.available(), 0);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::prelude::*;
+use bevy_platform::collections::HashSet;
+
+/// The IDs of all lines and options a [`Dialogue`] has delivered so far, e.g. for a fast-forward that stops at unread text.
+/// The [`Dialogue`] marks lines as seen when they are delivered and reports whether they were seen before in [`Line::is_seen`].
+///
+/// Unlike variables, seen lines are usually shared by all save slots. That's why they are kept apart from the [`VariableStorage`]:
+/// save them on their own via [`Dialogue::seen_lines`] and restore them via [`Dialogue::set_seen_lines`].
+/// With the `serde` feature, they are serialized as a list of line IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct SeenLines(HashSet<LineId>);
+
+impl SeenLines {
+    /// Creates an empty set of seen lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the line with the given ID was seen.
+    pub fn contains(&self, line_id: &LineId) -> bool {
+        self.0.contains(line_id)
+    }
+
+    /// Marks the line with the given ID as seen. Returns `true` if it wasn't seen before.
+    pub fn insert(&mut self, line_id: LineId) -> bool {
+        self.0.insert(line_id)
+    }
+
+    /// Marks the line with the given ID as unseen. Returns `true` if it was seen before.
+    pub fn remove(&mut self, line_id: &LineId) -> bool {
+        self.0.remove(line_id)
+    }
+
+    /// The IDs of the seen lines in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &LineId> {
+        self.0.iter()
+    }
+
+    /// The number of seen lines.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no line was seen yet.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Marks all lines as unseen.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl FromIterator<LineId> for SeenLines {
+    fn from_iter<T: IntoIterator<Item = LineId>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<LineId> for SeenLines {
+    fn extend<T: IntoIterator<Item = LineId>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for SeenLines {
+    type Item = LineId;
+    type IntoIter = <HashSet<LineId> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
@@ -0,0 +1,305 @@
+use crate::prelude::*;
+use bevy_platform::collections::HashMap;
+use bevy_platform::sync::{Arc, Mutex};
+use core::any::Any;
+use log::error;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+
+/// A [`VariableStorage`] that persists variables in an [SQLite](https://www.sqlite.org/) database, with one table per save slot.
+///
+/// Every change is written to the database right away, so that variables survive crashes of the game.
+/// To write a batch of changes at once, e.g. all `<<set>>`s performed by a single call to [`Dialogue::continue_`],
+/// wrap them in [`SqliteVariableStorage::begin_transaction`] and [`SqliteVariableStorage::commit_transaction`].
+/// Since all shallow clones share the same connection, this also works for the clone owned by the [`Dialogue`]:
+///
+/// ```
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::YarnValue;
+/// let storage = SqliteVariableStorage::open_in_memory("slot_1")?;
+/// let mut dialogue_storage = storage.clone_shallow();
+///
+/// storage.begin_transaction()?;
+/// dialogue_storage.set("$gold".to_owned(), YarnValue::Number(10.0))?;
+/// dialogue_storage.set("$met_sally".to_owned(), YarnValue::Boolean(true))?;
+/// storage.rollback_transaction()?;
+/// assert!(storage.variables().is_empty());
+/// # Ok::<(), VariableStorageError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteVariableStorage {
+    connection: Arc<Mutex<Connection>>,
+    slot: String,
+    table: String,
+}
+
+impl SqliteVariableStorage {
+    /// Opens or creates the database at the given path and uses the variables of the given save slot, creating its table if needed.
+    pub fn open(path: impl AsRef<Path>, slot: impl Into<String>) -> Result<Self> {
+        let connection = Connection::open(path).map_err(internal_error)?;
+        Self::from_connection(connection, slot)
+    }
+
+    /// Creates a database that only lives as long as the storage and its shallow clones, e.g. for tests.
+    pub fn open_in_memory(slot: impl Into<String>) -> Result<Self> {
+        let connection = Connection::open_in_memory().map_err(internal_error)?;
+        Self::from_connection(connection, slot)
+    }
+
+    /// Uses the variables of the given save slot, creating its table if needed.
+    pub fn from_connection(connection: Connection, slot: impl Into<String>) -> Result<Self> {
+        Self::new(Arc::new(Mutex::new(connection)), slot.into())
+    }
+
+    /// Creates a storage for another save slot of the same database, sharing the connection and thus any running transaction.
+    pub fn with_slot(&self, slot: impl Into<String>) -> Result<Self> {
+        Self::new(self.connection.clone(), slot.into())
+    }
+
+    fn new(connection: Arc<Mutex<Connection>>, slot: String) -> Result<Self> {
+        // Table names can't be bound as parameters, so the slot is quoted as an identifier instead
+        let table = format!("\"slot_{}\"", slot.replace('"', "\"\""));
+        connection
+            .lock()
+            .unwrap()
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {table} (name TEXT PRIMARY KEY NOT NULL, kind TEXT NOT NULL, value)"
+                ),
+                [],
+            )
+            .map_err(internal_error)?;
+        Ok(Self {
+            connection,
+            slot,
+            table,
+        })
+    }
+
+    /// The name of the save slot whose variables are stored.
+    pub fn slot(&self) -> &str {
+        &self.slot
+    }
+
+    /// Starts a transaction, so that the following changes by this storage and all of its shallow clones are only written together by
+    /// [`SqliteVariableStorage::commit_transaction`], or discarded by [`SqliteVariableStorage::rollback_transaction`].
+    /// Fails if a transaction is already running.
+    pub fn begin_transaction(&self) -> Result<()> {
+        self.execute_batch("BEGIN")
+    }
+
+    /// Writes all changes since [`SqliteVariableStorage::begin_transaction`].
+    pub fn commit_transaction(&self) -> Result<()> {
+        self.execute_batch("COMMIT")
+    }
+
+    /// Discards all changes since [`SqliteVariableStorage::begin_transaction`].
+    pub fn rollback_transaction(&self) -> Result<()> {
+        self.execute_batch("ROLLBACK")
+    }
+
+    /// Returns `true` if a transaction was started and not committed or rolled back yet.
+    pub fn is_in_transaction(&self) -> bool {
+        !self.connection.lock().unwrap().is_autocommit()
+    }
+
+    fn execute_batch(&self, sql: &str) -> Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute_batch(sql)
+            .map_err(internal_error)
+    }
+
+    fn insert(&self, connection: &Connection, name: &str, value: &YarnValue) -> Result<()> {
+        let (kind, value) = match value {
+            YarnValue::Number(number) => ("number", Value::Real(f64::from(*number))),
+            YarnValue::String(string) => ("string", Value::Text(string.clone())),
+            YarnValue::Boolean(boolean) => ("boolean", Value::Integer(i64::from(*boolean))),
+        };
+        connection
+            .prepare_cached(&format!(
+                "INSERT OR REPLACE INTO {} (name, kind, value) VALUES (?1, ?2, ?3)",
+                self.table
+            ))
+            .and_then(|mut statement| statement.execute(params![name, kind, value]))
+            .map_err(internal_error)?;
+        Ok(())
+    }
+}
+
+impl VariableStorage for SqliteVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+        MemoryVariableStorage::validate_name(&name)?;
+        let connection = self.connection.lock().unwrap();
+        self.insert(&connection, &name, &value)
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue> {
+        MemoryVariableStorage::validate_name(name)?;
+        let connection = self.connection.lock().unwrap();
+        let row = connection
+            .prepare_cached(&format!(
+                "SELECT kind, value FROM {} WHERE name = ?1",
+                self.table
+            ))
+            .and_then(|mut statement| {
+                statement
+                    .query_row([name], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .optional()
+            })
+            .map_err(internal_error)?;
+        let Some((kind, value)) = row else {
+            return Err(VariableStorageError::VariableNotFound {
+                name: name.to_owned(),
+            });
+        };
+        to_yarn_value(name, kind, value)
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+        for name in values.keys() {
+            MemoryVariableStorage::validate_name(name)?;
+        }
+        let connection = self.connection.lock().unwrap();
+        // A savepoint works both inside and outside of a transaction started by the host
+        connection
+            .execute_batch("SAVEPOINT extend_variables")
+            .map_err(internal_error)?;
+        let result = values
+            .iter()
+            .try_for_each(|(name, value)| self.insert(&connection, name, value));
+        let end_savepoint = if result.is_ok() {
+            "RELEASE extend_variables"
+        } else {
+            "ROLLBACK TO extend_variables; RELEASE extend_variables"
+        };
+        connection
+            .execute_batch(end_savepoint)
+            .map_err(internal_error)?;
+        result
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        let connection = self.connection.lock().unwrap();
+        let rows = connection
+            .prepare_cached(&format!("SELECT name, kind, value FROM {}", self.table))
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect::<rusqlite::Result<Vec<(String, String, Value)>>>()
+            });
+        match rows {
+            Ok(rows) => rows
+                .into_iter()
+                .filter_map(|(name, kind, value)| {
+                    to_yarn_value(&name, kind, value)
+                        .inspect_err(|e| error!("Skipping unreadable variable: {e}"))
+                        .ok()
+                        .map(|value| (name, value))
+                })
+                .collect(),
+            Err(e) => {
+                error!("Failed to read variables of save slot {}: {e}", self.slot);
+                HashMap::default()
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        let result = self
+            .connection
+            .lock()
+            .unwrap()
+            .execute(&format!("DELETE FROM {}", self.table), []);
+        if let Err(e) = result {
+            error!("Failed to clear variables of save slot {}: {e}", self.slot);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn to_yarn_value(name: &str, kind: String, value: Value) -> Result<YarnValue> {
+    match (kind.as_str(), value) {
+        ("number", Value::Real(number)) => Ok(YarnValue::Number(number as f32)),
+        ("number", Value::Integer(number)) => Ok(YarnValue::Number(number as f32)),
+        ("string", Value::Text(string)) => Ok(YarnValue::String(string)),
+        ("boolean", Value::Integer(boolean)) => Ok(YarnValue::Boolean(boolean != 0)),
+        (kind, value) => Err(VariableStorageError::InternalError {
+            error: format!("Variable {name} has an invalid value {value:?} of kind {kind}").into(),
+        }),
+    }
+}
+
+fn internal_error(error: rusqlite::Error) -> VariableStorageError {
+    VariableStorageError::InternalError {
+        error: Box::new(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable_storage_conformance::assert_conforms;
+
+    #[test]
+    fn conforms() {
+        assert_conforms(|| Box::new(SqliteVariableStorage::open_in_memory("test").unwrap()));
+    }
+
+    #[test]
+    fn save_slots_are_separate() {
+        let mut first = SqliteVariableStorage::open_in_memory("first").unwrap();
+        let mut second = first.with_slot("second \"quoted\"").unwrap();
+        first.set("$gold".to_owned(), 10.0.into()).unwrap();
+        second.set("$gold".to_owned(), 20.0.into()).unwrap();
+        assert_eq!(first.get("$gold").unwrap(), YarnValue::Number(10.0));
+        assert_eq!(second.get("$gold").unwrap(), YarnValue::Number(20.0));
+
+        second.clear();
+        assert!(first.contains("$gold"));
+    }
+
+    #[test]
+    fn transactions_commit_or_discard_changes_of_all_clones() {
+        let storage = SqliteVariableStorage::open_in_memory("test").unwrap();
+        let mut clone = storage.clone_shallow();
+
+        storage.begin_transaction().unwrap();
+        assert!(storage.is_in_transaction());
+        clone.set("$gold".to_owned(), 10.0.into()).unwrap();
+        storage.commit_transaction().unwrap();
+        assert!(!storage.is_in_transaction());
+
+        storage.begin_transaction().unwrap();
+        clone.set("$gold".to_owned(), 20.0.into()).unwrap();
+        VariableStorage::extend(
+            clone.as_mut(),
+            [("$met".to_owned(), true.into())].into_iter().collect(),
+        )
+        .unwrap();
+        storage.rollback_transaction().unwrap();
+        assert_eq!(
+            storage.variables(),
+            [("$gold".to_owned(), YarnValue::Number(10.0))]
+                .into_iter()
+                .collect()
+        );
+    }
+}
@@ -20,6 +20,17 @@ pub trait TextProvider: Debug + Send + Sync {
     fn accept_line_hints(&mut self, line_ids: &[LineId]);
     /// Returns the text for the given [`LineId`]. Will only be called if [`TextProvider::are_lines_available`] returns `true`.
     fn get_text(&self, id: &LineId) -> Option<String>;
+    /// Returns the text for the given [`LineId`] with the values of its inline expressions already inserted.
+    /// Implement this for text formats that handle substitutions themselves, such as Fluent's arguments.
+    /// The default returns `None`, in which case the text is looked up via [`TextProvider::get_text`]
+    /// and its `{0}`, `{1}`, etc. placeholders are replaced by the [`Dialogue`](crate::prelude::Dialogue).
+    fn get_text_with_substitutions(
+        &self,
+        _id: &LineId,
+        _substitutions: &[YarnValue],
+    ) -> Option<String> {
+        None
+    }
     /// Sets the current language. If `None` is passed, the base language will be used.
     fn set_language(&mut self, language: Option<Language>);
     /// Returns the current language. If `None` is returned, the base language is used.
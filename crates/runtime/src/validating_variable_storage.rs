@@ -0,0 +1,229 @@
+use crate::prelude::*;
+use bevy_platform::collections::HashMap;
+use bevy_platform::sync::Arc;
+use core::any::Any;
+use yarnspinner_core::types::TypedValue;
+
+/// A [`VariableStorage`] that wraps another one and only accepts values for declared variables of the declared type,
+/// so that a host storing e.g. a string into a number variable gets an error right away instead of a type panic in the VM later.
+///
+/// The declarations are usually taken from the `declarations` of a compilation,
+/// passing each declaration's `name`, `r#type` and `default_value`:
+///
+/// ```ignore
+/// let storage = ValidatingVariableStorage::new(
+///     Box::new(MemoryVariableStorage::new()),
+///     compilation.declarations.iter().map(|declaration| {
+///         (declaration.name.clone(), declaration.r#type.clone(), declaration.default_value.clone())
+///     }),
+/// );
+/// ```
+///
+/// Writes fail with [`VariableStorageError::UndeclaredVariable`] or [`VariableStorageError::TypeMismatch`].
+/// Reading a declared variable that was never set returns its declared default value, if it has one.
+#[derive(Debug, Clone)]
+pub struct ValidatingVariableStorage {
+    inner: Box<dyn VariableStorage>,
+    declarations: Arc<HashMap<String, (Type, Option<YarnValue>)>>,
+}
+
+impl ValidatingVariableStorage {
+    /// Wraps the given [`VariableStorage`] and validates writes against the given declarations of names, types and default values.
+    /// Declarations of functions are ignored.
+    pub fn new(
+        inner: Box<dyn VariableStorage>,
+        declarations: impl IntoIterator<Item = (String, Type, Option<YarnValue>)>,
+    ) -> Self {
+        let declarations = declarations
+            .into_iter()
+            .filter(|(_, r#type, _)| !matches!(r#type, Type::Function(_)))
+            .map(|(name, r#type, default_value)| (name, (r#type, default_value)))
+            .collect();
+        Self {
+            inner,
+            declarations: Arc::new(declarations),
+        }
+    }
+
+    /// Gets the wrapped [`VariableStorage`].
+    pub fn inner(&self) -> &dyn VariableStorage {
+        self.inner.as_ref()
+    }
+
+    /// Gets the declared type of a variable, or `None` if it is not declared.
+    pub fn declared_type(&self, name: &str) -> Option<&Type> {
+        self.declarations.get(name).map(|(r#type, _)| r#type)
+    }
+
+    /// Gets the declared default value of a variable, or `None` if it is not declared or has no default value.
+    pub fn declared_default_value(&self, name: &str) -> Option<&YarnValue> {
+        self.declarations
+            .get(name)
+            .and_then(|(_, default_value)| default_value.as_ref())
+    }
+
+    fn validate(&self, name: &str, value: &YarnValue) -> Result<()> {
+        if !name.starts_with('$') {
+            return Err(VariableStorageError::InvalidVariableName {
+                name: name.to_owned(),
+            });
+        }
+        let Some((expected, _)) = self.declarations.get(name) else {
+            return Err(VariableStorageError::UndeclaredVariable {
+                name: name.to_owned(),
+            });
+        };
+        let actual = value.r#type();
+        if *expected == Type::Any || *expected == actual {
+            Ok(())
+        } else {
+            Err(VariableStorageError::TypeMismatch {
+                name: name.to_owned(),
+                expected: expected.clone(),
+                actual,
+            })
+        }
+    }
+}
+
+impl VariableStorage for ValidatingVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+        self.validate(&name, &value)?;
+        self.inner.set(name, value)
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue> {
+        self.inner.get(name).or_else(|error| match error {
+            VariableStorageError::VariableNotFound { .. } => {
+                self.declared_default_value(name).cloned().ok_or(error)
+            }
+            _ => Err(error),
+        })
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.inner.contains(name)
+    }
+
+    fn is_persistent(&self) -> bool {
+        self.inner.is_persistent()
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+        for (name, value) in &values {
+            self.validate(name, value)?;
+        }
+        VariableStorage::extend(self.inner.as_mut(), values)
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.inner.variables()
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    fn take_changes(&self) -> Vec<VariableChange> {
+        self.inner.take_changes()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable_storage_conformance::assert_conforms;
+
+    fn storage() -> ValidatingVariableStorage {
+        ValidatingVariableStorage::new(
+            Box::new(MemoryVariableStorage::new()),
+            [
+                ("$gold".to_owned(), Type::Number, Some(0.0.into())),
+                ("$name".to_owned(), Type::String, None),
+                ("$anything".to_owned(), Type::Any, None),
+            ],
+        )
+    }
+
+    #[test]
+    fn conforms() {
+        assert_conforms(|| {
+            Box::new(ValidatingVariableStorage::new(
+                Box::new(MemoryVariableStorage::new()),
+                ["$number", "$string", "$boolean", "$kept"]
+                    .map(|name| (name.to_owned(), Type::Any, None)),
+            ))
+        });
+    }
+
+    #[test]
+    fn rejects_undeclared_variables_and_type_mismatches() {
+        let mut storage = storage();
+        storage.set("$gold".to_owned(), 10.0.into()).unwrap();
+        storage.set("$anything".to_owned(), true.into()).unwrap();
+
+        assert!(matches!(
+            storage.set("$gold".to_owned(), "lots".into()),
+            Err(VariableStorageError::TypeMismatch {
+                expected: Type::Number,
+                actual: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            storage.set("$silver".to_owned(), 1.0.into()),
+            Err(VariableStorageError::UndeclaredVariable { .. })
+        ));
+
+        let values = [("$gold", 1.0.into()), ("$name", false.into())]
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect();
+        assert!(VariableStorage::extend(&mut storage, values).is_err());
+        assert_eq!(storage.get("$gold").unwrap(), YarnValue::Number(10.0));
+    }
+
+    #[test]
+    fn returns_declared_defaults_for_unset_variables() {
+        let storage = storage();
+        assert_eq!(storage.get("$gold").unwrap(), YarnValue::Number(0.0));
+        assert!(!storage.contains("$gold"));
+        assert!(matches!(
+            storage.get("$name"),
+            Err(VariableStorageError::VariableNotFound { .. })
+        ));
+        assert!(storage.variables().is_empty());
+    }
+
+    #[test]
+    fn forwards_changes_of_observed_storage() {
+        let mut storage = ValidatingVariableStorage::new(
+            Box::new(ObservedVariableStorage::new(Box::new(
+                MemoryVariableStorage::new(),
+            ))),
+            [("$gold".to_owned(), Type::Number, None)],
+        );
+        storage.set("$gold".to_owned(), 5.0.into()).unwrap();
+        assert_eq!(
+            storage.take_changes(),
+            [VariableChange {
+                name: "$gold".to_owned(),
+                old_value: None,
+                new_value: Some(5.0.into()),
+            }]
+        );
+        assert!(storage.take_changes().is_empty());
+    }
+}
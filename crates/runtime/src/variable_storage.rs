@@ -31,6 +31,12 @@ pub trait VariableStorage: Debug + Send + Sync {
     fn contains(&self, name: &str) -> bool {
         self.get(name).is_ok()
     }
+    /// Returns `true` if the values outlive the [`Dialogue`], e.g. because they are saved to a file.
+    /// When a program is loaded, the variables already in a persistent storage keep their values,
+    /// while a storage that is not persistent has all declared variables reset to the program's initial values.
+    fn is_persistent(&self) -> bool {
+        false
+    }
     /// Extends this variable storage with the given values. Must fail with a [`VariableStorageError::InvalidVariableName`] if any of the variable names do not start with a `$`.
     /// Existing variables must be overwritten.
     fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()>;
@@ -38,6 +44,12 @@ pub trait VariableStorage: Debug + Send + Sync {
     fn variables(&self) -> HashMap<String, YarnValue>;
     /// Clears all variables in this variable storage.
     fn clear(&mut self);
+    /// Removes and returns the changes recorded since the last call, oldest first.
+    /// Used by the [`Dialogue`] to emit [`DialogueEvent::VariableChanged`]s.
+    /// Storages that don't record their changes, which is the default, return an empty list. See [`ObservedVariableStorage`].
+    fn take_changes(&self) -> Vec<VariableChange> {
+        Vec::new()
+    }
     /// Gets the [`VariableStorage`] as a trait object.
     /// This allows retrieving the concrete type by downcasting, using the `downcast_ref` method available through the `Any` trait.
     fn as_any(&self) -> &dyn Any;
@@ -57,9 +69,23 @@ impl Extend<(String, YarnValue)> for Box<dyn VariableStorage> {
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub enum VariableStorageError {
-    InvalidVariableName { name: String },
-    VariableNotFound { name: String },
-    InternalError { error: Box<dyn Error + Send + Sync> },
+    InvalidVariableName {
+        name: String,
+    },
+    VariableNotFound {
+        name: String,
+    },
+    UndeclaredVariable {
+        name: String,
+    },
+    TypeMismatch {
+        name: String,
+        expected: Type,
+        actual: Type,
+    },
+    InternalError {
+        error: Box<dyn Error + Send + Sync>,
+    },
 }
 
 impl Error for VariableStorageError {}
@@ -73,6 +99,15 @@ impl Display for VariableStorageError {
                 "{name} is not a valid variable name: Variable names must start with a \'$\'. (Did you mean to use \'${name}\'?)"
             ),
             VariableNotFound { name } => write!(f, "Variable name {name} is not defined"),
+            UndeclaredVariable { name } => write!(f, "Variable {name} is not declared"),
+            TypeMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Variable {name} is declared as {expected}, but was given a value of type {actual}"
+            ),
             InternalError { error } => write!(f, "Internal variable storage error: {error}"),
         }
     }
@@ -141,7 +176,7 @@ impl VariableStorage for MemoryVariableStorage {
 }
 
 impl MemoryVariableStorage {
-    fn validate_name(name: impl AsRef<str>) -> Result<()> {
+    pub(crate) fn validate_name(name: impl AsRef<str>) -> Result<()> {
         let name = name.as_ref();
         if name.starts_with('$') {
             Ok(())
@@ -152,3 +187,14 @@ impl MemoryVariableStorage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variable_storage_conformance::assert_conforms;
+
+    #[test]
+    fn memory_variable_storage_conforms() {
+        assert_conforms(|| Box::new(MemoryVariableStorage::new()));
+    }
+}
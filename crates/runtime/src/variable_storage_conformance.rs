@@ -0,0 +1,100 @@
+//! Checks of the contract documented on [`VariableStorage`], shared by the tests of all implementations.
+
+use crate::prelude::*;
+use bevy_platform::collections::HashMap;
+
+/// Runs all checks, each on a fresh, empty storage created by `new_storage`.
+pub(crate) fn assert_conforms(new_storage: impl Fn() -> Box<dyn VariableStorage>) {
+    stores_values_of_every_type(new_storage());
+    reports_missing_variables(new_storage());
+    rejects_invalid_names(new_storage());
+    extend_overwrites_existing_variables(new_storage());
+    clear_removes_all_variables(new_storage());
+    shallow_clones_share_variables(new_storage());
+}
+
+fn values() -> [(String, YarnValue); 3] {
+    [
+        ("$number".to_owned(), YarnValue::Number(1.5)),
+        ("$string".to_owned(), YarnValue::String("Hello".to_owned())),
+        ("$boolean".to_owned(), YarnValue::Boolean(true)),
+    ]
+}
+
+fn stores_values_of_every_type(mut storage: Box<dyn VariableStorage>) {
+    for (name, value) in values() {
+        storage.set(name.clone(), value.clone()).unwrap();
+        assert_eq!(storage.get(&name).unwrap(), value);
+        assert!(storage.contains(&name));
+    }
+    storage
+        .set(
+            "$number".to_owned(),
+            YarnValue::String("changed type".to_owned()),
+        )
+        .unwrap();
+    assert_eq!(
+        storage.get("$number").unwrap(),
+        YarnValue::String("changed type".to_owned())
+    );
+    assert_eq!(storage.variables().len(), 3);
+}
+
+fn reports_missing_variables(storage: Box<dyn VariableStorage>) {
+    assert!(matches!(
+        storage.get("$missing"),
+        Err(VariableStorageError::VariableNotFound { .. })
+    ));
+    assert!(!storage.contains("$missing"));
+    assert!(storage.variables().is_empty());
+}
+
+fn rejects_invalid_names(mut storage: Box<dyn VariableStorage>) {
+    assert!(matches!(
+        storage.set("number".to_owned(), YarnValue::Number(1.0)),
+        Err(VariableStorageError::InvalidVariableName { .. })
+    ));
+    assert!(matches!(
+        storage.get("number"),
+        Err(VariableStorageError::InvalidVariableName { .. })
+    ));
+
+    let mut values: HashMap<_, _> = values().into_iter().collect();
+    values.insert("number".to_owned(), YarnValue::Number(1.0));
+    assert!(matches!(
+        VariableStorage::extend(storage.as_mut(), values),
+        Err(VariableStorageError::InvalidVariableName { .. })
+    ));
+    assert!(storage.variables().is_empty());
+}
+
+fn extend_overwrites_existing_variables(mut storage: Box<dyn VariableStorage>) {
+    storage
+        .set("$number".to_owned(), YarnValue::Number(0.0))
+        .unwrap();
+    storage
+        .set("$kept".to_owned(), YarnValue::Boolean(false))
+        .unwrap();
+    VariableStorage::extend(storage.as_mut(), values().into_iter().collect()).unwrap();
+
+    let mut expected: HashMap<_, _> = values().into_iter().collect();
+    expected.insert("$kept".to_owned(), YarnValue::Boolean(false));
+    assert_eq!(storage.variables(), expected);
+}
+
+fn clear_removes_all_variables(mut storage: Box<dyn VariableStorage>) {
+    VariableStorage::extend(storage.as_mut(), values().into_iter().collect()).unwrap();
+    storage.clear();
+    assert!(storage.variables().is_empty());
+    assert!(!storage.contains("$number"));
+}
+
+fn shallow_clones_share_variables(mut storage: Box<dyn VariableStorage>) {
+    let mut clone = storage.clone_shallow();
+    clone
+        .set("$number".to_owned(), YarnValue::Number(2.0))
+        .unwrap();
+    assert_eq!(storage.get("$number").unwrap(), YarnValue::Number(2.0));
+    storage.clear();
+    assert!(!clone.contains("$number"));
+}
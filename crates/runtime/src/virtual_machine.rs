@@ -7,6 +7,7 @@ pub(crate) use self::{execution_state::*, state::*};
 use crate::Result;
 use crate::markup::{LineParser, ParsedMarkup};
 use crate::prelude::*;
+use bevy_platform::sync::Arc;
 use core::fmt::Debug;
 use log::*;
 
@@ -19,6 +20,10 @@ pub(crate) struct VirtualMachine {
     pub(crate) program: Option<Program>,
     pub(crate) variable_storage: Box<dyn VariableStorage>,
     pub(crate) line_hints_enabled: bool,
+    pub(crate) history: Option<DialogueHistory>,
+    pub(crate) rollback_journal: RollbackJournal,
+    pub(crate) seen_lines: SeenLines,
+    pub(crate) unavailable_options_policy: UnavailableOptionsPolicy,
     current_node_name: Option<String>,
     state: State,
     execution_state: ExecutionState,
@@ -27,6 +32,8 @@ pub(crate) struct VirtualMachine {
     line_parser: LineParser,
     text_provider: Box<dyn TextProvider>,
     language_code: Option<Language>,
+    /// Created on the first line that needs it, as setting up the formatters for a language is not cheap.
+    number_formatter: Option<Arc<NumberFormatter>>,
 }
 
 impl VirtualMachine {
@@ -42,6 +49,7 @@ impl VirtualMachine {
             line_parser,
             text_provider,
             language_code: Default::default(),
+            number_formatter: Default::default(),
             program: Default::default(),
             current_node_name: Default::default(),
             state: Default::default(),
@@ -49,6 +57,10 @@ impl VirtualMachine {
             current_node: Default::default(),
             batched_events: Default::default(),
             line_hints_enabled: Default::default(),
+            history: Default::default(),
+            rollback_journal: Default::default(),
+            seen_lines: Default::default(),
+            unavailable_options_policy: Default::default(),
         }
     }
 
@@ -68,9 +80,14 @@ impl VirtualMachine {
         self.variable_storage.as_mut()
     }
 
+    pub(crate) fn line_parser_mut(&mut self) -> &mut LineParser {
+        &mut self.line_parser
+    }
+
     pub(crate) fn set_language_code(&mut self, language_code: impl Into<Option<Language>>) {
         let language_code = language_code.into();
         self.language_code.clone_from(&language_code);
+        self.number_formatter = None;
         self.line_parser.set_language_code(language_code.clone());
         self.text_provider.set_language(language_code);
     }
@@ -183,12 +200,16 @@ impl VirtualMachine {
         mut instruction_fn: impl FnMut(&mut Self, &Instruction) -> crate::Result<()>,
     ) -> crate::Result<Vec<DialogueEvent>> {
         self.assert_can_continue()?;
+        self.rollback_journal.leave_checkpoint();
         self.set_execution_state(ExecutionState::Running);
+        self.batched_events.extend(self.take_variable_changes());
 
         while self.execution_state == ExecutionState::Running {
             let current_node = self.current_node.clone().unwrap();
             let current_instruction = &current_node.instructions[self.state.program_counter];
             instruction_fn(self, current_instruction)?;
+            // Deliver changes right after the instruction that caused them, so that they arrive in order with lines and commands
+            self.batched_events.extend(self.take_variable_changes());
             // ## Implementation note
             // The original increments the program counter here, but that leads to intentional underflow on [`OpCode::RunNode`],
             // so we do the incrementation in [`VirtualMachine::run_instruction`] instead.
@@ -240,14 +261,24 @@ impl VirtualMachine {
 
         // We now know what number option was selected; push the
         // corresponding node name to the stack.
-        let destination_node = self.state.current_options[selected_option_id.0]
-            .destination_node
-            .clone();
+        let selected_option = &self.state.current_options[selected_option_id.0];
+        let destination_node = selected_option.destination_node.clone();
+        if let Some(history) = self.history.as_mut() {
+            let substitutions =
+                core::mem::take(&mut self.state.current_option_substitutions[selected_option_id.0]);
+            history.record(
+                &selected_option.line,
+                substitutions,
+                Some(selected_option_id),
+            );
+        }
         self.state.push(destination_node);
+        self.rollback_journal.leave_checkpoint();
 
         // We no longer need the accumulated list of options; clear it
         // so that it's ready for the next one
         self.state.current_options.clear();
+        self.state.current_option_substitutions.clear();
 
         // We're no longer in the WaitingForOptions state; we are now waiting for our game to let us continue
         self.set_execution_state(ExecutionState::WaitingForContinue);
@@ -332,8 +363,12 @@ impl VirtualMachine {
 
                 let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 1);
                 let line = self.prepare_line(string_id, &substitutions)?;
+                self.seen_lines.insert(line.id.clone());
+                if let Some(history) = self.history.as_mut() {
+                    history.record(&line, substitutions, None);
+                }
 
-                self.batched_events.push(DialogueEvent::Line(line));
+                self.batched_events.push(DialogueEvent::Line(line.clone()));
 
                 // Implementation note:
                 // In the original, this is only done if `execution_state` is still `DeliveringContent`,
@@ -342,6 +377,9 @@ impl VirtualMachine {
                 // called `continue_` themselves outside of the line handler.
                 self.set_execution_state(ExecutionState::WaitingForContinue);
                 self.state.program_counter += 1;
+                if self.rollback_journal.checkpoints_on_lines() {
+                    self.checkpoint(DialogueEvent::Line(line));
+                }
             }
             OpCode::RunCommand => {
                 // Passes a string to the client as a custom command
@@ -352,9 +390,10 @@ impl VirtualMachine {
                     .into_iter()
                     .enumerate()
                     .fold(command_text, |command_text, (i, substitution)| {
-                        command_text.replace(&format!("{{{i}}}"), &substitution)
+                        command_text.replace(&format!("{{{i}}}"), &String::from(substitution))
                     });
                 let command = Command::parse(command_text);
+                self.rollback_journal.record_command(&command);
 
                 self.batched_events.push(DialogueEvent::Command(command));
 
@@ -400,6 +439,7 @@ impl VirtualMachine {
                     destination_node: node_name,
                     is_available: line_condition_passed,
                 });
+                self.state.current_option_substitutions.push(substitutions);
                 self.state.program_counter += 1;
             }
             OpCode::ShowOptions => {
@@ -411,19 +451,60 @@ impl VirtualMachine {
                     return Ok(());
                 }
 
+                if self
+                    .state
+                    .current_options
+                    .iter()
+                    .all(|option| !option.is_available)
+                {
+                    match self.unavailable_options_policy {
+                        UnavailableOptionsPolicy::Deliver => {}
+                        UnavailableOptionsPolicy::Fallthrough => {
+                            // Programs compiled before the end of the group was pushed are delivered as usual
+                            if self.option_group_pushed_its_end() {
+                                self.state.current_options.clear();
+                                self.state.current_option_substitutions.clear();
+                                // Let the following `Jump` continue at the end of the group instead of at a selected option
+                                let end_of_group_label: String = self.state.peek();
+                                self.state.push(end_of_group_label);
+                                self.state.program_counter += 1;
+                                return Ok(());
+                            }
+                        }
+                        UnavailableOptionsPolicy::Error => {
+                            let line_ids = self
+                                .state
+                                .current_options
+                                .iter()
+                                .map(|option| option.line.id.clone())
+                                .collect();
+                            return Err(DialogueError::NoAvailableOptionsError { line_ids });
+                        }
+                    }
+                }
+
                 // We can't continue until our client tell us which option to pick
                 self.set_execution_state(ExecutionState::WaitingOnOptionSelection);
 
+                let presented_line_ids = self
+                    .state
+                    .current_options
+                    .iter()
+                    .filter(|option| option.is_available)
+                    .map(|option| option.line.id.clone());
+                self.seen_lines.extend(presented_line_ids);
+
                 // Pass the options set to the client, as well as a
                 // delegate for them to call when the user has made
                 // a selection
                 let current_options = self.state.current_options.clone();
                 self.batched_events
-                    .push(DialogueEvent::Options(current_options));
+                    .push(DialogueEvent::Options(current_options.clone()));
 
                 // Implementation note:
                 // Not checking the execution state now since we have no line handler to call `continue_` from.
                 self.state.program_counter += 1;
+                self.checkpoint(DialogueEvent::Options(current_options));
             }
             OpCode::PushString => {
                 // Pushes a string value onto the stack. The operand is an index into the string table, so that's looked up first.
@@ -548,6 +629,11 @@ impl VirtualMachine {
                 // Store the top value on the stack in a variable.
                 let top_value = self.state.peek_value().clone();
                 let variable_name: String = instruction.read_operand(0);
+                if self.rollback_journal.is_enabled() {
+                    let previous_value = self.variable_storage.get(&variable_name).ok();
+                    self.rollback_journal
+                        .record_variable_write(&variable_name, previous_value);
+                }
                 self.variable_storage.set(variable_name, top_value.into())?;
                 self.state.program_counter += 1;
             }
@@ -577,18 +663,104 @@ impl VirtualMachine {
         Ok(())
     }
 
-    fn prepare_line(&mut self, string_id: LineId, substitutions: &[String]) -> Result<Line> {
-        let line_text = self.text_provider.get_text(&string_id).ok_or_else(|| {
-            DialogueError::LineProviderError {
-                id: string_id.clone(),
-                language_code: self.language_code.clone(),
+    /// Takes a checkpoint of the current state for [`VirtualMachine::rollback`], which must happen after the program counter was advanced.
+    fn checkpoint(&mut self, event: DialogueEvent) {
+        if !self.rollback_journal.is_enabled() {
+            return;
+        }
+        let node_name = self.current_node_name.clone().unwrap();
+        self.rollback_journal
+            .checkpoint(node_name, self.state.clone(), event);
+    }
+
+    /// Restores the state of the checkpoint `steps` checkpoints back and undoes the variable writes since.
+    /// Returns a [`DialogueEvent::Rollback`] followed by the event that was delivered at the checkpoint.
+    pub(crate) fn rollback(&mut self, steps: usize) -> Result<Vec<DialogueEvent>> {
+        let available = self.rollback_journal.available();
+        if steps == 0 || steps > available {
+            return Err(DialogueError::InvalidRollbackError { steps, available });
+        }
+        let (checkpoint, variable_writes, undone_commands) = self.rollback_journal.rewind(steps);
+        for (name, previous_value) in variable_writes {
+            // A variable that didn't exist yet was read from the program's initial values
+            let previous_value = previous_value.or_else(|| {
+                self.program
+                    .as_ref()?
+                    .initial_values
+                    .get(&name)
+                    .cloned()
+                    .map(Into::into)
+            });
+            if let Some(previous_value) = previous_value {
+                self.variable_storage.set(name, previous_value)?;
             }
-        })?;
-        let substituted_text = expand_substitutions(&line_text, substitutions);
+        }
+
+        self.current_node = Some(self.get_node_from_name(&checkpoint.node_name)?.clone());
+        self.current_node_name = Some(checkpoint.node_name.clone());
+        self.state = checkpoint.state;
+        self.batched_events.clear();
+        let execution_state = match checkpoint.event {
+            DialogueEvent::Options(_) => ExecutionState::WaitingOnOptionSelection,
+            _ => ExecutionState::WaitingForContinue,
+        };
+        self.set_execution_state(execution_state);
+
+        let mut events = vec![DialogueEvent::Rollback(Rollback {
+            steps,
+            node_name: checkpoint.node_name,
+            undone_commands,
+        })];
+        events.extend(self.take_variable_changes());
+        events.push(checkpoint.event);
+        Ok(events)
+    }
+
+    /// Takes the changes recorded by the variable storage, see [`VariableStorage::take_changes`].
+    fn take_variable_changes(&self) -> Vec<DialogueEvent> {
+        self.variable_storage
+            .take_changes()
+            .into_iter()
+            .map(DialogueEvent::VariableChanged)
+            .collect()
+    }
+
+    pub(crate) fn prepare_line(
+        &mut self,
+        string_id: LineId,
+        substitutions: &[YarnValue],
+    ) -> Result<Line> {
+        let substituted_text = if let Some(text) = self
+            .text_provider
+            .get_text_with_substitutions(&string_id, substitutions)
+        {
+            text
+        } else {
+            let line_text = self.text_provider.get_text(&string_id).ok_or_else(|| {
+                DialogueError::LineProviderError {
+                    id: string_id.clone(),
+                    language_code: self.language_code.clone(),
+                }
+            })?;
+            let number_formatter = substitutions
+                .iter()
+                .any(|substitution| matches!(substitution, YarnValue::Number(_)))
+                .then(|| {
+                    self.number_formatter.get_or_insert_with(|| {
+                        Arc::new(NumberFormatter::new(self.language_code.clone()))
+                    })
+                });
+            expand_substitutions(
+                &line_text,
+                substitutions,
+                number_formatter.map(|formatter| &**formatter),
+            )
+        };
         let markup = self
             .parse_markup(&substituted_text)
             .map_err(DialogueError::MarkupParseError)?;
         let line = Line {
+            is_seen: self.seen_lines.contains(&string_id),
             id: string_id,
             text: markup.text,
             attributes: markup.attributes,
@@ -596,6 +768,17 @@ impl VirtualMachine {
         Ok(line)
     }
 
+    /// Whether the instruction before the current `ShowOptions` pushed the label at the end of its option group,
+    /// which the compiler emits so that the dialogue can skip the group when none of its options are available.
+    fn option_group_pushed_its_end(&self) -> bool {
+        let current_node = self.current_node.as_ref().unwrap();
+        self.state
+            .program_counter
+            .checked_sub(1)
+            .and_then(|index| current_node.instructions.get(index))
+            .is_some_and(|instruction| instruction.opcode == i32::from(OpCode::PushString))
+    }
+
     /// Looks up the instruction number for a named label in the current node.
     ///
     /// # Panics
@@ -625,11 +808,11 @@ impl VirtualMachine {
         &mut self,
         instruction: &Instruction,
         index: usize,
-    ) -> Vec<String> {
+    ) -> Vec<YarnValue> {
         let expression_count: usize = instruction.operands[index].clone().try_into().unwrap();
         let mut values: Vec<_> = (0..expression_count)
             .rev()
-            .map(|_| self.state.pop())
+            .map(|_| self.state.pop_value().raw_value)
             .collect();
         values.reverse();
         values
@@ -647,16 +830,124 @@ fn assert_up_to_date_compiler(predicate: bool) {
 /// Replaces all substitution markers in a text with the given substitution list.
 ///
 /// This method replaces substitution markers
-/// (for example, `{0}`) with the corresponding entry in `substitutions`.
-/// If `test` contains a substitution marker whose
+/// (for example, `{0}` or `{0:N2}`) with the corresponding entry in `substitutions`.
+/// Numbers with a [`FormatSpecifier`] after the colon are formatted by the `number_formatter` for the current language.
+/// Inside of markup tags, e.g. `[plural value={0} one="coin" other="coins"]`, the formatting is skipped so that the
+/// markup parser can read the numbers.
+/// If `text` contains a substitution marker whose
 /// index is not present in `substitutions`, it is
 /// ignored.
 #[must_use]
-fn expand_substitutions(text: &str, substitutions: &[String]) -> String {
-    substitutions
-        .iter()
-        .enumerate()
-        .fold(text.to_owned(), |text, (i, substitution)| {
-            text.replace(&format!("{{{i}}}",), substitution)
-        })
+fn expand_substitutions(
+    text: &str,
+    substitutions: &[YarnValue],
+    number_formatter: Option<&NumberFormatter>,
+) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut is_inside_markup_tag = false;
+    let mut skip_until = 0;
+    let mut chars = text.char_indices();
+    while let Some((start, char)) = chars.next() {
+        if start < skip_until {
+            continue;
+        }
+        match char {
+            '\\' => {
+                expanded.push(char);
+                if let Some((_, escaped)) = chars.next() {
+                    expanded.push(escaped);
+                }
+                continue;
+            }
+            '[' => is_inside_markup_tag = true,
+            ']' => is_inside_markup_tag = false,
+            '{' => {
+                let rest = &text[start + 1..];
+                let substitution = rest.find('}').and_then(|end| {
+                    let marker = &rest[..end];
+                    let (index, specifier) = match marker.split_once(':') {
+                        Some((index, specifier)) => (index, Some(specifier)),
+                        None => (marker, None),
+                    };
+                    let value = substitutions.get(index.parse::<usize>().ok()?)?;
+                    Some((end, value, specifier))
+                });
+                if let Some((end, value, specifier)) = substitution {
+                    let formatted = match (value, number_formatter) {
+                        (YarnValue::Number(number), Some(number_formatter))
+                            if !is_inside_markup_tag =>
+                        {
+                            // Invalid specifiers were already reported by the compiler, so we just ignore them here.
+                            let specifier = specifier.and_then(|specifier| specifier.parse().ok());
+                            number_formatter.format(*number, specifier)
+                        }
+                        _ => String::from(value),
+                    };
+                    expanded.push_str(&formatted);
+                    // Skip the marker and its closing brace
+                    skip_until = start + 1 + end + 1;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        expanded.push(char);
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(text: &str, substitutions: &[YarnValue], language: Option<&str>) -> String {
+        let number_formatter = NumberFormatter::new(language.map(Language::new));
+        expand_substitutions(text, substitutions, Some(&number_formatter))
+    }
+
+    #[test]
+    fn expands_substitutions_with_language() {
+        let substitutions = [YarnValue::from(1234.5), YarnValue::from("Alice")];
+        assert_eq!(
+            expand("{1} has {0} coins", &substitutions, Some("de-DE")),
+            "Alice has 1234.5 coins"
+        );
+        assert_eq!(
+            expand("{1} has {0:N1} coins", &substitutions, Some("de-DE")),
+            "Alice has 1.234,5 coins"
+        );
+        assert_eq!(
+            expand("{1} has {0:N0} coins", &substitutions, Some("en-US")),
+            "Alice has 1,235 coins"
+        );
+    }
+
+    #[test]
+    fn expands_substitutions_without_language_like_before() {
+        let substitutions = [YarnValue::from(1234.5)];
+        assert_eq!(expand("{0} coins", &substitutions, None), "1234.5 coins");
+        assert_eq!(expand("{0:F0} coins", &substitutions, None), "1235 coins");
+    }
+
+    #[test]
+    fn does_not_format_numbers_inside_markup_tags() {
+        let substitutions = [YarnValue::from(1234.0)];
+        assert_eq!(
+            expand(
+                "[plural value={0:N0} one=\"coin\" other=\"coins\"/] [b]{0:N0}[/b]",
+                &substitutions,
+                Some("en-US")
+            ),
+            "[plural value=1234 one=\"coin\" other=\"coins\"/] [b]1,234[/b]"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_and_escaped_markers() {
+        let substitutions = [YarnValue::from(1.0)];
+        assert_eq!(
+            expand("{1} \\{0} {0:X} {0}", &substitutions, Some("en-US")),
+            "{1} \\{0} 1 1"
+        );
+    }
 }
@@ -19,6 +19,10 @@ pub(crate) struct State {
     /// when the next RunOption instruction is encountered.
     pub(crate) current_options: Vec<DialogueOption>,
 
+    /// The values of the inline expressions of each option in `current_options`,
+    /// kept to record the selected option in the [`DialogueHistory`].
+    pub(crate) current_option_substitutions: Vec<Vec<YarnValue>>,
+
     /// The value stack.
     pub(crate) stack: Vec<InternalValue>,
 }
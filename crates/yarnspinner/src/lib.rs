@@ -18,20 +18,25 @@ pub mod prelude {
         YarnFn, YarnValue, yarn_library,
     };
     pub use crate::runtime::{
-        Command as YarnCommand, CompiledProgramAnalyser as YarnAnalyser,
-        Context as YarnAnalysisContext, Dialogue, DialogueError, DialogueEvent, DialogueOption,
-        Language, Line as YarnLine, MarkupAttribute, MarkupValue, OptionId,
-        Result as YarnRuntimeResult, StringTable, TextProvider, VariableStorage,
+        AttributeMarkerProcessor, Command as YarnCommand, CommandCompletion, CommandError,
+        CommandRegistry, CompiledProgramAnalyser as YarnAnalyser, Context as YarnAnalysisContext,
+        Dialogue, DialogueError, DialogueEvent, DialogueHistory, DialogueOption, HistoryEntry,
+        Language, Line as YarnLine, MarkupAttribute, MarkupAttributeMarker, MarkupValue,
+        ObservedVariableStorage, OptionId, Result as YarnRuntimeResult, SeenLines, StringTable,
+        TextProvider, TextUnit, UnavailableOptionsPolicy, ValidatingVariableStorage,
+        VariableChange, VariableStorage,
     };
+    #[cfg(feature = "async")]
+    pub use crate::runtime::{CommandFuture, DialogueEventStream, OptionSelector};
 }
 
 pub mod core {
     //! Core types and traits that are used by both the compiler and runtime.
     pub use yarnspinner_core::prelude::{
         Header, Instruction, IntoYarnValueFromNonYarnValue, InvalidOpCodeError, Library, LineId,
-        Node, Position, Program, Type, UntypedYarnFn, YarnFn, YarnFnParam, YarnFnParamItem,
-        YarnValue, YarnValueCastError, YarnValueWrapper, YarnValueWrapperIter, optionality,
-        yarn_fn_type, yarn_library,
+        Node, Position, Program, Type, UntypedYarnFn, YarnFn, YarnFnParam, YarnFnParamError,
+        YarnFnParamItem, YarnValue, YarnValueCastError, YarnValueWrapper, YarnValueWrapperIter,
+        optionality, yarn_fn_type, yarn_library,
     };
 }
 pub mod compiler {
@@ -44,8 +49,9 @@ pub mod runtime {
     //! Types and traits used by the runtime, in particular the [`Dialogue`] struct.
     pub use yarnspinner_runtime::Result;
     pub use yarnspinner_runtime::markup::{
-        CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, MarkupAttribute, MarkupParseError,
-        MarkupValue, TRIM_WHITESPACE_PROPERTY,
+        AttributeMarkerProcessor, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+        MarkupAttribute, MarkupAttributeMarker, MarkupParseError, MarkupValue,
+        REPLACEMENT_MARKER_CONTENTS, TRIM_WHITESPACE_PROPERTY, TagType, TextUnit,
     };
     pub use yarnspinner_runtime::prelude::*;
 }
@@ -8,6 +8,7 @@ use bevy::prelude::World;
 use bevy_platform::collections::HashMap;
 use test_base::prelude::*;
 use yarnspinner::compiler::*;
+use yarnspinner::core::*;
 use yarnspinner::runtime::*;
 
 mod test_base;
@@ -336,11 +337,534 @@ fn test_selecting_option_from_inside_option_callback() {
                     let expected_step = test_plan.next_expected_step;
                     assert_eq!(ExpectedStepType::Stop, expected_step);
                 }
-                DialogueEvent::Command(_)
-                | DialogueEvent::NodeComplete(_)
-                | DialogueEvent::NodeStart(_)
-                | DialogueEvent::LineHints(_) => {}
+                _ => {}
             }
         }
     }
 }
+
+#[test]
+fn test_history_records_lines_and_chosen_options() {
+    let source = "<<declare $gold = 5>>\nAlice: I have {$gold} gold. #line:gold\n-> Buy #line:buy\n-> Leave #line:leave\nBob: Bye! #line:bye\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let string_table: HashMap<_, _> = result
+        .string_table
+        .iter()
+        .map(|(id, info)| (id.clone(), info.text.clone()))
+        .collect();
+
+    let mut test_base = TestBase::new().with_compilation(result);
+    test_base.dialogue.set_history(DialogueHistory::new(10));
+    test_base.dialogue.set_node("Start").unwrap();
+
+    #[cfg(feature = "bevy")]
+    let mut world = World::default();
+
+    while test_base.dialogue.can_continue() {
+        #[cfg(feature = "bevy")]
+        let events = test_base.dialogue.continue_with_world(&mut world);
+        #[cfg(not(feature = "bevy"))]
+        let events = test_base.dialogue.continue_();
+        let events =
+            events.unwrap_or_else(|e| panic!("Encountered error while running dialogue: {e}"));
+        if events
+            .iter()
+            .any(|event| matches!(event, DialogueEvent::Options(_)))
+        {
+            test_base.dialogue.set_selected_option(OptionId(1)).unwrap();
+        }
+    }
+
+    let entries: Vec<_> = test_base.dialogue.history().unwrap().entries().collect();
+    assert_eq!(
+        entries,
+        [
+            &HistoryEntry {
+                line_id: "line:gold".into(),
+                substitutions: vec![YarnValue::Number(5.0)],
+                character: Some("Alice".to_owned()),
+                selected_option: None,
+            },
+            &HistoryEntry {
+                line_id: "line:leave".into(),
+                substitutions: vec![],
+                character: None,
+                selected_option: Some(OptionId(1)),
+            },
+            &HistoryEntry {
+                line_id: "line:bye".into(),
+                substitutions: vec![],
+                character: Some("Bob".to_owned()),
+                selected_option: None,
+            },
+        ]
+    );
+
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(string_table);
+    text_provider.extend_translation(
+        "de",
+        [
+            (
+                LineId::from("line:gold"),
+                "Alice: Ich habe {0} Gold.".to_owned(),
+            ),
+            (LineId::from("line:leave"), "Gehen".to_owned()),
+            (LineId::from("line:bye"), "Bob: Tschüss!".to_owned()),
+        ],
+    );
+    test_base.string_table.replace(text_provider);
+    test_base.dialogue.set_language_code(Language::from("de"));
+
+    let resolved: Vec<_> = test_base
+        .dialogue
+        .resolve_history()
+        .unwrap()
+        .into_iter()
+        .map(|entry| {
+            (
+                entry.line.character_name().map(ToOwned::to_owned),
+                entry.line.text_without_character_name(),
+                entry.selected_option,
+            )
+        })
+        .collect();
+    assert_eq!(
+        resolved,
+        [
+            (
+                Some("Alice".to_owned()),
+                "Ich habe 5 Gold.".to_owned(),
+                None
+            ),
+            (None, "Gehen".to_owned(), Some(OptionId(1))),
+            (Some("Bob".to_owned()), "Tschüss!".to_owned(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_rollback_restores_variables_visit_counts_and_options() {
+    let source = "title: Start\n---\n<<declare $gold = 5>>\n-> Buy #line:buy\n    <<set $gold to $gold - 3>>\n    <<shake>>\n    <<jump Shop>>\n-> Leave #line:leave\n===\ntitle: Shop\n---\nClerk: Visits: {visited_count(\"Shop\")} #line:shop\n-> Again #line:again\n    <<jump Start>>\n-> Done #line:done\n===\n";
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "<input>".to_string(),
+            source: source.to_string(),
+        })
+        .compile()
+        .unwrap();
+    let mut test_base = TestBase::new().with_compilation(result);
+    test_base.dialogue.set_rollback_depth(5);
+    test_base.dialogue.set_node("Start").unwrap();
+
+    let continue_until_options = |dialogue: &mut Dialogue| loop {
+        #[cfg(feature = "bevy")]
+        let events = dialogue.continue_with_world(&mut World::default());
+        #[cfg(not(feature = "bevy"))]
+        let events = dialogue.continue_();
+        let events =
+            events.unwrap_or_else(|e| panic!("Encountered error while running dialogue: {e}"));
+        if let Some(DialogueEvent::Options(options)) = events.last() {
+            break options.clone();
+        }
+    };
+    let gold = |dialogue: &Dialogue| dialogue.variable_storage().get("$gold").unwrap();
+    let shop_visits = |dialogue: &Dialogue| {
+        let name = Library::generate_unique_visited_variable_for_node("Shop");
+        match dialogue.variable_storage().get(&name) {
+            Ok(YarnValue::Number(count)) => count,
+            _ => 0.0,
+        }
+    };
+
+    let first_options = continue_until_options(&mut test_base.dialogue);
+    assert_eq!(test_base.dialogue.available_rollbacks(), 0);
+    test_base.dialogue.set_selected_option(OptionId(0)).unwrap();
+    continue_until_options(&mut test_base.dialogue);
+    test_base.dialogue.set_selected_option(OptionId(0)).unwrap();
+    continue_until_options(&mut test_base.dialogue);
+
+    assert_eq!(gold(&test_base.dialogue), YarnValue::Number(2.0));
+    assert_eq!(shop_visits(&test_base.dialogue), 1.0);
+    assert_eq!(test_base.dialogue.available_rollbacks(), 2);
+
+    let events = test_base.dialogue.rollback(2).unwrap();
+    assert_eq!(
+        events,
+        [
+            DialogueEvent::Rollback(Rollback {
+                steps: 2,
+                node_name: "Start".to_owned(),
+                undone_commands: vec![Command {
+                    name: "shake".to_owned(),
+                    parameters: vec![],
+                    raw: "shake".to_owned(),
+                }],
+            }),
+            DialogueEvent::Options(first_options),
+        ]
+    );
+    assert_eq!(gold(&test_base.dialogue), YarnValue::Number(5.0));
+    assert_eq!(shop_visits(&test_base.dialogue), 0.0);
+    assert!(test_base.dialogue.is_waiting_for_option_selection());
+    assert_eq!(test_base.dialogue.available_rollbacks(), 0);
+    assert!(test_base.dialogue.rollback(1).is_err());
+
+    test_base.dialogue.set_selected_option(OptionId(1)).unwrap();
+    assert_eq!(test_base.dialogue.available_rollbacks(), 1);
+}
+
+#[test]
+fn test_rollback_to_line_with_substitutions_continues_after_it() {
+    let source = "<<declare $x = 1>>\nThe value is {$x}. #line:before\n<<set $x to 2>>\nNow it is {$x}. #line:after\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut test_base = TestBase::new().with_compilation(result);
+    test_base.dialogue.set_rollback_depth(5);
+    test_base.dialogue.set_checkpoints_on_lines(true);
+    test_base.dialogue.set_node("Start").unwrap();
+
+    let next_line = |dialogue: &mut Dialogue| loop {
+        #[cfg(feature = "bevy")]
+        let events = dialogue.continue_with_world(&mut World::default());
+        #[cfg(not(feature = "bevy"))]
+        let events = dialogue.continue_();
+        let events =
+            events.unwrap_or_else(|e| panic!("Encountered error while running dialogue: {e}"));
+        if let Some(line) = events.into_iter().find_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line),
+            _ => None,
+        }) {
+            break line;
+        }
+    };
+
+    let before = next_line(&mut test_base.dialogue);
+    assert_eq!(before.text, "The value is 1.");
+    assert_eq!(next_line(&mut test_base.dialogue).text, "Now it is 2.");
+    assert_eq!(test_base.dialogue.available_rollbacks(), 1);
+
+    let events = test_base.dialogue.rollback(1).unwrap();
+    assert_eq!(events.last(), Some(&DialogueEvent::Line(before)));
+    assert_eq!(
+        test_base.dialogue.variable_storage().get("$x").unwrap(),
+        YarnValue::Number(1.0)
+    );
+
+    // The line is not delivered again, the dialogue continues right after it
+    let after = next_line(&mut test_base.dialogue);
+    assert_eq!(after.id, LineId::from("line:after"));
+    assert_eq!(after.text, "Now it is 2.");
+}
+
+#[test]
+fn test_seen_lines_are_reported_when_delivered_again() {
+    let source = "<<declare $rich = false>>\nAlice: Welcome! #line:welcome\n-> Browse #line:browse\n-> Buy everything <<if $rich>> #line:buy\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut test_base = TestBase::new().with_compilation(result);
+
+    let run_start = |dialogue: &mut Dialogue| {
+        dialogue.set_node("Start").unwrap();
+        let mut seen = Vec::new();
+        while dialogue.can_continue() {
+            #[cfg(feature = "bevy")]
+            let events = dialogue.continue_with_world(&mut World::default());
+            #[cfg(not(feature = "bevy"))]
+            let events = dialogue.continue_();
+            let events =
+                events.unwrap_or_else(|e| panic!("Encountered error while running dialogue: {e}"));
+            for event in events {
+                match event {
+                    DialogueEvent::Line(line) => seen.push((line.id, line.is_seen)),
+                    DialogueEvent::Options(options) => {
+                        seen.extend(
+                            options
+                                .iter()
+                                .map(|option| (option.line.id.clone(), option.is_seen())),
+                        );
+                        dialogue.set_selected_option(OptionId(0)).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        seen
+    };
+
+    assert_eq!(
+        run_start(&mut test_base.dialogue),
+        [
+            (LineId::from("line:welcome"), false),
+            (LineId::from("line:browse"), false),
+            (LineId::from("line:buy"), false),
+        ]
+    );
+    assert_eq!(
+        run_start(&mut test_base.dialogue),
+        [
+            (LineId::from("line:welcome"), true),
+            (LineId::from("line:browse"), true),
+            (LineId::from("line:buy"), false),
+        ]
+    );
+
+    let seen_lines = test_base.dialogue.set_seen_lines(SeenLines::new());
+    assert_eq!(seen_lines.len(), 2);
+    assert!(!seen_lines.contains(&LineId::from("line:buy")));
+    assert_eq!(
+        run_start(&mut test_base.dialogue)[0],
+        (LineId::from("line:welcome"), false)
+    );
+}
+
+#[test]
+fn test_unavailable_options_fall_through_or_error_depending_on_policy() {
+    let source = "<<declare $rich = false>>\n-> Buy the sword <<if $rich>> #line:sword\n    Shopkeeper: Thank you! #line:thanks\n-> Buy the shield <<if $rich>> #line:shield\nShopkeeper: Come again. #line:bye\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut test_base = TestBase::new().with_compilation(result);
+    let dialogue = &mut test_base.dialogue;
+
+    let run_start = |dialogue: &mut Dialogue| -> yarnspinner::runtime::Result<Vec<DialogueEvent>> {
+        dialogue.set_node("Start").unwrap();
+        let mut delivered = Vec::new();
+        while dialogue.can_continue() {
+            #[cfg(feature = "bevy")]
+            let events = dialogue.continue_with_world(&mut World::default())?;
+            #[cfg(not(feature = "bevy"))]
+            let events = dialogue.continue_()?;
+            let is_waiting_for_option = events
+                .iter()
+                .any(|event| matches!(event, DialogueEvent::Options(_)));
+            delivered.extend(events);
+            if is_waiting_for_option {
+                break;
+            }
+        }
+        Ok(delivered)
+    };
+
+    assert_eq!(
+        dialogue.unavailable_options_policy(),
+        UnavailableOptionsPolicy::Deliver
+    );
+    let events = run_start(dialogue).unwrap();
+    let Some(DialogueEvent::Options(options)) = events.last() else {
+        panic!("Expected options, got {events:?}");
+    };
+    assert!(options.iter().all(|option| !option.is_available));
+
+    dialogue.stop();
+    dialogue.set_unavailable_options_policy(UnavailableOptionsPolicy::Fallthrough);
+    let line_ids: Vec<_> = run_start(dialogue)
+        .unwrap()
+        .into_iter()
+        .filter_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line.id),
+            DialogueEvent::Options(options) => panic!("Unexpected options: {options:?}"),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(line_ids, [LineId::from("line:bye")]);
+
+    dialogue.stop();
+    dialogue.set_unavailable_options_policy(UnavailableOptionsPolicy::Error);
+    let Err(DialogueError::NoAvailableOptionsError { line_ids }) = run_start(dialogue) else {
+        panic!("Expected an error because no option is available");
+    };
+    assert_eq!(
+        line_ids,
+        [LineId::from("line:sword"), LineId::from("line:shield")]
+    );
+}
+
+#[test]
+fn test_observed_variable_storage_reports_changes_in_order() {
+    let source =
+        "<<declare $gold = 0>>\n<<set $gold to 10>>\n<<shop>>\n<<set $gold to $gold + 5>>\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = Dialogue::new(
+        Box::new(ObservedVariableStorage::new(Box::new(
+            MemoryVariableStorage::new(),
+        ))),
+        Box::new(StringTableTextProvider::new()),
+    );
+    dialogue.replace_program(result.program.unwrap());
+    dialogue.set_node("Start").unwrap();
+
+    let continue_ = |dialogue: &mut Dialogue| {
+        #[cfg(feature = "bevy")]
+        let events = dialogue.continue_with_world(&mut World::default());
+        #[cfg(not(feature = "bevy"))]
+        let events = dialogue.continue_();
+        events
+            .unwrap()
+            .into_iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    DialogueEvent::VariableChanged(_) | DialogueEvent::Command(_)
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let change = |old_value: Option<f32>, new_value: f32| {
+        DialogueEvent::VariableChanged(VariableChange {
+            name: "$gold".to_owned(),
+            old_value: old_value.map(Into::into),
+            new_value: Some(new_value.into()),
+        })
+    };
+
+    let events = continue_(&mut dialogue);
+    let Some((DialogueEvent::Command(command), changes)) = events.split_last() else {
+        panic!("Expected the changes to be followed by a command, got {events:?}");
+    };
+    assert_eq!(command.name, "shop");
+    assert_eq!(changes, [change(None, 0.0), change(Some(0.0), 10.0)]);
+
+    // Changes made by the host are delivered with the next batch of events
+    dialogue
+        .variable_storage_mut()
+        .set("$gold".to_owned(), 100.0.into())
+        .unwrap();
+    assert_eq!(
+        continue_(&mut dialogue),
+        [change(Some(10.0), 100.0), change(Some(100.0), 105.0)]
+    );
+}
+
+#[test]
+fn test_events_are_yielded_one_by_one() {
+    let source = "Alice: Hi! #line:hi\n<<wave>>\n-> Wave back #line:wave\n-> Leave #line:leave\n    Alice: Bye! #line:bye\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let mut line_ids = Vec::new();
+    let mut events = dialogue.events();
+    while let Some(event) = events.next() {
+        match event.unwrap() {
+            DialogueEvent::Line(line) => line_ids.push(line.id),
+            DialogueEvent::Command(command) => assert_eq!(command.name, "wave"),
+            DialogueEvent::Options(options) => events.select_option(options[1].id).unwrap(),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        line_ids,
+        [LineId::from("line:hi"), LineId::from("line:bye")]
+    );
+    assert!(!dialogue.is_active());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn test_event_stream_waits_for_commands_and_option_selection() {
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use futures_core::Stream;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Completes once the test set the flag, standing in for e.g. a timer of an async runtime.
+    struct Flag(Arc<AtomicBool>);
+
+    impl Future for Flag {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            if self.0.load(Ordering::SeqCst) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    let source =
+        "<<wait 1>>\n-> Stay #line:stay\n-> Leave #line:leave\n    Alice: Bye! #line:bye\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let is_waiting_done = Arc::new(AtomicBool::new(false));
+    let flag = is_waiting_done.clone();
+    let mut stream = DialogueEventStream::new(&mut dialogue).with_command_handler(move |command| {
+        (command.name == "wait").then(|| Box::pin(Flag(flag.clone())) as CommandFuture)
+    });
+    let option_selector = stream.option_selector();
+    let mut context = Context::from_waker(Waker::noop());
+    let mut poll_next = || match Pin::new(&mut stream).poll_next(&mut context) {
+        Poll::Ready(Some(event)) => Some(event),
+        Poll::Ready(None) => panic!("Stream ended unexpectedly"),
+        Poll::Pending => None,
+    };
+
+    let mut next_event = || loop {
+        match poll_next() {
+            Some(Ok(DialogueEvent::NodeStart(_) | DialogueEvent::LineHints(_))) => continue,
+            Some(event) => return Some(event),
+            None => return None,
+        }
+    };
+    let Some(Ok(DialogueEvent::Command(command))) = next_event() else {
+        panic!("Expected the command first");
+    };
+    assert_eq!(command.name, "wait");
+    assert!(next_event().is_none());
+
+    is_waiting_done.store(true, Ordering::SeqCst);
+    let Some(Ok(DialogueEvent::Options(options))) = next_event() else {
+        panic!("Expected options once the command completed");
+    };
+    assert!(next_event().is_none());
+
+    option_selector.select(OptionId(options.len()));
+    assert!(matches!(
+        next_event(),
+        Some(Err(DialogueError::InvalidOptionIdError { .. }))
+    ));
+    assert!(next_event().is_none());
+
+    option_selector.select(options[1].id);
+    let Some(Ok(DialogueEvent::Line(line))) = next_event() else {
+        panic!("Expected the line of the selected option");
+    };
+    assert_eq!(line.id, LineId::from("line:bye"));
+}
+
+#[test]
+fn test_commands_are_dispatched_through_registry() {
+    use std::sync::{Arc, Mutex};
+
+    let source = "<<give gold 10>>\n<<give sword>>\n<<wave>>\n";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let mut dialogue = TestBase::new().with_compilation(result).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let inventory = Arc::new(Mutex::new(Vec::new()));
+    let mut commands = CommandRegistry::new();
+    let items = inventory.clone();
+    commands.add_command("give", move |item: String, amount: Option<usize>| {
+        items.lock().unwrap().push((item, amount.unwrap_or(1)));
+    });
+
+    let mut errors = Vec::new();
+    for event in dialogue.events() {
+        if let DialogueEvent::Command(command) = event.unwrap() {
+            match commands.dispatch(&command) {
+                Ok(completion) => assert!(completion.is_complete()),
+                Err(error) => errors.push(error),
+            }
+        }
+    }
+    assert_eq!(
+        *inventory.lock().unwrap(),
+        [("gold".to_owned(), 10), ("sword".to_owned(), 1)]
+    );
+    assert_eq!(
+        errors,
+        [CommandError::UnknownCommand {
+            name: "wave".to_owned()
+        }]
+    );
+}
@@ -3,6 +3,7 @@
 use crate::test_base::*;
 use test_base::prelude::*;
 use yarnspinner::compiler::*;
+use yarnspinner::core::Position;
 
 mod test_base;
 
@@ -113,3 +114,126 @@ fn test_compiling_same_file_twice_fails() {
             .any(|d| d.message.contains("Duplicate line ID line:794945"))
     );
 }
+
+#[test]
+fn test_diagnostics_have_codes() {
+    let result = Compiler::from_test_source("<<if someFunction(>><<endif>>")
+        .compile()
+        .unwrap_err();
+
+    assert!(
+        result
+            .0
+            .iter()
+            .all(|d| d.code == Some(DiagnosticCode::SyntaxError))
+    );
+}
+
+#[test]
+fn test_duplicate_line_id_points_to_first_usage() {
+    let result = Compiler::from_test_source("One #line:abc\nTwo #line:abc")
+        .compile()
+        .unwrap_err();
+
+    let diagnostic = result
+        .0
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::DuplicateLineId))
+        .unwrap();
+    assert_eq!(1, diagnostic.related_locations.len());
+    assert_eq!(
+        diagnostic.file_name,
+        diagnostic.related_locations[0].file_name
+    );
+}
+
+#[test]
+fn test_undeclared_variable_suggests_declared_one() {
+    let result = Compiler::from_test_source("<<declare $gold = 10>>\nYou have {$gol} coins.")
+        .compile()
+        .unwrap_err();
+
+    let diagnostic = result
+        .0
+        .iter()
+        .find(|d| d.code == Some(DiagnosticCode::UndeterminedVariableType))
+        .unwrap();
+    assert_eq!(1, diagnostic.suggestions.len());
+    let suggestion = &diagnostic.suggestions[0];
+    assert_eq!("$gold", suggestion.replacement);
+    assert_eq!("Did you mean `$gold`?", suggestion.message);
+    assert_eq!(diagnostic.range.as_ref(), Some(&suggestion.range));
+}
+
+#[test]
+fn test_jump_to_unknown_node_warns_with_suggestion() {
+    let compilation = Compiler::new()
+        .add_file(File {
+            file_name: "test.yarn".to_owned(),
+            source: "title: Start\n---\n<<jump Ending>>\n===\ntitle: Endings\n---\n===\n"
+                .to_owned(),
+        })
+        .compile()
+        .unwrap();
+
+    assert_eq!(1, compilation.warnings.len());
+    let warning = &compilation.warnings[0];
+    assert_eq!(Some(DiagnosticCode::UnknownNode), warning.code);
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+    assert_eq!("Endings", warning.suggestions[0].replacement);
+    assert_eq!(
+        Position {
+            line: 2,
+            character: 7
+        }..Position {
+            line: 2,
+            character: 13
+        },
+        warning.suggestions[0].range
+    );
+}
+
+#[test]
+fn test_option_group_without_unconditional_option_warns() {
+    let compilation = Compiler::from_test_source(
+        "<<declare $rich = false>>\n-> Buy the sword <<if $rich>>\n-> Buy the shield <<if $rich>>\n",
+    )
+    .compile()
+    .unwrap();
+
+    assert_eq!(1, compilation.warnings.len());
+    let warning = &compilation.warnings[0];
+    assert_eq!(Some(DiagnosticCode::NoUnconditionalOption), warning.code);
+    assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+
+    let compilation = Compiler::from_test_source(
+        "<<declare $rich = false>>\n-> Buy the sword <<if $rich>>\n-> Leave\n",
+    )
+    .compile()
+    .unwrap();
+    assert!(compilation.warnings.is_empty());
+}
+
+#[test]
+fn test_renders_diagnostics_with_source() {
+    let file = File {
+        file_name: "test.yarn".to_owned(),
+        source: "title: Start\n---\n<<declare $gold = 10>>\nYou have {$gol} coins.\n===\n"
+            .to_owned(),
+    };
+    let result = Compiler::new()
+        .add_file(file.clone())
+        .compile()
+        .unwrap_err();
+
+    let rendered = DiagnosticRenderer::new()
+        .with_source(&file)
+        .with_color_choice(ColorChoice::Never)
+        .render_all(&result.0);
+
+    assert!(rendered.contains("error[YS0015]"));
+    assert!(rendered.contains("--> test.yarn:4:"));
+    assert!(rendered.contains("You have {$gol} coins."));
+    assert!(rendered.contains("help: Did you mean `$gold`?"));
+    assert!(!rendered.contains('\u{1b}'));
+}
@@ -177,6 +177,16 @@ fn test_invalid_characters_in_node_title() {
 
 #[test]
 fn test_sources() {
+    run_test_sources(false);
+}
+
+/// The optimized programs must behave exactly like the unoptimized ones, so they have to pass the same test plans.
+#[test]
+fn test_sources_with_optimizations() {
+    run_test_sources(true);
+}
+
+fn run_test_sources(optimize: bool) {
     for file in [
         "TestCases",
         "TestCases/ParseFailures",
@@ -196,6 +206,7 @@ fn test_sources() {
         let result = Compiler::default()
             .read_file(&path)
             .extend_library(test_base.dialogue.library().clone())
+            .with_optimizations(optimize)
             .compile();
 
         if !test_plan.exists() {
@@ -229,6 +240,49 @@ fn test_sources() {
     }
 }
 
+#[test]
+fn formatting_test_sources_preserves_their_programs() {
+    for file in TestBase::file_sources("TestCases") {
+        let path = test_data_path().join(&file);
+        if !path.with_extension("testplan").exists() {
+            continue;
+        }
+        println!("INFO: Formatting file {}", file.display());
+        let original = File {
+            file_name: file.display().to_string(),
+            source: std::fs::read_to_string(&path).unwrap(),
+        };
+        let formatted = File {
+            source: format_file(&original).unwrap(),
+            ..original.clone()
+        };
+        assert!(is_formatted(&formatted).unwrap());
+
+        let test_base = TestBase::default().extend_library(|library| {
+            library.add_function("add_three_operands", |a: i32, b: i32, c: i32| a + b + c);
+        });
+        let compile = |file: File| {
+            Compiler::default()
+                .add_file(file)
+                .extend_library(test_base.dialogue.library().clone())
+                .compile()
+                .unwrap()
+                .program
+                .unwrap()
+        };
+        let original_program = compile(original);
+        let formatted_program = compile(formatted);
+        for (name, node) in &original_program.nodes {
+            assert_eq!(
+                node.instructions,
+                formatted_program.nodes[name].instructions,
+                "Formatting changed node {name} of {}",
+                file.display()
+            );
+        }
+    }
+}
+
 #[test]
 #[should_panic]
 fn crashes_on_command_expression_evaluating_whitespace() {
@@ -272,6 +272,43 @@ before 🧑🏾‍❤️‍💋‍🧑🏻after #line:abc130 // with a comment
     assert_eq!(visited_ids.len(), compilation.string_table.len());
 }
 
+#[test]
+fn test_line_tags_are_added_to_lines_with_format_specifiers() {
+    let original_text = "title: Start
+---
+<<declare $gold = 0>>
+You have {$gold:N0} coins.
+You have {$gold : N2}
+===";
+
+    let output = Compiler::add_tags_to_lines(original_text, Vec::new())
+        .unwrap()
+        .unwrap();
+
+    let line_tag_regex =
+        Regex::new(r"^You have \{\$gold ?: ?N[02]\}( coins\.)? #line:\w+ ?$").unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert!(line_tag_regex.is_match(lines[3]), "{output}");
+    assert!(line_tag_regex.is_match(lines[4]), "{output}");
+
+    let file = File {
+        file_name: "input".to_string(),
+        source: output,
+    };
+    let compilation = Compiler::new()
+        .add_file(file)
+        .with_compilation_type(CompilationType::StringsOnly)
+        .compile()
+        .unwrap();
+    let mut texts: Vec<_> = compilation
+        .string_table
+        .values()
+        .map(|string_info| string_info.text.as_str())
+        .collect();
+    texts.sort();
+    assert_eq!(texts, ["You have {0:N0} coins.", "You have {0:N2}"]);
+}
+
 #[test]
 fn test_debug_output_is_produced() {
     let file = File {
@@ -251,6 +251,9 @@ impl TestBase {
                     DialogueEvent::NodeComplete(_) => {}
                     DialogueEvent::NodeStart(_) => {}
                     DialogueEvent::LineHints(_) => {}
+                    DialogueEvent::Rollback(_) => {}
+                    DialogueEvent::VariableChanged(_) => {}
+                    _ => {}
                     DialogueEvent::DialogueComplete => {
                         let Some(test_plan) = self.test_plan.as_mut() else {
                             continue;
@@ -485,6 +485,52 @@ fn test_variable_storage_extended_with_program_initial_values() {
     );
 }
 
+#[test]
+fn test_validating_variable_storage_uses_compiled_declarations() {
+    let source = "
+        <<declare $gold = 10>>
+        <<set $gold to $gold + 1>>
+        <<if visited(\"Start\")>>
+        <<endif>>
+    ";
+    let result = Compiler::from_test_source(source).compile().unwrap();
+    let storage = ValidatingVariableStorage::new(
+        Box::new(MemoryVariableStorage::new()),
+        result.declarations.iter().map(|declaration| {
+            (
+                declaration.name.clone(),
+                declaration.r#type.clone(),
+                declaration.default_value.clone(),
+            )
+        }),
+    );
+    assert_eq!(storage.get("$gold").unwrap(), YarnValue::Number(10.0));
+
+    let mut dialogue = Dialogue::new(Box::new(storage), Box::new(StringTableTextProvider::new()));
+    dialogue.replace_program(result.program.unwrap());
+    dialogue.set_node("Start").unwrap();
+    #[cfg(feature = "bevy")]
+    dialogue
+        .continue_with_world(&mut bevy::prelude::World::default())
+        .unwrap();
+    #[cfg(not(feature = "bevy"))]
+    dialogue.continue_().unwrap();
+    assert_eq!(
+        dialogue.variable_storage().get("$gold").unwrap(),
+        YarnValue::Number(11.0)
+    );
+
+    let variable_storage = dialogue.variable_storage_mut();
+    assert!(matches!(
+        variable_storage.set("$gold".to_owned(), "plenty".into()),
+        Err(VariableStorageError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        variable_storage.set("$silver".to_owned(), 1.0.into()),
+        Err(VariableStorageError::UndeclaredVariable { .. })
+    ));
+}
+
 #[test]
 fn test_explicit_types() {
     let result = Compiler::from_test_source(